@@ -0,0 +1,153 @@
+use crate::{
+    analysis::chi_squared,
+    shift::{letter_index, letter_pairs},
+    Cipher,
+    CipherError,
+};
+use std::fmt;
+
+/// The affine cipher, which maps each letter `x` to `a * x + b` mod 26.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Affine;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AffineKey {
+    pub a: u8,
+    pub b: u8,
+}
+
+impl fmt::Display for AffineKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{},{}", self.a, self.b)
+    }
+}
+
+/// Parses a key written as `A,B`, the way keys are displayed.
+pub fn parse_key(text: &str) -> Result<AffineKey, CipherError> {
+    let mut parts = text.split(',').map(|part| part.trim().parse().ok());
+    match (parts.next().flatten(), parts.next().flatten(), parts.next()) {
+        (Some(a), Some(b), None) => Ok(AffineKey { a, b }),
+        _ => Err(CipherError::InvalidKey(String::from("must be two numbers, A,B"))),
+    }
+}
+
+/// Every `a` that has an inverse mod 26, and so can be used in a key.
+pub const VALID_A: [u8; 12] = [1, 3, 5, 7, 9, 11, 15, 17, 19, 21, 23, 25];
+
+/// Returns the `x` such that `a * x` is 1 mod 26, if there is one. There is
+/// only an inverse if `a` is coprime to 26, that is odd and not 13.
+pub fn mod_inverse(a: u8) -> Option<u8> {
+    (1..26).find(|&x| (a as u32 * x as u32) % 26 == 1)
+}
+
+/// Applies `x -> a * x + b` to each letter, preserving case.
+fn map_letters(text: &str, a: u32, b: u32) -> String {
+    text.chars()
+        .map(|c| match letter_index(c) {
+            Some(x) => {
+                let base = if c.is_ascii_uppercase() { 65 } else { 97 };
+                std::char::from_u32((a * x + b) % 26 + base).unwrap()
+            },
+            None => c,
+        })
+        .collect()
+}
+
+/// The inverse of `key.a`, which only exists for a valid key.
+fn key_inverse(key: AffineKey) -> Result<u8, CipherError> {
+    mod_inverse(key.a)
+        .ok_or_else(|| CipherError::InvalidKey(String::from("A must be coprime to 26, so odd and not 13")))
+}
+
+pub fn encrypt(plaintext: &str, key: AffineKey) -> Result<String, CipherError> {
+    key_inverse(key)?;
+    Ok(map_letters(plaintext, key.a as u32 % 26, key.b as u32 % 26))
+}
+
+pub fn decrypt(ciphertext: &str, key: AffineKey) -> Result<String, CipherError> {
+    // x = a^-1 * (y - b) = a^-1 * y + a^-1 * (26 - b)
+    let inverse = key_inverse(key)? as u32;
+    let b = inverse * (26 - key.b as u32 % 26) % 26;
+    Ok(map_letters(ciphertext, inverse, b))
+}
+
+/// Solves for the key that encrypts each plaintext letter to its ciphertext
+/// letter. Two pairs are enough, as long as the difference between the
+/// plaintext letters is coprime to 26.
+pub fn key_from_pairs(first: (char, char), second: (char, char)) -> Option<AffineKey> {
+    let (p1, c1) = (letter_index(first.0)?, letter_index(first.1)?);
+    let (p2, c2) = (letter_index(second.0)?, letter_index(second.1)?);
+    // c1 - c2 = a * (p1 - p2)
+    let inverse = mod_inverse(((p1 + 26 - p2) % 26) as u8)? as u32;
+    let a = (c1 + 26 - c2) * inverse % 26;
+    let b = (c1 + 26 * 26 - a * p1) % 26;
+    Some(AffineKey {
+        a: a as u8,
+        b: b as u8,
+    })
+}
+
+pub fn find_key(plaintext: &str, ciphertext: &str) -> Result<AffineKey, CipherError> {
+    let pairs = letter_pairs(plaintext, ciphertext)?;
+    let key = pairs
+        .iter()
+        .enumerate()
+        .find_map(|(i, &(_, p1, c1))| {
+            pairs[i + 1..].iter().find_map(|&(_, p2, c2)| key_from_pairs((p1, c1), (p2, c2)))
+        })
+        .ok_or(CipherError::NoKey)?;
+    match pairs
+        .iter()
+        .find(|&&(_, plain, cipher)| encrypt(&plain.to_string(), key).unwrap() != cipher.to_string())
+    {
+        Some(&(index, _, _)) => Err(CipherError::InconsistentKey(index)),
+        None => Ok(key),
+    }
+}
+
+/// A possible decryption of a ciphertext, as found by [`crack`].
+#[derive(Clone, Debug)]
+pub struct Candidate {
+    pub key: AffineKey,
+    pub plaintext: String,
+    /// The chi-squared score of the plaintext against English; lower is better.
+    pub score: f64,
+}
+
+/// Decrypts `ciphertext` with all 312 keys, most English-looking first.
+pub fn crack(ciphertext: &str) -> Vec<Candidate> {
+    let mut candidates = VALID_A
+        .iter()
+        .flat_map(|&a| (0..26).map(move |b| AffineKey { a, b }))
+        .map(|key| {
+            let plaintext = decrypt(ciphertext, key).unwrap();
+            Candidate {
+                key,
+                score: chi_squared(&plaintext),
+                plaintext,
+            }
+        })
+        .collect::<Vec<_>>();
+    candidates.sort_by(|a, b| a.score.partial_cmp(&b.score).unwrap());
+    candidates
+}
+
+impl Cipher for Affine {
+    type Key = AffineKey;
+
+    fn is_valid_key(&self, key: &AffineKey) -> bool {
+        mod_inverse(key.a).is_some()
+    }
+
+    fn encrypt(&self, plaintext: &str, key: &AffineKey) -> Result<String, CipherError> {
+        encrypt(plaintext, *key)
+    }
+
+    fn decrypt(&self, ciphertext: &str, key: &AffineKey) -> Result<String, CipherError> {
+        decrypt(ciphertext, *key)
+    }
+
+    fn find_key(&self, plaintext: &str, ciphertext: &str) -> Result<AffineKey, CipherError> {
+        find_key(plaintext, ciphertext)
+    }
+}
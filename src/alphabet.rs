@@ -0,0 +1,139 @@
+/// The letters a cipher works with, in order. Shifts wrap around after the
+/// last one.
+///
+/// Letters are matched exactly, or by their lowercase form if the alphabet
+/// doesn't have them as they are, in which case the output is uppercase too.
+/// So a lowercase alphabet keeps the case of the text, while one with both
+/// cases treats them as different letters.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Alphabet {
+    letters: Vec<char>,
+}
+
+/// A named alphabet to start from.
+pub struct Preset {
+    pub name: &'static str,
+    pub letters: &'static str,
+}
+
+pub const PRESETS: [Preset; 6] = [
+    Preset { name: "English", letters: "abcdefghijklmnopqrstuvwxyz" },
+    Preset { name: "German", letters: "abcdefghijklmnopqrstuvwxyzäöüß" },
+    Preset { name: "Spanish", letters: "abcdefghijklmnñopqrstuvwxyz" },
+    Preset { name: "Russian", letters: "абвгдеёжзийклмнопрстуфхцчшщъыьэюя" },
+    Preset { name: "Greek", letters: "αβγδεζηθικλμνξοπρστυφχψω" },
+    Preset { name: "English and digits", letters: "abcdefghijklmnopqrstuvwxyz0123456789" },
+];
+
+/// Why `letters` can't be used as an alphabet, in words. Whitespace is ignored.
+pub fn error(letters: &str) -> Option<String> {
+    let letters = letters.chars().filter(|c| !c.is_whitespace()).collect::<Vec<_>>();
+    if letters.is_empty() {
+        return Some(String::from("the alphabet is empty"));
+    }
+    for (i, c) in letters.iter().enumerate() {
+        if letters[..i].contains(c) {
+            return Some(format!("{} is in the alphabet twice", c));
+        }
+    }
+    None
+}
+
+impl Alphabet {
+    /// Returns `None` if `letters` is empty or has a letter in it twice.
+    /// Whitespace is ignored.
+    pub fn new(letters: &str) -> Option<Self> {
+        if error(letters).is_some() {
+            return None;
+        }
+        Some(Alphabet {
+            letters: letters.chars().filter(|c| !c.is_whitespace()).collect(),
+        })
+    }
+
+    /// The alphabet with the given name in [`PRESETS`], ignoring case.
+    pub fn preset(name: &str) -> Option<Self> {
+        let preset = PRESETS.iter().find(|preset| preset.name.eq_ignore_ascii_case(name))?;
+        Alphabet::new(preset.letters)
+    }
+
+    /// The preset called `text`, or failing that an alphabet of its letters.
+    /// The error says why the letters can't be an alphabet.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        match Alphabet::preset(text).or_else(|| Alphabet::new(text)) {
+            Some(alphabet) => Ok(alphabet),
+            None => Err(error(text).unwrap()),
+        }
+    }
+
+    pub fn english() -> Self {
+        Alphabet::new(PRESETS[0].letters).unwrap()
+    }
+
+    pub fn len(&self) -> u32 {
+        self.letters.len() as u32
+    }
+
+    /// Always false, since an alphabet can't be empty.
+    pub fn is_empty(&self) -> bool {
+        self.letters.is_empty()
+    }
+
+    pub fn letters(&self) -> &[char] {
+        &self.letters
+    }
+
+    /// Returns the position of `c` in the alphabet, and whether it was only
+    /// found by its lowercase form.
+    pub fn find(&self, c: char) -> Option<(u32, bool)> {
+        if let Some(i) = self.letters.iter().position(|&x| x == c) {
+            return Some((i as u32, false));
+        }
+        match single(c.to_lowercase()) {
+            Some(lower) if lower != c => {
+                let i = self.letters.iter().position(|&x| x == lower)?;
+                Some((i as u32, true))
+            },
+            _ => None,
+        }
+    }
+
+    /// Returns the position of `c` in the alphabet, ignoring case.
+    pub fn index(&self, c: char) -> Option<u32> {
+        self.find(c).map(|(i, _)| i)
+    }
+
+    /// Returns the letter at `index`, uppercased if `upper` is set and it has
+    /// a single uppercase form.
+    pub fn letter(&self, index: u32, upper: bool) -> char {
+        let c = self.letters[(index % self.len()) as usize];
+        if !upper {
+            return c;
+        }
+        single(c.to_uppercase()).unwrap_or(c)
+    }
+
+    /// Shifts `c` `count` places along the alphabet, keeping its case.
+    /// Anything that isn't in the alphabet is returned unchanged.
+    pub fn shift(&self, c: char, count: u32) -> char {
+        match self.find(c) {
+            Some((i, upper)) => self.letter(i + count % self.len(), upper),
+            None => c,
+        }
+    }
+}
+
+/// The only character in `chars`, if there is exactly one. Changing case can
+/// turn one character into several, like ß into SS.
+pub(crate) fn single(mut chars: impl Iterator<Item = char>) -> Option<char> {
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Some(c),
+        _ => None,
+    }
+}
+
+impl Default for Alphabet {
+    fn default() -> Self {
+        Alphabet::english()
+    }
+}
@@ -0,0 +1,185 @@
+use crate::shift::letter_index;
+use std::{collections::HashMap, sync::OnceLock};
+
+/// How often each letter from A to Z appears in typical English text.
+pub const ENGLISH_FREQUENCIES: [f64; 26] = [
+    0.08167, 0.01492, 0.02782, 0.04253, 0.12702, 0.02228, 0.02015, 0.06094, 0.06966, 0.00153,
+    0.00772, 0.04025, 0.02406, 0.06749, 0.07507, 0.01929, 0.00095, 0.05987, 0.06327, 0.09056,
+    0.02758, 0.00978, 0.02360, 0.00150, 0.01974, 0.00074,
+];
+
+/// Counts how many times each letter appears in `text`, ignoring case.
+pub fn letter_counts(text: &str) -> [usize; 26] {
+    let mut counts = [0; 26];
+    for i in text.chars().filter_map(letter_index) {
+        counts[i as usize] += 1;
+    }
+    counts
+}
+
+/// Compares the letter distribution of `text` to English with Pearson's
+/// chi-squared statistic. The lower the score, the more English `text` looks.
+pub fn chi_squared(text: &str) -> f64 {
+    let counts = letter_counts(text);
+    let total = counts.iter().sum::<usize>() as f64;
+    counts
+        .iter()
+        .zip(ENGLISH_FREQUENCIES.iter())
+        .map(|(&count, &frequency)| {
+            let expected = total * frequency;
+            if expected == 0.0 {
+                0.0
+            } else {
+                (count as f64 - expected).powi(2) / expected
+            }
+        })
+        .sum()
+}
+
+/// The index of coincidence of typical English text.
+pub const ENGLISH_IOC: f64 = 0.0667;
+
+/// The index of coincidence of uniformly random letters.
+pub const RANDOM_IOC: f64 = 1.0 / 26.0;
+
+/// The probability that two letters picked at random from `text` are the same.
+/// Returns 0 if there are fewer than two letters.
+pub fn index_of_coincidence(text: &str) -> f64 {
+    let counts = letter_counts(text);
+    let total = counts.iter().sum::<usize>();
+    if total < 2 {
+        return 0.0;
+    }
+    let pairs = counts.iter().map(|&n| n * n.saturating_sub(1)).sum::<usize>();
+    pairs as f64 / (total * (total - 1)) as f64
+}
+
+/// The fifty most common bigrams in English and how often they appear, from
+/// Peter Norvig's counts over the Google Books corpus.
+const ENGLISH_BIGRAMS: [(&str, f64); 50] = [
+    ("th", 0.0356), ("he", 0.0307), ("in", 0.0243), ("er", 0.0205), ("an", 0.0199),
+    ("re", 0.0185), ("on", 0.0176), ("at", 0.0149), ("en", 0.0145), ("nd", 0.0135),
+    ("ti", 0.0134), ("es", 0.0134), ("or", 0.0128), ("te", 0.0120), ("of", 0.0117),
+    ("ed", 0.0117), ("is", 0.0113), ("it", 0.0112), ("al", 0.0109), ("ar", 0.0107),
+    ("st", 0.0105), ("to", 0.0104), ("nt", 0.0104), ("ng", 0.0095), ("se", 0.0093),
+    ("ha", 0.0093), ("as", 0.0087), ("ou", 0.0087), ("io", 0.0083), ("le", 0.0083),
+    ("ve", 0.0083), ("co", 0.0079), ("me", 0.0079), ("de", 0.0076), ("hi", 0.0076),
+    ("ri", 0.0073), ("ro", 0.0073), ("ic", 0.0070), ("ne", 0.0069), ("ea", 0.0069),
+    ("ra", 0.0069), ("ce", 0.0065), ("li", 0.0062), ("ch", 0.0060), ("ll", 0.0058),
+    ("be", 0.0058), ("ma", 0.0057), ("si", 0.0055), ("om", 0.0055), ("ur", 0.0054),
+];
+
+/// How surprising the pairs of adjacent letters in `text` are to an English
+/// reader, in bits per pair. The lower the score, the more English `text` looks.
+///
+/// Unlike [`chi_squared`], this can tell real words apart from text that only
+/// has English letter frequencies, so it is better for ranking the results of
+/// searches that fit each letter separately.
+pub fn bigram_score(text: &str) -> f64 {
+    let listed = ENGLISH_BIGRAMS.iter().map(|(_, frequency)| frequency).sum::<f64>();
+    let unlisted = (1.0 - listed) / (26 * 26 - ENGLISH_BIGRAMS.len()) as f64;
+
+    let mut probabilities = [unlisted; 26 * 26];
+    for (bigram, frequency) in ENGLISH_BIGRAMS.iter() {
+        let mut letters = bigram.chars().filter_map(letter_index);
+        let (a, b) = (letters.next().unwrap(), letters.next().unwrap());
+        probabilities[(a * 26 + b) as usize] = *frequency;
+    }
+
+    let letters = text.chars().map(letter_index).collect::<Vec<_>>();
+    let (total, count) = letters
+        .windows(2)
+        .filter_map(|pair| Some((pair[0]? * 26 + pair[1]?) as usize))
+        .fold((0.0, 0), |(total, count), bigram| {
+            (total - probabilities[bigram].log2(), count + 1)
+        });
+    if count == 0 {
+        0.0
+    } else {
+        total / count as f64
+    }
+}
+
+/// Counts every run of `n` adjacent letters in `text`, ignoring case. The most
+/// common come first, and runs that are equally common are in alphabetical order.
+pub fn ngram_counts(text: &str, n: usize) -> Vec<(String, usize)> {
+    let chars = text.chars().collect::<Vec<_>>();
+    let mut counts = HashMap::new();
+    for window in chars.windows(n) {
+        if window.iter().all(|&c| letter_index(c).is_some()) {
+            let ngram = window.iter().map(char::to_ascii_uppercase).collect::<String>();
+            *counts.entry(ngram).or_insert(0) += 1;
+        }
+    }
+    let mut counts = counts.into_iter().collect::<Vec<_>>();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    counts
+}
+
+/// Log probabilities of every run of four letters, for telling English apart
+/// from gibberish more reliably than single letters or pairs can.
+pub struct Quadgrams {
+    log_probabilities: Vec<f64>,
+}
+
+impl Quadgrams {
+    /// The table for English, built the first time it's needed from counts of
+    /// the quadgrams in the prose of the Rust 1.95.0 documentation, which is
+    /// licensed under MIT or Apache-2.0. `src/data/quadgrams.py` regenerates
+    /// them. Quadgrams that never appear are treated as very unlikely.
+    pub fn english() -> &'static Self {
+        static ENGLISH: OnceLock<Quadgrams> = OnceLock::new();
+        ENGLISH.get_or_init(Quadgrams::load_english)
+    }
+
+    fn load_english() -> Self {
+        let counts = include_str!("data/quadgrams.txt")
+            .lines()
+            .map(|line| {
+                let mut parts = line.split(' ');
+                let quadgram = parts.next().unwrap();
+                let count = parts.next().unwrap().parse::<f64>().unwrap();
+                (quadgram, count)
+            })
+            .collect::<Vec<_>>();
+        let total = counts.iter().map(|(_, count)| count).sum::<f64>();
+
+        let mut log_probabilities = vec![(0.01 / total).log10(); 26 * 26 * 26 * 26];
+        for (quadgram, count) in counts {
+            let index = quadgram
+                .chars()
+                .filter_map(letter_index)
+                .fold(0, |index, x| index * 26 + x as usize);
+            log_probabilities[index] = (count / total).log10();
+        }
+        Quadgrams { log_probabilities }
+    }
+
+    /// The log probability of a quadgram, given as the positions of its letters
+    /// in the alphabet.
+    pub fn log_probability(&self, quadgram: [u32; 4]) -> f64 {
+        let index = quadgram.iter().fold(0, |index, &x| index * 26 + x as usize);
+        self.log_probabilities[index]
+    }
+
+    /// How unlikely the letters of `text` are to be English, per quadgram,
+    /// ignoring anything that isn't a letter. The lower the score, the more
+    /// English `text` looks.
+    pub fn score(&self, text: &str) -> f64 {
+        let letters = text.chars().filter_map(letter_index).collect::<Vec<_>>();
+        self.score_letters(&letters)
+    }
+
+    /// Like [`score`](Quadgrams::score), for text that is already just the
+    /// positions of its letters in the alphabet.
+    pub fn score_letters(&self, letters: &[u32]) -> f64 {
+        if letters.len() < 4 {
+            return 0.0;
+        }
+        let total = letters
+            .windows(4)
+            .map(|window| self.log_probability([window[0], window[1], window[2], window[3]]))
+            .sum::<f64>();
+        -total / (letters.len() - 3) as f64
+    }
+}
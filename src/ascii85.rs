@@ -0,0 +1,185 @@
+use crate::{
+    cipher::{match_encoding, utf8},
+    encoding::{without_whitespace, DecodeError},
+    Cipher,
+    CipherError,
+};
+
+/// Ascii85 encoding, which writes every four bytes as five characters. There
+/// is no key, so the key type is `()`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Ascii85 {
+    pub options: Ascii85Options,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Ascii85Variant {
+    /// Adobe's version, using `!` to `u`, with `z` for four zero bytes.
+    #[default]
+    Adobe,
+    /// ZeroMQ's Z85, whose characters are safe to put in source code. It can
+    /// only encode whole groups of four bytes.
+    Z85,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Ascii85Options {
+    pub variant: Ascii85Variant,
+    /// Wrap Adobe's output in `<~` and `~>`, as in PostScript.
+    pub delimiters: bool,
+}
+
+const Z85_ALPHABET: &[u8; 85] =
+    b"0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ.-:+=^!/*?&<>()[]{}@%$#";
+
+impl Ascii85Variant {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Ascii85Variant::Adobe => "Ascii85",
+            Ascii85Variant::Z85 => "Z85",
+        }
+    }
+
+    fn digit(&self, x: u32) -> char {
+        match self {
+            Ascii85Variant::Adobe => (x as u8 + b'!') as char,
+            Ascii85Variant::Z85 => Z85_ALPHABET[x as usize] as char,
+        }
+    }
+
+    fn value(&self, c: char) -> Option<u32> {
+        match self {
+            Ascii85Variant::Adobe => ('!'..='u').contains(&c).then(|| c as u32 - '!' as u32),
+            Ascii85Variant::Z85 => Z85_ALPHABET.iter().position(|&x| x as char == c).map(|x| x as u32),
+        }
+    }
+}
+
+/// Returns `None` if Z85 is asked to encode a number of bytes that isn't a
+/// multiple of four.
+pub fn encode_bytes(bytes: &[u8], options: &Ascii85Options) -> Option<String> {
+    let variant = options.variant;
+    if variant == Ascii85Variant::Z85 && !bytes.len().is_multiple_of(4) {
+        return None;
+    }
+    let mut output = String::new();
+    for group in bytes.chunks(4) {
+        let mut padded = [0; 4];
+        padded[..group.len()].copy_from_slice(group);
+        let value = u32::from_be_bytes(padded);
+        if variant == Ascii85Variant::Adobe && value == 0 && group.len() == 4 {
+            output.push('z');
+            continue;
+        }
+        let mut digits = [0; 5];
+        let mut rest = value;
+        for digit in digits.iter_mut().rev() {
+            *digit = rest % 85;
+            rest /= 85;
+        }
+        // A group of fewer than four bytes only needs one more character than
+        // it has bytes.
+        output.extend(digits[..group.len() + 1].iter().map(|&x| variant.digit(x)));
+    }
+    if variant == Ascii85Variant::Adobe && options.delimiters {
+        output = format!("<~{}~>", output);
+    }
+    Some(output)
+}
+
+pub fn encode(plaintext: &str, options: &Ascii85Options) -> Option<String> {
+    encode_bytes(plaintext.as_bytes(), options)
+}
+
+/// Decodes `text`, ignoring whitespace. Adobe's `<~` and `~>` delimiters are
+/// optional.
+pub fn decode_bytes(text: &str, options: &Ascii85Options) -> Result<Vec<u8>, DecodeError> {
+    let variant = options.variant;
+    let mut chars = without_whitespace(text);
+    if variant == Ascii85Variant::Adobe {
+        if chars.len() >= 2 && chars[0].1 == '<' && chars[1].1 == '~' {
+            chars.drain(..2);
+        }
+        let len = chars.len();
+        if len >= 2 && chars[len - 2].1 == '~' && chars[len - 1].1 == '>' {
+            chars.truncate(len - 2);
+        }
+    }
+
+    let mut bytes = Vec::new();
+    let mut group = Vec::new();
+    for &(position, c) in &chars {
+        if variant == Ascii85Variant::Adobe && c == 'z' {
+            if !group.is_empty() {
+                return Err(DecodeError::at(position, "z can only come between groups of five"));
+            }
+            bytes.extend_from_slice(&[0; 4]);
+            continue;
+        }
+        let x = match variant.value(c) {
+            Some(x) => x,
+            None => return Err(DecodeError::at(position, format!("{} isn't in the {} alphabet", c, variant.name()))),
+        };
+        group.push((position, x));
+        if group.len() == 5 {
+            bytes.extend_from_slice(&decode_group(&group)?);
+            group.clear();
+        }
+    }
+    if !group.is_empty() {
+        if variant == Ascii85Variant::Z85 || group.len() == 1 {
+            return Err(DecodeError::at(
+                text.chars().count(),
+                "the last group is too short to make up a whole byte, so some are missing",
+            ));
+        }
+        // Fill the group in with the highest digit, then take off as many
+        // bytes as were missing.
+        let missing = 5 - group.len();
+        group.extend(std::iter::repeat_n((text.chars().count(), 84), missing));
+        let decoded = decode_group(&group)?;
+        bytes.extend_from_slice(&decoded[..4 - missing]);
+    }
+    Ok(bytes)
+}
+
+/// Turns five digits back into four bytes.
+fn decode_group(group: &[(usize, u32)]) -> Result<[u8; 4], DecodeError> {
+    let mut value = 0u64;
+    for &(_, x) in group {
+        value = value * 85 + x as u64;
+    }
+    if value > u32::MAX as u64 {
+        return Err(DecodeError::at(group[0].0, "this group is too big to fit in four bytes"));
+    }
+    Ok((value as u32).to_be_bytes())
+}
+
+/// Returns `None` if the input isn't valid or doesn't decode to UTF-8.
+pub fn decode(text: &str, options: &Ascii85Options) -> Option<String> {
+    String::from_utf8(decode_bytes(text, options).ok()?).ok()
+}
+
+fn z85_length() -> CipherError {
+    CipherError::InvalidText(String::from("Z85 can only encode a multiple of four bytes"))
+}
+
+impl Cipher for Ascii85 {
+    type Key = ();
+
+    fn is_valid_key(&self, _key: &()) -> bool {
+        true
+    }
+
+    fn encrypt(&self, plaintext: &str, _key: &()) -> Result<String, CipherError> {
+        encode(plaintext, &self.options).ok_or_else(z85_length)
+    }
+
+    fn decrypt(&self, ciphertext: &str, _key: &()) -> Result<String, CipherError> {
+        utf8(decode_bytes(ciphertext, &self.options)?)
+    }
+
+    fn find_key(&self, plaintext: &str, ciphertext: &str) -> Result<(), CipherError> {
+        match_encoding(&self.encrypt(plaintext, &())?, ciphertext)
+    }
+}
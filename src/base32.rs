@@ -0,0 +1,152 @@
+use crate::{
+    cipher::{match_encoding, utf8},
+    encoding::{without_whitespace, DecodeError},
+    Cipher,
+    CipherError,
+};
+
+/// Base32 encoding. There is no key, so the key type is `()`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Base32 {
+    pub options: Base32Options,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Base32Alphabet {
+    /// `A` to `Z` then `2` to `7`, padded with `=`, as in RFC 4648.
+    #[default]
+    Rfc4648,
+    /// Douglas Crockford's alphabet, which leaves out I, L, O and U so that
+    /// it can be read aloud and typed in without mix-ups.
+    Crockford,
+}
+
+impl Base32Alphabet {
+    fn letters(&self) -> &'static [u8; 32] {
+        match self {
+            Base32Alphabet::Rfc4648 => b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567",
+            Base32Alphabet::Crockford => b"0123456789ABCDEFGHJKMNPQRSTVWXYZ",
+        }
+    }
+
+    /// The value of `c`, ignoring case. Crockford reads O as 0, and I and L
+    /// as 1.
+    fn value(&self, c: char) -> Option<u32> {
+        let c = c.to_ascii_uppercase();
+        let c = match (self, c) {
+            (Base32Alphabet::Crockford, 'O') => '0',
+            (Base32Alphabet::Crockford, 'I') | (Base32Alphabet::Crockford, 'L') => '1',
+            _ => c,
+        };
+        self.letters().iter().position(|&x| x as char == c).map(|x| x as u32)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Base32Options {
+    pub alphabet: Base32Alphabet,
+    /// Leave off the `=` signs that pad the output to a multiple of eight.
+    /// Crockford's alphabet is never padded.
+    pub no_padding: bool,
+}
+
+pub fn encode_bytes(bytes: &[u8], options: &Base32Options) -> String {
+    let letters = options.alphabet.letters();
+    let mut output = String::new();
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for &byte in bytes {
+        buffer = buffer << 8 | byte as u32;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            output.push(letters[(buffer >> bits & 31) as usize] as char);
+        }
+        buffer &= (1 << bits) - 1;
+    }
+    if bits > 0 {
+        output.push(letters[(buffer << (5 - bits) & 31) as usize] as char);
+    }
+    if options.alphabet == Base32Alphabet::Rfc4648 && !options.no_padding {
+        while !output.len().is_multiple_of(8) {
+            output.push('=');
+        }
+    }
+    output
+}
+
+pub fn encode(plaintext: &str, options: &Base32Options) -> String {
+    encode_bytes(plaintext.as_bytes(), options)
+}
+
+/// Decodes `base32`, ignoring case and whitespace. Padding is optional, and
+/// Crockford's alphabet also ignores dashes.
+pub fn decode_bytes(base32: &str, options: &Base32Options) -> Result<Vec<u8>, DecodeError> {
+    let mut chars = without_whitespace(base32);
+    if options.alphabet == Base32Alphabet::Crockford {
+        chars.retain(|&(_, c)| c != '-');
+    } else {
+        while chars.last().is_some_and(|&(_, c)| c == '=') {
+            chars.pop();
+        }
+    }
+
+    let mut bytes = Vec::new();
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for &(position, c) in &chars {
+        let x = match options.alphabet.value(c) {
+            Some(x) => x,
+            None if c == '=' => return Err(DecodeError::at(position, "padding can only come at the end")),
+            None => return Err(DecodeError::at(position, format!("{} isn't a Base32 character", c))),
+        };
+        buffer = buffer << 5 | x;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    // A whole byte fits in fewer than five more bits, so any more than that
+    // means the text was cut short.
+    if bits >= 5 {
+        return Err(DecodeError::at(
+            base32.chars().count(),
+            "the last character doesn't make up a whole byte, so some are missing",
+        ));
+    }
+    if buffer != 0 {
+        let (position, c) = *chars.last().unwrap();
+        return Err(DecodeError::at(
+            position,
+            format!("{} has leftover bits set, so the text is cut short or corrupted", c),
+        ));
+    }
+    Ok(bytes)
+}
+
+/// Returns `None` if the input isn't valid Base32 or doesn't decode to UTF-8.
+pub fn decode(base32: &str, options: &Base32Options) -> Option<String> {
+    String::from_utf8(decode_bytes(base32, options).ok()?).ok()
+}
+
+impl Cipher for Base32 {
+    type Key = ();
+
+    fn is_valid_key(&self, _key: &()) -> bool {
+        true
+    }
+
+    fn encrypt(&self, plaintext: &str, _key: &()) -> Result<String, CipherError> {
+        Ok(encode(plaintext, &self.options))
+    }
+
+    fn decrypt(&self, ciphertext: &str, _key: &()) -> Result<String, CipherError> {
+        utf8(decode_bytes(ciphertext, &self.options)?)
+    }
+
+    fn find_key(&self, plaintext: &str, ciphertext: &str) -> Result<(), CipherError> {
+        match_encoding(&encode(plaintext, &self.options), ciphertext)
+    }
+}
@@ -0,0 +1,191 @@
+use crate::{
+    cipher::{match_encoding, utf8},
+    encoding::{without_whitespace, DecodeError},
+    Cipher,
+    CipherError,
+};
+
+/// Base58 encoding with Bitcoin's alphabet. There is no key, so the key type
+/// is `()`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Base58 {
+    /// Add a checksum to the end, as Base58Check does.
+    pub check: bool,
+}
+
+/// The digits and letters, leaving out 0, O, I and l, which look alike.
+const ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// The first four bytes of SHA-256 applied twice, as Base58Check uses.
+fn checksum(bytes: &[u8]) -> [u8; 4] {
+    let hash = sha256(&sha256(bytes));
+    [hash[0], hash[1], hash[2], hash[3]]
+}
+
+/// Each zero byte at the start is written as `1`. The rest are written as one
+/// big number in base 58.
+pub fn encode_bytes(bytes: &[u8], check: bool) -> String {
+    let mut bytes = bytes.to_vec();
+    if check {
+        let checksum = checksum(&bytes);
+        bytes.extend_from_slice(&checksum);
+    }
+    let zeros = bytes.iter().take_while(|&&byte| byte == 0).count();
+    // The digits, least significant first.
+    let mut digits: Vec<u32> = Vec::new();
+    for &byte in &bytes[zeros..] {
+        let mut carry = byte as u32;
+        for digit in digits.iter_mut() {
+            carry += *digit << 8;
+            *digit = carry % 58;
+            carry /= 58;
+        }
+        while carry > 0 {
+            digits.push(carry % 58);
+            carry /= 58;
+        }
+    }
+    std::iter::repeat_n('1', zeros)
+        .chain(digits.iter().rev().map(|&digit| ALPHABET[digit as usize] as char))
+        .collect()
+}
+
+pub fn encode(plaintext: &str, check: bool) -> String {
+    encode_bytes(plaintext.as_bytes(), check)
+}
+
+/// Decodes `base58`, ignoring whitespace. With `check`, the checksum is
+/// checked and taken off.
+pub fn decode_bytes(base58: &str, check: bool) -> Result<Vec<u8>, DecodeError> {
+    let chars = without_whitespace(base58);
+    let zeros = chars.iter().take_while(|&&(_, c)| c == '1').count();
+    // The bytes, least significant first.
+    let mut bytes: Vec<u8> = Vec::new();
+    for &(position, c) in &chars[zeros..] {
+        let mut carry = match ALPHABET.iter().position(|&x| x as char == c) {
+            Some(x) => x as u32,
+            None => {
+                let reason = match c {
+                    '0' | 'O' | 'I' | 'l' => format!("{} is left out of Base58 because it looks like another character", c),
+                    _ => format!("{} isn't a Base58 character", c),
+                };
+                return Err(DecodeError::at(position, reason));
+            },
+        };
+        for byte in bytes.iter_mut() {
+            carry += (*byte as u32) * 58;
+            *byte = carry as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            bytes.push(carry as u8);
+            carry >>= 8;
+        }
+    }
+    let mut bytes = std::iter::repeat_n(0, zeros).chain(bytes.into_iter().rev()).collect::<Vec<_>>();
+    if check {
+        if bytes.len() < 4 {
+            return Err(DecodeError::whole("the text is too short to have a checksum"));
+        }
+        let (data, sum) = bytes.split_at(bytes.len() - 4);
+        if checksum(data) != sum {
+            return Err(DecodeError::whole("the checksum doesn't match, so the text has a mistake in it"));
+        }
+        bytes.truncate(bytes.len() - 4);
+    }
+    Ok(bytes)
+}
+
+/// Returns `None` if the input isn't valid Base58 or doesn't decode to UTF-8.
+pub fn decode(base58: &str, check: bool) -> Option<String> {
+    String::from_utf8(decode_bytes(base58, check).ok()?).ok()
+}
+
+const ROUND_CONSTANTS: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+/// SHA-256, as described in FIPS 180-4. It's only needed for checksums, so
+/// it's kept simple rather than fast.
+pub fn sha256(bytes: &[u8]) -> [u8; 32] {
+    let mut state: [u32; 8] = [
+        0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+    ];
+    // Pad with a 1 bit, then zeros, then the length in bits, to fill a
+    // multiple of 64 bytes.
+    let mut message = bytes.to_vec();
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend_from_slice(&(bytes.len() as u64 * 8).to_be_bytes());
+
+    for block in message.chunks(64) {
+        let mut w = [0u32; 64];
+        for (i, word) in block.chunks(4).enumerate() {
+            w[i] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+        }
+        for i in 16..64 {
+            let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ w[i - 15] >> 3;
+            let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ w[i - 2] >> 10;
+            w[i] = w[i - 16].wrapping_add(s0).wrapping_add(w[i - 7]).wrapping_add(s1);
+        }
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = state;
+        for i in 0..64 {
+            let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+            let choice = (e & f) ^ (!e & g);
+            let temp1 = h
+                .wrapping_add(s1)
+                .wrapping_add(choice)
+                .wrapping_add(ROUND_CONSTANTS[i])
+                .wrapping_add(w[i]);
+            let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+            let majority = (a & b) ^ (a & c) ^ (b & c);
+            let temp2 = s0.wrapping_add(majority);
+            h = g;
+            g = f;
+            f = e;
+            e = d.wrapping_add(temp1);
+            d = c;
+            c = b;
+            b = a;
+            a = temp1.wrapping_add(temp2);
+        }
+        for (x, y) in state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+            *x = x.wrapping_add(y);
+        }
+    }
+
+    let mut hash = [0; 32];
+    for (i, x) in state.iter().enumerate() {
+        hash[i * 4..i * 4 + 4].copy_from_slice(&x.to_be_bytes());
+    }
+    hash
+}
+
+impl Cipher for Base58 {
+    type Key = ();
+
+    fn is_valid_key(&self, _key: &()) -> bool {
+        true
+    }
+
+    fn encrypt(&self, plaintext: &str, _key: &()) -> Result<String, CipherError> {
+        Ok(encode(plaintext, self.check))
+    }
+
+    fn decrypt(&self, ciphertext: &str, _key: &()) -> Result<String, CipherError> {
+        utf8(decode_bytes(ciphertext, self.check)?)
+    }
+
+    fn find_key(&self, plaintext: &str, ciphertext: &str) -> Result<(), CipherError> {
+        match_encoding(&encode(plaintext, self.check), ciphertext)
+    }
+}
@@ -0,0 +1,120 @@
+use crate::{
+    cipher::{match_encoding, utf8},
+    encoding::DecodeError,
+    Cipher,
+    CipherError,
+};
+
+/// Base64 encoding. There is no key, so the key type is `()`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Base64 {
+    pub options: Base64Options,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Base64Options {
+    /// Use `-` and `_` instead of `+` and `/`, so the output can go in URLs
+    /// and file names.
+    pub url_safe: bool,
+    /// Leave off the `=` signs that pad the output to a multiple of four.
+    pub no_padding: bool,
+    /// Break the output into lines of [`MIME_LINE_LENGTH`], as in email.
+    pub wrap: bool,
+}
+
+/// The longest line MIME allows in Base64.
+pub const MIME_LINE_LENGTH: usize = 76;
+
+impl Base64Options {
+    fn config(&self) -> ::base64::Config {
+        let char_set = if self.url_safe {
+            ::base64::CharacterSet::UrlSafe
+        } else {
+            ::base64::CharacterSet::Standard
+        };
+        ::base64::Config::new(char_set, !self.no_padding)
+    }
+}
+
+pub fn encode_bytes(bytes: &[u8], options: &Base64Options) -> String {
+    let base64 = ::base64::encode_config(bytes, options.config());
+    if !options.wrap {
+        return base64;
+    }
+    // Base64 is all ASCII, so this can't split a character.
+    base64
+        .as_bytes()
+        .chunks(MIME_LINE_LENGTH)
+        .map(|line| std::str::from_utf8(line).unwrap())
+        .collect::<Vec<_>>()
+        .join("\r\n")
+}
+
+pub fn encode(plaintext: &str, options: &Base64Options) -> String {
+    encode_bytes(plaintext.as_bytes(), options)
+}
+
+/// Decodes `base64`, ignoring whitespace so that wrapped lines can be decoded.
+/// Padding is optional.
+pub fn decode_bytes(base64: &str, options: &Base64Options) -> Result<Vec<u8>, DecodeError> {
+    // Remember where each byte of the stripped text came from, so that errors
+    // can point at the right character.
+    let mut stripped = String::new();
+    let mut positions = Vec::new();
+    for (i, c) in base64.chars().enumerate().filter(|(_, c)| !c.is_whitespace()) {
+        stripped.push(c);
+        positions.extend(std::iter::repeat_n(i, c.len_utf8()));
+    }
+    let char_at = |offset: usize| {
+        let position = positions[offset];
+        (position, base64.chars().nth(position).unwrap())
+    };
+    ::base64::decode_config(&stripped, options.config()).map_err(|e| match e {
+        ::base64::DecodeError::InvalidByte(offset, _) => {
+            let (position, c) = char_at(offset);
+            let reason = match c {
+                '=' => String::from("padding can only come at the end"),
+                '-' | '_' if !options.url_safe => format!("{} is only in the URL-safe alphabet", c),
+                '+' | '/' if options.url_safe => format!("{} isn't in the URL-safe alphabet", c),
+                _ => format!("{} isn't a Base64 character", c),
+            };
+            DecodeError::at(position, reason)
+        },
+        ::base64::DecodeError::InvalidLength => DecodeError::at(
+            base64.chars().count(),
+            "the last character doesn't make up a whole byte, so some are missing",
+        ),
+        ::base64::DecodeError::InvalidLastSymbol(offset, _) => {
+            let (position, c) = char_at(offset);
+            DecodeError::at(
+                position,
+                format!("{} has leftover bits set, so the text is cut short or corrupted", c),
+            )
+        },
+    })
+}
+
+/// Returns `None` if the input isn't valid Base64 or doesn't decode to UTF-8.
+pub fn decode(base64: &str, options: &Base64Options) -> Option<String> {
+    String::from_utf8(decode_bytes(base64, options).ok()?).ok()
+}
+
+impl Cipher for Base64 {
+    type Key = ();
+
+    fn is_valid_key(&self, _key: &()) -> bool {
+        true
+    }
+
+    fn encrypt(&self, plaintext: &str, _key: &()) -> Result<String, CipherError> {
+        Ok(encode(plaintext, &self.options))
+    }
+
+    fn decrypt(&self, ciphertext: &str, _key: &()) -> Result<String, CipherError> {
+        utf8(decode_bytes(ciphertext, &self.options)?)
+    }
+
+    fn find_key(&self, plaintext: &str, ciphertext: &str) -> Result<(), CipherError> {
+        match_encoding(&encode(plaintext, &self.options), ciphertext)
+    }
+}
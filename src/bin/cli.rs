@@ -0,0 +1,1034 @@
+use ciphertools::{
+    affine::{self, Affine},
+    alphabet::Alphabet,
+    ascii85::{self, Ascii85Options, Ascii85Variant},
+    base32::{self, Base32Alphabet, Base32Options},
+    base58,
+    base64::{self, Base64Options},
+    columnar::{self, Columnar},
+    enigma::{self, EnigmaKey},
+    hex::{self, HexOptions, Separator},
+    hill::{self, Hill, Matrix},
+    identify,
+    morse::{self, Keying, MorseOptions},
+    playfair::{Merge, Playfair, PlayfairKey},
+    rail_fence::{self, RailFence},
+    recipe::Recipe,
+    shift::{self, Shift},
+    substitution::{self, Substitution},
+    text_options::{Case, TextOptions},
+    vigenere::{self, Variant, Vigenere, VARIANTS},
+    wav::Wav,
+    Cipher,
+};
+use clap::{App, AppSettings, Arg, ArgMatches, SubCommand};
+use std::{
+    fs,
+    io::{self, Read, Write},
+    process,
+};
+
+fn main() {
+    let matches = app().get_matches();
+    match run(&matches) {
+        // Ignore errors, so that piping into something like `head` doesn't panic.
+        Ok(output) => drop(writeln!(io::stdout(), "{}", output)),
+        Err(e) => {
+            eprintln!("error: {}", e);
+            process::exit(1);
+        },
+    }
+}
+
+fn app() -> App<'static, 'static> {
+    App::new("ciphertools-cli")
+        .about("Runs the CipherTools ciphers and encodings from the command line.")
+        .setting(AppSettings::SubcommandRequiredElseHelp)
+        .subcommand(
+            SubCommand::with_name("shift")
+                .about("Shifts each character along the alphabet.")
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+                .subcommand(
+                    transform("encrypt")
+                        .arg(key_arg())
+                        .arg(alphabet_letters_arg())
+                        .args(&text_options_args()),
+                )
+                .subcommand(
+                    transform("decrypt")
+                        .arg(key_arg())
+                        .arg(alphabet_letters_arg())
+                        .args(&text_options_args()),
+                )
+                .subcommand(find_key().arg(alphabet_letters_arg()).args(&text_options_args()))
+                .subcommand(
+                    transform("crack")
+                        .about("Ranks every possible decryption by how English it looks, best first.")
+                        .arg(alphabet_letters_arg()),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("vigenere")
+                .about("Shifts each character using the repeated key, or one of its variants.")
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+                .subcommand(
+                    transform("encrypt")
+                        .arg(key_arg())
+                        .arg(alphabet_letters_arg())
+                        .args(&text_options_args())
+                        .arg(key_on_letters_arg())
+                        .arg(variant_arg()),
+                )
+                .subcommand(
+                    transform("decrypt")
+                        .arg(key_arg())
+                        .arg(alphabet_letters_arg())
+                        .args(&text_options_args())
+                        .arg(key_on_letters_arg())
+                        .arg(variant_arg()),
+                )
+                .subcommand(
+                    find_key()
+                        .arg(alphabet_letters_arg())
+                        .args(&text_options_args())
+                        .arg(key_on_letters_arg())
+                        .arg(variant_arg()),
+                )
+                .subcommand(
+                    transform("crack")
+                        .about("Guesses the key from the ciphertext alone, best first.")
+                        .arg(alphabet_letters_arg())
+                        .args(&text_options_args())
+                        .arg(key_on_letters_arg())
+                        .arg(variant_arg())
+                        .arg(
+                            Arg::with_name("max-length")
+                                .long("max-length")
+                                .takes_value(true)
+                                .default_value("20")
+                                .help("The longest key to look for"),
+                        ),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("affine")
+                .about("Maps each letter x to ax + b. Keys are given as A,B.")
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+                .subcommand(transform("encrypt").arg(key_arg()))
+                .subcommand(transform("decrypt").arg(key_arg()))
+                .subcommand(find_key())
+                .subcommand(transform("crack").about(
+                    "Ranks the decryptions under every key by how English they look, best first.",
+                )),
+        )
+        .subcommand(
+            SubCommand::with_name("substitution")
+                .about("Replaces each letter with another. Keys are the 26-letter cipher alphabet.")
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+                .subcommand(transform("encrypt").arg(alphabet_arg()).arg(keyword_arg()))
+                .subcommand(transform("decrypt").arg(alphabet_arg()).arg(keyword_arg()))
+                .subcommand(find_key())
+                .subcommand(
+                    transform("crack")
+                        .about("Guesses the key from the ciphertext alone by hill climbing.")
+                        .arg(
+                            Arg::with_name("restarts")
+                                .long("restarts")
+                                .takes_value(true)
+                                .default_value("100")
+                                .help("How many times to restart the climb"),
+                        ),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("playfair")
+                .about("Swaps pairs of letters using a 5x5 square. Keys are the keyword that fills the square.")
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+                .subcommand(transform("encrypt").arg(key_arg()).args(&square_args()))
+                .subcommand(transform("decrypt").arg(key_arg()).args(&square_args())),
+        )
+        .subcommand(
+            SubCommand::with_name("hill")
+                .about("Multiplies blocks of letters by a matrix. Keys are given as rows, like 3,3;2,5.")
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+                .subcommand(transform("encrypt").arg(matrix_arg()).args(&matrix_keyword_args()))
+                .subcommand(transform("decrypt").arg(matrix_arg()).args(&matrix_keyword_args()))
+                .subcommand(find_key().arg(
+                    Arg::with_name("size")
+                        .long("size")
+                        .takes_value(true)
+                        .help("The size of the key to look for. Each size up to 5 is tried if not given."),
+                )),
+        )
+        .subcommand(
+            SubCommand::with_name("enigma")
+                .about("Simulates the Enigma I, M3 and M4. Encrypting and decrypting are the same.")
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+                .subcommand(transform("encrypt").args(&enigma_args()))
+                .subcommand(transform("decrypt").args(&enigma_args())),
+        )
+        .subcommand(
+            SubCommand::with_name("rail-fence")
+                .about("Writes the text in a zigzag and reads it off rail by rail. Keys are given as RAILS,OFFSET.")
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+                .subcommand(transform("encrypt").arg(key_arg()))
+                .subcommand(transform("decrypt").arg(key_arg()))
+                .subcommand(find_key()),
+        )
+        .subcommand(
+            SubCommand::with_name("columnar")
+                .about(
+                    "Writes the text in rows and reads the columns off in the keyword's order. \
+                     Keys are a keyword, or two separated by a comma for double transposition.",
+                )
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+                .subcommand(transform("encrypt").arg(key_arg()).arg(padding_arg()))
+                .subcommand(transform("decrypt").arg(key_arg()).arg(padding_arg()))
+                .subcommand(find_key()),
+        )
+        .subcommand(
+            SubCommand::with_name("base64")
+                .about("Transforms text into its Base64 representation.")
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+                .subcommand(transform("encode").args(&base64_args()))
+                .subcommand(transform("decode").args(&base64_args()).arg(hex_output_arg())),
+        )
+        .subcommand(
+            SubCommand::with_name("hex")
+                .about("Writes each byte as two hexadecimal digits.")
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+                .subcommand(
+                    transform("encode")
+                        .arg(
+                            Arg::with_name("separator")
+                                .long("separator")
+                                .takes_value(true)
+                                .possible_values(&["none", "space", "colon", "dash"])
+                                .default_value("none")
+                                .help("What goes between the bytes"),
+                        )
+                        .arg(Arg::with_name("upper").long("upper").help("Uses A to F rather than a to f")),
+                )
+                .subcommand(transform("decode").arg(hex_output_arg())),
+        )
+        .subcommand(
+            SubCommand::with_name("base32")
+                .about("Transforms text into its Base32 representation.")
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+                .subcommand(transform("encode").args(&base32_args()))
+                .subcommand(transform("decode").args(&base32_args()).arg(hex_output_arg())),
+        )
+        .subcommand(
+            SubCommand::with_name("base58")
+                .about("Transforms text into its Base58 representation, with Bitcoin's alphabet.")
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+                .subcommand(transform("encode").arg(check_arg()))
+                .subcommand(transform("decode").arg(check_arg()).arg(hex_output_arg())),
+        )
+        .subcommand(
+            SubCommand::with_name("ascii85")
+                .about("Writes every four bytes as five printable characters.")
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+                .subcommand(transform("encode").args(&ascii85_args()))
+                .subcommand(transform("decode").args(&ascii85_args()).arg(hex_output_arg())),
+        )
+        .subcommand(
+            SubCommand::with_name("morse")
+                .about(
+                    "Translates text to International Morse and back. Letters between < and > are run \
+                     together into a prosign, like <SK>.",
+                )
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+                .subcommand(transform("encode").args(&morse_args()))
+                .subcommand(transform("decode").args(&morse_args()))
+                .subcommand(
+                    transform("to-wav")
+                        .about("Keys the text in Morse as a tone, saved as a WAV file.")
+                        .args(&morse_args())
+                        .args(&keying_args())
+                        .arg(
+                            Arg::with_name("output")
+                                .short("o")
+                                .long("output")
+                                .takes_value(true)
+                                .required(true)
+                                .help("The WAV file to write"),
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("from-wav")
+                        .about("Listens for Morse in a WAV file and decodes it.")
+                        .args(&morse_args())
+                        .arg(Arg::with_name("file").required(true).help("The WAV file to read"))
+                        .arg(Arg::with_name("morse").long("morse").help("Prints the Morse rather than decoding it")),
+                ),
+        )
+        .subcommand(
+            transform("identify")
+                .about("Guesses which cipher or encoding the text came from, most likely first.")
+                .arg(Arg::with_name("statistics").long("statistics").help("Prints the statistics behind the guesses too")),
+        )
+        .subcommand(
+            transform("recipe")
+                .about(
+                    "Runs the text through each step of a recipe file in turn. Each line of the file is a \
+                     step, like \"vigenere decrypt key=lemon variant=beaufort\".",
+                )
+                .arg(
+                    Arg::with_name("recipe")
+                        .short("r")
+                        .long("recipe")
+                        .takes_value(true)
+                        .required(true)
+                        .help("The recipe file"),
+                )
+                .arg(Arg::with_name("steps").long("steps").help("Prints the output of every step, not just the last")),
+        )
+}
+
+/// A subcommand that reads one text from an argument, a file or stdin.
+fn transform(name: &'static str) -> App<'static, 'static> {
+    SubCommand::with_name(name)
+        .arg(
+            Arg::with_name("text")
+                .help("The text to transform. Read from stdin if neither this nor --input is given."),
+        )
+        .arg(
+            Arg::with_name("input")
+                .short("i")
+                .long("input")
+                .takes_value(true)
+                .conflicts_with("text")
+                .help("Reads the text from a file"),
+        )
+}
+
+fn find_key() -> App<'static, 'static> {
+    SubCommand::with_name("find-key")
+        .about("Finds the key that encrypts the plaintext to the ciphertext.")
+        .arg(
+            Arg::with_name("plaintext")
+                .short("p")
+                .long("plaintext")
+                .takes_value(true)
+                .required_unless_one(&["plaintext-file"]),
+        )
+        .arg(
+            Arg::with_name("plaintext-file")
+                .long("plaintext-file")
+                .takes_value(true)
+                .conflicts_with("plaintext"),
+        )
+        .arg(
+            Arg::with_name("ciphertext")
+                .short("c")
+                .long("ciphertext")
+                .takes_value(true)
+                .required_unless_one(&["ciphertext-file"]),
+        )
+        .arg(
+            Arg::with_name("ciphertext-file")
+                .long("ciphertext-file")
+                .takes_value(true)
+                .conflicts_with("ciphertext"),
+        )
+}
+
+fn key_arg() -> Arg<'static, 'static> {
+    Arg::with_name("key")
+        .short("k")
+        .long("key")
+        .takes_value(true)
+        .required(true)
+}
+
+/// Like [`key_arg`], but the key can be given as a keyword instead.
+fn alphabet_arg() -> Arg<'static, 'static> {
+    key_arg().required_unless("keyword")
+}
+
+fn keyword_arg() -> Arg<'static, 'static> {
+    Arg::with_name("keyword")
+        .long("keyword")
+        .takes_value(true)
+        .conflicts_with("key")
+        .help("Uses the keyword's letters, then the rest of the alphabet, as the key")
+}
+
+fn alphabet_letters_arg() -> Arg<'static, 'static> {
+    Arg::with_name("alphabet")
+        .long("alphabet")
+        .takes_value(true)
+        .default_value("english")
+        .help("The letters to shift along, or one of english, german, spanish, russian, greek")
+}
+
+/// Reads `--alphabet` as the name of a preset, or failing that as the letters themselves.
+fn read_alphabet(matches: &ArgMatches) -> Result<Alphabet, String> {
+    Alphabet::parse(matches.value_of("alphabet").unwrap()).map_err(|e| format!("invalid alphabet: {}", e))
+}
+
+fn text_options_args() -> [Arg<'static, 'static>; 3] {
+    [
+        Arg::with_name("strip")
+            .long("strip")
+            .help("Leaves out anything that isn't in the alphabet"),
+        Arg::with_name("case")
+            .long("case")
+            .takes_value(true)
+            .possible_values(&["keep", "upper", "lower"])
+            .default_value("keep"),
+        Arg::with_name("groups")
+            .long("groups")
+            .help("Splits the ciphertext into blocks of five letters"),
+    ]
+}
+
+fn key_on_letters_arg() -> Arg<'static, 'static> {
+    Arg::with_name("key-on-letters")
+        .long("key-on-letters")
+        .help("Only moves on to the next letter of the key after a letter")
+}
+
+fn variant_arg() -> Arg<'static, 'static> {
+    Arg::with_name("variant")
+        .long("variant")
+        .takes_value(true)
+        .possible_values(&VARIANTS.iter().map(|variant| variant.id()).collect::<Vec<_>>())
+        .default_value("vigenere")
+        .help("Gronsfeld keys are digits, autokey keys are the primer, and running keys are a long text")
+}
+
+fn read_vigenere(matches: &ArgMatches) -> Result<Vigenere, String> {
+    Ok(Vigenere {
+        alphabet: read_alphabet(matches)?,
+        options: read_text_options(matches),
+        // clap only allows the variants' ids.
+        variant: Variant::parse(matches.value_of("variant").unwrap()).unwrap(),
+    })
+}
+
+fn read_text_options(matches: &ArgMatches) -> TextOptions {
+    TextOptions {
+        key_on_letters: matches.is_present("key-on-letters"),
+        strip: matches.is_present("strip"),
+        case: match matches.value_of("case") {
+            Some("upper") => Case::Upper,
+            Some("lower") => Case::Lower,
+            _ => Case::Keep,
+        },
+        groups: matches.is_present("groups"),
+    }
+}
+
+fn matrix_arg() -> Arg<'static, 'static> {
+    key_arg().required_unless("keyword")
+}
+
+fn matrix_keyword_args() -> [Arg<'static, 'static>; 2] {
+    [
+        Arg::with_name("keyword")
+            .long("keyword")
+            .takes_value(true)
+            .conflicts_with("key")
+            .help("Fills the matrix row by row with the keyword's letters"),
+        Arg::with_name("size")
+            .long("size")
+            .takes_value(true)
+            .default_value("2")
+            .help("The size of the matrix to fill with --keyword"),
+    ]
+}
+
+fn square_args() -> [Arg<'static, 'static>; 2] {
+    [
+        Arg::with_name("filler")
+            .long("filler")
+            .takes_value(true)
+            .default_value("X")
+            .help("The letter put between doubled letters and after a lone last letter"),
+        Arg::with_name("omit-q")
+            .long("omit-q")
+            .help("Leaves Q out of the square instead of merging J into I"),
+    ]
+}
+
+fn enigma_args() -> [Arg<'static, 'static>; 6] {
+    [
+        Arg::with_name("model")
+            .long("model")
+            .takes_value(true)
+            .possible_values(&["I", "M3", "M4"])
+            .default_value("I"),
+        Arg::with_name("reflector")
+            .long("reflector")
+            .takes_value(true)
+            .default_value("B")
+            .help("A, B, C, B-thin or C-thin"),
+        Arg::with_name("rotors")
+            .long("rotors")
+            .takes_value(true)
+            .default_value("I,II,III")
+            .help("The rotors from left to right, like II,IV,V, or Beta,II,IV,I on the M4"),
+        Arg::with_name("rings")
+            .long("rings")
+            .takes_value(true)
+            .help("The ring settings from left to right, like BUL. All A if not given."),
+        Arg::with_name("positions")
+            .long("positions")
+            .takes_value(true)
+            .help("The starting positions from left to right, like BLA. All A if not given."),
+        Arg::with_name("plugboard")
+            .long("plugboard")
+            .takes_value(true)
+            .default_value("")
+            .help("Pairs of letters to swap, like \"AV BS CG\""),
+    ]
+}
+
+fn enigma_key(matches: &ArgMatches) -> Result<EnigmaKey, String> {
+    enigma::parse_key(
+        matches.value_of("model").unwrap(),
+        matches.value_of("reflector").unwrap(),
+        matches.value_of("rotors").unwrap(),
+        matches.value_of("rings"),
+        matches.value_of("positions"),
+        matches.value_of("plugboard").unwrap(),
+    )
+    .map_err(|e| e.to_string())
+}
+
+fn padding_arg() -> Arg<'static, 'static> {
+    Arg::with_name("padding")
+        .long("padding")
+        .takes_value(true)
+        .help("Pads the first grid with this character so that it is regular")
+}
+
+fn base64_args() -> [Arg<'static, 'static>; 3] {
+    [
+        Arg::with_name("url-safe")
+            .long("url-safe")
+            .help("Uses - and _ instead of + and /"),
+        Arg::with_name("no-padding")
+            .long("no-padding")
+            .help("Leaves off the = padding. Decoding accepts either."),
+        Arg::with_name("wrap")
+            .long("wrap")
+            .help("Breaks the output into 76-character lines, as in email"),
+    ]
+}
+
+fn read_base64_options(matches: &ArgMatches) -> Base64Options {
+    Base64Options {
+        url_safe: matches.is_present("url-safe"),
+        no_padding: matches.is_present("no-padding"),
+        wrap: matches.is_present("wrap"),
+    }
+}
+
+fn read_hex_options(matches: &ArgMatches) -> HexOptions {
+    let separator = match matches.value_of("separator").unwrap() {
+        "space" => Separator::Space,
+        "colon" => Separator::Colon,
+        "dash" => Separator::Dash,
+        _ => Separator::None,
+    };
+    HexOptions {
+        separator,
+        upper: matches.is_present("upper"),
+    }
+}
+
+fn base32_args() -> [Arg<'static, 'static>; 2] {
+    [
+        Arg::with_name("crockford")
+            .long("crockford")
+            .help("Uses Crockford's alphabet, which leaves out I, L, O and U"),
+        Arg::with_name("no-padding")
+            .long("no-padding")
+            .help("Leaves off the = padding. Decoding accepts either."),
+    ]
+}
+
+fn read_base32_options(matches: &ArgMatches) -> Base32Options {
+    Base32Options {
+        alphabet: if matches.is_present("crockford") {
+            Base32Alphabet::Crockford
+        } else {
+            Base32Alphabet::Rfc4648
+        },
+        no_padding: matches.is_present("no-padding"),
+    }
+}
+
+fn check_arg() -> Arg<'static, 'static> {
+    Arg::with_name("check")
+        .long("check")
+        .help("Adds a checksum to the end, or checks and removes it, as in Base58Check")
+}
+
+fn ascii85_args() -> [Arg<'static, 'static>; 2] {
+    [
+        Arg::with_name("z85")
+            .long("z85")
+            .help("Uses ZeroMQ's Z85, which only encodes whole groups of four bytes"),
+        Arg::with_name("delimiters")
+            .long("delimiters")
+            .help("Wraps the output in <~ and ~>. Decoding accepts either."),
+    ]
+}
+
+fn read_ascii85_options(matches: &ArgMatches) -> Ascii85Options {
+    Ascii85Options {
+        variant: if matches.is_present("z85") {
+            Ascii85Variant::Z85
+        } else {
+            Ascii85Variant::Adobe
+        },
+        delimiters: matches.is_present("delimiters"),
+    }
+}
+
+fn morse_args() -> [Arg<'static, 'static>; 4] {
+    [
+        Arg::with_name("dot").long("dot").takes_value(true).default_value("."),
+        Arg::with_name("dash").long("dash").takes_value(true).default_value("-"),
+        Arg::with_name("letter-separator")
+            .long("letter-separator")
+            .takes_value(true)
+            .default_value(" "),
+        Arg::with_name("word-separator")
+            .long("word-separator")
+            .takes_value(true)
+            .default_value(" / "),
+    ]
+}
+
+fn read_morse_options(matches: &ArgMatches) -> Result<MorseOptions, String> {
+    let symbol = |name: &str| {
+        let value = matches.value_of(name).unwrap();
+        let mut chars = value.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Ok(c),
+            _ => Err(format!("invalid {}: it must be one character", name)),
+        }
+    };
+    let options = MorseOptions {
+        dot: symbol("dot")?,
+        dash: symbol("dash")?,
+        letter_separator: String::from(matches.value_of("letter-separator").unwrap()),
+        word_separator: String::from(matches.value_of("word-separator").unwrap()),
+    };
+    match morse::options_error(&options) {
+        Some(e) => Err(format!("invalid options: {}", e)),
+        None => Ok(options),
+    }
+}
+
+fn keying_args() -> [Arg<'static, 'static>; 4] {
+    [
+        Arg::with_name("wpm")
+            .long("wpm")
+            .takes_value(true)
+            .default_value("20")
+            .help("The speed of each letter, in words per minute"),
+        Arg::with_name("farnsworth")
+            .long("farnsworth")
+            .takes_value(true)
+            .help("Slows the overall speed to this many words per minute by stretching the gaps"),
+        Arg::with_name("frequency")
+            .long("frequency")
+            .takes_value(true)
+            .default_value("600")
+            .help("The pitch of the tone, in hertz"),
+        Arg::with_name("sample-rate")
+            .long("sample-rate")
+            .takes_value(true)
+            .default_value("8000"),
+    ]
+}
+
+fn read_keying(matches: &ArgMatches) -> Result<Keying, String> {
+    let number = |name: &str, value: &str| match value.parse::<u32>() {
+        Ok(x) if x > 0 => Ok(x),
+        _ => Err(format!("invalid {}: it must be a whole number above 0", name)),
+    };
+    let wpm = number("wpm", matches.value_of("wpm").unwrap())?;
+    Ok(Keying {
+        wpm,
+        farnsworth_wpm: match matches.value_of("farnsworth") {
+            Some(value) => number("farnsworth", value)?,
+            None => wpm,
+        },
+        frequency: number("frequency", matches.value_of("frequency").unwrap())?,
+        sample_rate: number("sample-rate", matches.value_of("sample-rate").unwrap())?,
+    })
+}
+
+fn hex_output_arg() -> Arg<'static, 'static> {
+    Arg::with_name("hex").long("hex").help("Prints the decoded bytes as hex")
+}
+
+/// Shows decoded bytes as text, or as hex if `--hex` was given.
+fn decoded_output(bytes: Vec<u8>, matches: &ArgMatches) -> Result<String, String> {
+    if matches.is_present("hex") {
+        return Ok(hex::encode_bytes(&bytes, &HexOptions::SPACED));
+    }
+    String::from_utf8(bytes)
+        .map_err(|_| String::from("invalid text: the decoded bytes aren't UTF-8, so use --hex to see them"))
+}
+
+fn run(matches: &ArgMatches) -> Result<String, String> {
+    match matches.subcommand() {
+        ("shift", Some(matches)) => match matches.subcommand() {
+            ("crack", Some(matches)) => Ok(shift::crack(&read_text(matches)?, &read_alphabet(matches)?)
+                .iter()
+                .map(|candidate| format!("{}\t{:.1}\t{}", candidate.count, candidate.score, candidate.plaintext))
+                .collect::<Vec<_>>()
+                .join("\n")),
+            (_, Some(sub_matches)) => {
+                let cipher = Shift {
+                    alphabet: read_alphabet(sub_matches)?,
+                    options: read_text_options(sub_matches),
+                };
+                run_cipher(&cipher, matches, |key| {
+                    key.parse()
+                        .ok()
+                        .filter(|key| cipher.is_valid_key(key))
+                        .ok_or_else(|| {
+                            format!("invalid key: must be a number from 0 to {}", cipher.alphabet.len() - 1)
+                        })
+                })
+            },
+            _ => unreachable!(),
+        },
+        ("vigenere", Some(matches)) => match matches.subcommand() {
+            ("crack", Some(matches)) => {
+                let max_len = matches
+                    .value_of("max-length")
+                    .unwrap()
+                    .parse()
+                    .map_err(|_| String::from("invalid max length: must be a number"))?;
+                let cipher = read_vigenere(matches)?;
+                Ok(vigenere::crack(&read_text(matches)?, max_len, &cipher)
+                    .ok_or_else(|| format!("the {} variant can't be cracked", cipher.variant.name()))?
+                    .candidates
+                    .iter()
+                    .map(|candidate| format!("{}\t{:.1}\t{}", candidate.key, candidate.score, candidate.plaintext))
+                    .collect::<Vec<_>>()
+                    .join("\n"))
+            },
+            (_, Some(sub_matches)) => {
+                let cipher = read_vigenere(sub_matches)?;
+                run_cipher(&cipher, matches, |key| {
+                    let error = match cipher.variant {
+                        Variant::Gronsfeld => "invalid key: must be digits",
+                        Variant::Porta if !cipher.alphabet.len().is_multiple_of(2) => {
+                            "invalid alphabet: Porta needs an even number of letters"
+                        },
+                        _ => "invalid key: must be letters from the alphabet",
+                    };
+                    Some(String::from(key))
+                        .filter(|key| cipher.is_valid_key(key))
+                        .ok_or_else(|| String::from(error))
+                })
+            },
+            _ => unreachable!(),
+        },
+        ("affine", Some(matches)) => match matches.subcommand() {
+            ("crack", Some(matches)) => Ok(affine::crack(&read_text(matches)?)
+                .iter()
+                .map(|candidate| format!("{}\t{:.1}\t{}", candidate.key, candidate.score, candidate.plaintext))
+                .collect::<Vec<_>>()
+                .join("\n")),
+            _ => run_cipher(&Affine, matches, |key| affine::parse_key(key).map_err(|e| e.to_string())),
+        },
+        ("substitution", Some(matches)) => match matches.subcommand() {
+            ("crack", Some(matches)) => {
+                let restarts = matches
+                    .value_of("restarts")
+                    .unwrap()
+                    .parse()
+                    .map_err(|_| String::from("invalid restarts: must be a number"))?;
+                let candidate = substitution::crack(&read_text(matches)?, restarts);
+                Ok(format!("{}\t{:.2}\t{}", candidate.key, candidate.score, candidate.plaintext))
+            },
+            (_, Some(sub_matches)) if sub_matches.is_present("keyword") => {
+                let key = substitution::keyword_alphabet(sub_matches.value_of("keyword").unwrap());
+                run_cipher(&Substitution, matches, |_| Ok(key.clone()))
+            },
+            _ => run_cipher(&Substitution, matches, |key| {
+                Some(String::from(key))
+                    .filter(|key| Substitution.is_valid_key(key))
+                    .ok_or_else(|| String::from("invalid key: must use each letter exactly once"))
+            }),
+        },
+        ("playfair", Some(matches)) => {
+            let (_, sub_matches) = matches.subcommand();
+            let sub_matches = sub_matches.unwrap();
+            let filler = sub_matches.value_of("filler").unwrap();
+            let merge = if sub_matches.is_present("omit-q") {
+                Merge::OmitQ
+            } else {
+                Merge::JIntoI
+            };
+            run_cipher(&Playfair, matches, |keyword| {
+                let key = PlayfairKey {
+                    keyword: String::from(keyword),
+                    merge,
+                    filler: filler.chars().next().unwrap_or('X'),
+                };
+                Some(key)
+                    .filter(|key| filler.chars().count() == 1 && Playfair.is_valid_key(key))
+                    .ok_or_else(|| String::from("invalid filler: must be one letter that is in the square"))
+            })
+        },
+        ("hill", Some(matches)) => {
+            let (_, sub_matches) = matches.subcommand();
+            let sub_matches = sub_matches.unwrap();
+            let size = match sub_matches.value_of("size") {
+                Some(size) => Some(
+                    size.parse::<usize>()
+                        .map_err(|_| String::from("invalid size: must be a number"))?,
+                ),
+                None => None,
+            };
+            if let ("find-key", Some(sub_matches)) = matches.subcommand() {
+                if let Some(size) = size {
+                    let plaintext = read_arg_or_file(sub_matches, "plaintext", "plaintext-file")?;
+                    let ciphertext = read_arg_or_file(sub_matches, "ciphertext", "ciphertext-file")?;
+                    return hill::find_key(&plaintext, &ciphertext, size)
+                        .map(|key| key.to_string())
+                        .map_err(|e| e.to_string());
+                }
+            }
+            let keyword = sub_matches.value_of("keyword");
+            run_cipher(&Hill, matches, |key| {
+                let key = match keyword {
+                    Some(keyword) => Matrix::from_keyword(keyword, size.unwrap_or(2))
+                        .ok_or_else(|| String::from("invalid keyword: too short to fill the matrix"))?,
+                    None => Matrix::parse(key)
+                        .ok_or_else(|| String::from("invalid key: must be rows of numbers, like 3,3;2,5"))?,
+                };
+                match hill::key_error(&key) {
+                    Some(error) => Err(format!("invalid key: {}", error)),
+                    None => Ok(key),
+                }
+            })
+        },
+        ("enigma", Some(matches)) => match matches.subcommand() {
+            ("encrypt", Some(matches)) | ("decrypt", Some(matches)) => {
+                let key = enigma_key(matches)?;
+                enigma::encrypt(&read_text(matches)?, &key).map_err(|e| e.to_string())
+            },
+            _ => unreachable!(),
+        },
+        ("rail-fence", Some(matches)) => {
+            run_cipher(&RailFence, matches, |key| rail_fence::parse_key(key).map_err(|e| e.to_string()))
+        },
+        ("columnar", Some(matches)) => {
+            let (_, sub_matches) = matches.subcommand();
+            let padding = sub_matches.unwrap().value_of("padding");
+            if padding.is_some_and(|padding| padding.chars().count() != 1) {
+                return Err(String::from("invalid padding: must be one character"));
+            }
+            let padding = padding.and_then(|padding| padding.chars().next());
+            run_cipher(&Columnar, matches, |key| columnar::parse_key(key, padding).map_err(|e| e.to_string()))
+        },
+        ("base64", Some(matches)) => match matches.subcommand() {
+            ("encode", Some(matches)) => Ok(base64::encode_bytes(&read_bytes(matches)?, &read_base64_options(matches))),
+            ("decode", Some(matches)) => {
+                let bytes = base64::decode_bytes(&read_text(matches)?, &read_base64_options(matches))
+                    .map_err(|e| format!("invalid Base64 {}", e))?;
+                decoded_output(bytes, matches)
+            },
+            _ => unreachable!(),
+        },
+        ("hex", Some(matches)) => match matches.subcommand() {
+            ("encode", Some(matches)) => Ok(hex::encode_bytes(&read_bytes(matches)?, &read_hex_options(matches))),
+            ("decode", Some(matches)) => {
+                let bytes = hex::decode_bytes(&read_text(matches)?).map_err(|e| format!("invalid hex {}", e))?;
+                decoded_output(bytes, matches)
+            },
+            _ => unreachable!(),
+        },
+        ("base32", Some(matches)) => match matches.subcommand() {
+            ("encode", Some(matches)) => Ok(base32::encode_bytes(&read_bytes(matches)?, &read_base32_options(matches))),
+            ("decode", Some(matches)) => {
+                let bytes = base32::decode_bytes(&read_text(matches)?, &read_base32_options(matches))
+                    .map_err(|e| format!("invalid Base32 {}", e))?;
+                decoded_output(bytes, matches)
+            },
+            _ => unreachable!(),
+        },
+        ("base58", Some(matches)) => match matches.subcommand() {
+            ("encode", Some(matches)) => Ok(base58::encode_bytes(&read_bytes(matches)?, matches.is_present("check"))),
+            ("decode", Some(matches)) => {
+                let bytes = base58::decode_bytes(&read_text(matches)?, matches.is_present("check"))
+                    .map_err(|e| format!("invalid Base58 {}", e))?;
+                decoded_output(bytes, matches)
+            },
+            _ => unreachable!(),
+        },
+        ("ascii85", Some(matches)) => match matches.subcommand() {
+            ("encode", Some(matches)) => ascii85::encode_bytes(&read_bytes(matches)?, &read_ascii85_options(matches))
+                .ok_or_else(|| String::from("invalid text: Z85 can only encode a multiple of four bytes")),
+            ("decode", Some(matches)) => {
+                let options = read_ascii85_options(matches);
+                let bytes = ascii85::decode_bytes(&read_text(matches)?, &options)
+                    .map_err(|e| format!("invalid {} {}", options.variant.name(), e))?;
+                decoded_output(bytes, matches)
+            },
+            _ => unreachable!(),
+        },
+        ("morse", Some(matches)) => match matches.subcommand() {
+            ("encode", Some(matches)) => {
+                morse::encode(&read_text(matches)?, &read_morse_options(matches)?).map_err(|e| format!("invalid text {}", e))
+            },
+            ("decode", Some(matches)) => {
+                morse::decode(&read_text(matches)?, &read_morse_options(matches)?).map_err(|e| format!("invalid Morse {}", e))
+            },
+            ("to-wav", Some(matches)) => {
+                let options = read_morse_options(matches)?;
+                let code = morse::encode(&read_text(matches)?, &options).map_err(|e| format!("invalid text {}", e))?;
+                let wav = morse::to_audio(&code, &options, &read_keying(matches)?).map_err(|e| e.to_string())?;
+                let path = matches.value_of("output").unwrap();
+                fs::write(path, wav.to_bytes()).map_err(|e| format!("could not write {}: {}", path, e))?;
+                Ok(code)
+            },
+            ("from-wav", Some(matches)) => {
+                let options = read_morse_options(matches)?;
+                let path = matches.value_of("file").unwrap();
+                let bytes = fs::read(path).map_err(|e| format!("could not read {}: {}", path, e))?;
+                let wav = Wav::from_bytes(&bytes).map_err(|e| format!("invalid WAV file: {}", e))?;
+                let code = morse::from_audio(&wav, &options).map_err(|e| format!("could not hear any Morse: {}", e))?;
+                if matches.is_present("morse") {
+                    return Ok(code);
+                }
+                morse::decode(&code, &options).map_err(|e| format!("invalid Morse {}, heard as {}", e, code))
+            },
+            _ => unreachable!(),
+        },
+        ("identify", Some(matches)) => {
+            let text = read_text(matches)?;
+            let statistics = identify::statistics(&text);
+            let mut lines = identify::guess(&text, &statistics)
+                .iter()
+                .map(|guess| format!("{:>3.0}%  {}: {}", guess.score * 100.0, guess.family.name(), guess.reason))
+                .collect::<Vec<_>>();
+            if lines.is_empty() {
+                lines.push(String::from("No guesses"));
+            }
+            if matches.is_present("statistics") {
+                lines.push(format!("{:#?}", statistics));
+            }
+            Ok(lines.join("\n"))
+        },
+        ("recipe", Some(matches)) => {
+            let recipe = Recipe::parse(&read_file(matches.value_of("recipe").unwrap())?)
+                .map_err(|e| format!("invalid recipe: {}", e))?;
+            let outputs = recipe.run(&read_text(matches)?);
+            let mut lines = Vec::new();
+            for (i, output) in outputs.into_iter().enumerate() {
+                let output = output.map_err(|e| e.to_string())?;
+                if matches.is_present("steps") || i + 1 == recipe.steps.len() {
+                    lines.push(output);
+                }
+            }
+            Ok(lines.join("\n"))
+        },
+        _ => unreachable!(),
+    }
+}
+
+fn run_cipher<C, F>(cipher: &C, matches: &ArgMatches, parse_key: F) -> Result<String, String>
+where
+    C: Cipher,
+    C::Key: ToString,
+    F: Fn(&str) -> Result<C::Key, String>,
+{
+    match matches.subcommand() {
+        ("encrypt", Some(matches)) => {
+            // `--key` is only optional when `parse_key` gets the key some other way.
+            let key = parse_key(matches.value_of("key").unwrap_or_default())?;
+            cipher
+                .encrypt(&read_text(matches)?, &key)
+                .map_err(|e| e.to_string())
+        },
+        ("decrypt", Some(matches)) => {
+            let key = parse_key(matches.value_of("key").unwrap_or_default())?;
+            cipher
+                .decrypt(&read_text(matches)?, &key)
+                .map_err(|e| e.to_string())
+        },
+        ("find-key", Some(matches)) => {
+            let plaintext = read_arg_or_file(matches, "plaintext", "plaintext-file")?;
+            let ciphertext = read_arg_or_file(matches, "ciphertext", "ciphertext-file")?;
+            cipher
+                .find_key(&plaintext, &ciphertext)
+                .map(|key| key.to_string())
+                .map_err(|e| e.to_string())
+        },
+        _ => unreachable!(),
+    }
+}
+
+/// Reads the text to transform from the positional argument, `--input` or stdin, in that order.
+fn read_text(matches: &ArgMatches) -> Result<String, String> {
+    if let Some(text) = matches.value_of("text") {
+        return Ok(String::from(text));
+    }
+    if let Some(path) = matches.value_of("input") {
+        return read_file(path);
+    }
+    let mut text = String::new();
+    io::stdin()
+        .read_to_string(&mut text)
+        .map_err(|e| format!("could not read stdin: {}", e))?;
+    Ok(strip_newline(text))
+}
+
+/// Like [`read_text`], but binary-safe. Files and stdin are read exactly as
+/// they are, trailing newline and all, so that encoding them matches tools
+/// like `base64`. Only the positional argument is taken as text.
+fn read_bytes(matches: &ArgMatches) -> Result<Vec<u8>, String> {
+    if let Some(text) = matches.value_of("text") {
+        return Ok(text.as_bytes().to_vec());
+    }
+    match matches.value_of("input") {
+        Some(path) => fs::read(path).map_err(|e| format!("could not read {}: {}", path, e)),
+        None => {
+            let mut bytes = Vec::new();
+            io::stdin()
+                .read_to_end(&mut bytes)
+                .map_err(|e| format!("could not read stdin: {}", e))?;
+            Ok(bytes)
+        },
+    }
+}
+
+fn read_arg_or_file(matches: &ArgMatches, arg: &str, file_arg: &str) -> Result<String, String> {
+    match matches.value_of(arg) {
+        Some(text) => Ok(String::from(text)),
+        None => read_file(matches.value_of(file_arg).unwrap()),
+    }
+}
+
+fn read_file(path: &str) -> Result<String, String> {
+    fs::read_to_string(path)
+        .map(strip_newline)
+        .map_err(|e| format!("could not read {}: {}", path, e))
+}
+
+/// Drops the trailing newline that files and piped input usually end with, so
+/// that it doesn't end up in the output twice.
+fn strip_newline(mut text: String) -> String {
+    if text.ends_with('\n') {
+        text.pop();
+        if text.ends_with('\r') {
+            text.pop();
+        }
+    }
+    text
+}
@@ -0,0 +1,94 @@
+use crate::encoding::DecodeError;
+use std::fmt;
+
+/// Why a cipher couldn't encrypt, decrypt or find a key. Indices count
+/// characters from 0.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CipherError {
+    /// The key can't be used, for the reason given.
+    InvalidKey(String),
+    /// The character at this index of the key can't be used.
+    InvalidKeyChar(usize),
+    /// The text can't be transformed with this key, for the reason given.
+    InvalidText(String),
+    /// The text isn't valid in this encoding.
+    Decode(DecodeError),
+    /// One of the plaintext and ciphertext runs out before the other.
+    MismatchedLengths,
+    /// The letter at this index of the plaintext is a different case in the
+    /// ciphertext.
+    MismatchedCase(usize),
+    /// The character at this index of the plaintext isn't a letter, and the
+    /// ciphertext has something else in its place.
+    NonAlphabeticMismatch(usize),
+    /// No key takes the letter at this index of the plaintext to the one in
+    /// the ciphertext, along with the letters before it.
+    InconsistentKey(usize),
+    /// No key encrypts the plaintext to the ciphertext.
+    NoKey,
+    /// The cipher has no way of finding keys.
+    Unsupported,
+}
+
+impl fmt::Display for CipherError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CipherError::InvalidKey(reason) => write!(f, "invalid key: {}", reason),
+            CipherError::InvalidKeyChar(index) => write!(f, "invalid key character at position {}", index + 1),
+            CipherError::InvalidText(reason) => write!(f, "invalid text: {}", reason),
+            CipherError::Decode(e) => match e.position {
+                Some(position) => write!(f, "invalid text at character {}: {}", position + 1, e.reason),
+                None => write!(f, "invalid text: {}", e.reason),
+            },
+            CipherError::MismatchedLengths => write!(f, "the plaintext and ciphertext have different lengths"),
+            CipherError::MismatchedCase(index) => write!(f, "mismatched case at character {}", index + 1),
+            CipherError::NonAlphabeticMismatch(index) => {
+                write!(f, "non-alphabetic mismatch at character {}", index + 1)
+            },
+            CipherError::InconsistentKey(index) => write!(f, "no key fits the letter at character {}", index + 1),
+            CipherError::NoKey => write!(f, "no key encrypts the plaintext to the ciphertext"),
+            CipherError::Unsupported => write!(f, "keys can't be found for this cipher"),
+        }
+    }
+}
+
+impl From<DecodeError> for CipherError {
+    fn from(e: DecodeError) -> Self {
+        CipherError::Decode(e)
+    }
+}
+
+/// Turns decoded bytes back into text.
+pub(crate) fn utf8(bytes: Vec<u8>) -> Result<String, CipherError> {
+    String::from_utf8(bytes).map_err(|_| CipherError::InvalidText(String::from("the decoded bytes aren't UTF-8 text")))
+}
+
+/// Finds the key for an encoding, which has none, so this only checks that
+/// the plaintext was `encoded` as the ciphertext.
+pub(crate) fn match_encoding(encoded: &str, ciphertext: &str) -> Result<(), CipherError> {
+    if encoded == ciphertext {
+        Ok(())
+    } else {
+        Err(CipherError::NoKey)
+    }
+}
+
+/// A reversible transformation of text under a key.
+///
+/// Every tool in this crate implements this, so that callers can drive any of
+/// them the same way.
+pub trait Cipher {
+    type Key;
+
+    /// Returns whether `key` can be used to encrypt or decrypt.
+    fn is_valid_key(&self, key: &Self::Key) -> bool;
+
+    /// Fails if the key is invalid.
+    fn encrypt(&self, plaintext: &str, key: &Self::Key) -> Result<String, CipherError>;
+
+    /// Fails if the key is invalid or the ciphertext is malformed.
+    fn decrypt(&self, ciphertext: &str, key: &Self::Key) -> Result<String, CipherError>;
+
+    /// Returns the key that encrypts `plaintext` to `ciphertext`, if there is one.
+    fn find_key(&self, plaintext: &str, ciphertext: &str) -> Result<Self::Key, CipherError>;
+}
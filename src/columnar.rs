@@ -0,0 +1,199 @@
+use crate::{shift::letter_index, Cipher, CipherError};
+use std::fmt;
+
+/// Keyed columnar transposition, which writes the text in rows under a
+/// keyword and reads the columns off in the keyword's alphabetical order.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Columnar;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ColumnarKey {
+    pub keyword: String,
+    /// If there is one, the text is transposed again under this keyword.
+    pub second_keyword: Option<String>,
+    /// What to fill the last row with, so that every column is the same length.
+    /// Without it, the grid is irregular. Only the first grid is padded, since
+    /// otherwise there'd be no telling the two lots of padding apart when
+    /// decrypting.
+    pub padding: Option<char>,
+}
+
+impl fmt::Display for ColumnarKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.second_keyword {
+            Some(second) => write!(f, "{},{}", self.keyword, second),
+            None => write!(f, "{}", self.keyword),
+        }
+    }
+}
+
+/// Parses the keywords written as `KEYWORD,SECOND`, the way keys are
+/// displayed, or just `KEYWORD` to transpose once.
+pub fn parse_key(keywords: &str, padding: Option<char>) -> Result<ColumnarKey, CipherError> {
+    let mut keywords = keywords.split(',').map(String::from);
+    let key = ColumnarKey {
+        keyword: keywords.next().unwrap(),
+        second_keyword: keywords.next(),
+        padding,
+    };
+    if keywords.next().is_some() {
+        return Err(CipherError::InvalidKey(String::from("there can be at most two keywords")));
+    }
+    Ok(key)
+}
+
+/// The order each column is read in, from the alphabetical order of the letters
+/// of `keyword`. Repeated letters are read left to right.
+pub fn column_ranks(keyword: &str) -> Option<Vec<usize>> {
+    let letters = keyword.chars().filter_map(letter_index).collect::<Vec<_>>();
+    if letters.is_empty() {
+        return None;
+    }
+    let mut columns = (0..letters.len()).collect::<Vec<_>>();
+    columns.sort_by_key(|&column| letters[column]);
+    let mut ranks = vec![0; letters.len()];
+    for (rank, column) in columns.into_iter().enumerate() {
+        ranks[column] = rank;
+    }
+    Some(ranks)
+}
+
+/// Fills out the last row with `padding`, if there is any.
+pub fn pad(text: &str, columns: usize, padding: Option<char>) -> String {
+    let mut text = text.to_string();
+    if let Some(padding) = padding {
+        let len = text.chars().count();
+        for _ in 0..(columns - len % columns) % columns {
+            text.push(padding);
+        }
+    }
+    text
+}
+
+/// For each character of the transposed text, where it came from in the
+/// text written into the grid.
+pub fn read_order(len: usize, ranks: &[usize]) -> Vec<usize> {
+    let mut columns = (0..ranks.len()).collect::<Vec<_>>();
+    columns.sort_by_key(|&column| ranks[column]);
+    columns
+        .into_iter()
+        .flat_map(|column| (column..len).step_by(ranks.len()))
+        .collect()
+}
+
+fn ranks(keyword: &str) -> Result<Vec<usize>, CipherError> {
+    column_ranks(keyword)
+        .ok_or_else(|| CipherError::InvalidKey(String::from("each keyword needs at least one letter")))
+}
+
+fn encrypt_once(text: &str, keyword: &str, padding: Option<char>) -> Result<String, CipherError> {
+    let ranks = ranks(keyword)?;
+    let chars = pad(text, ranks.len(), padding).chars().collect::<Vec<_>>();
+    Ok(read_order(chars.len(), &ranks).into_iter().map(|i| chars[i]).collect())
+}
+
+fn decrypt_once(text: &str, keyword: &str, padding: Option<char>) -> Result<String, CipherError> {
+    let ranks = ranks(keyword)?;
+    let chars = text.chars().collect::<Vec<_>>();
+    // A regular grid has no short columns to work out.
+    if padding.is_some() && chars.len() % ranks.len() != 0 {
+        return Err(CipherError::InvalidText(String::from("a regular grid must be completely full")));
+    }
+    let mut plaintext = vec![' '; chars.len()];
+    for (&i, &c) in read_order(chars.len(), &ranks).iter().zip(chars.iter()) {
+        plaintext[i] = c;
+    }
+    Ok(plaintext.into_iter().collect())
+}
+
+pub fn encrypt(plaintext: &str, key: &ColumnarKey) -> Result<String, CipherError> {
+    let once = encrypt_once(plaintext, &key.keyword, key.padding)?;
+    match &key.second_keyword {
+        Some(second) => encrypt_once(&once, second, None),
+        None => Ok(once),
+    }
+}
+
+/// Decrypts `ciphertext`. Any padding is left on the end.
+pub fn decrypt(ciphertext: &str, key: &ColumnarKey) -> Result<String, CipherError> {
+    let once = match &key.second_keyword {
+        Some(second) => decrypt_once(ciphertext, second, None)?,
+        None => ciphertext.to_string(),
+    };
+    decrypt_once(&once, &key.keyword, key.padding)
+}
+
+/// The most columns that [`find_key`] tries.
+pub const MAX_FIND_COLUMNS: usize = 26;
+
+/// Finds a single transposition, without padding, that turns `plaintext` into
+/// `ciphertext`. The keyword found is the alphabet in the order the columns
+/// are read.
+pub fn find_key(plaintext: &str, ciphertext: &str) -> Result<ColumnarKey, CipherError> {
+    let plain = plaintext.chars().collect::<Vec<_>>();
+    let cipher = ciphertext.chars().collect::<Vec<_>>();
+    if plain.len() != cipher.len() {
+        return Err(CipherError::MismatchedLengths);
+    }
+    if plain.is_empty() {
+        return Err(CipherError::InvalidText(String::from("there's no text to find the key from")));
+    }
+    (1..=MAX_FIND_COLUMNS.min(plain.len())).find_map(|width| {
+        let columns = (0..width)
+            .map(|column| plain.iter().skip(column).step_by(width).copied().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let mut ranks = vec![None; width];
+        if !match_columns(&columns, &cipher, 0, &mut ranks) {
+            return None;
+        }
+        let keyword = ranks
+            .iter()
+            .map(|rank| std::char::from_u32(rank.unwrap() as u32 + 65).unwrap())
+            .collect();
+        Some(ColumnarKey {
+            keyword,
+            second_keyword: None,
+            padding: None,
+        })
+    })
+    .ok_or(CipherError::NoKey)
+}
+
+/// Works out which column is read next, given that `rank` columns have been
+/// read so far, backtracking when more than one column fits.
+fn match_columns(columns: &[Vec<char>], cipher: &[char], rank: usize, ranks: &mut [Option<usize>]) -> bool {
+    if rank == columns.len() {
+        return cipher.is_empty();
+    }
+    for (column, text) in columns.iter().enumerate() {
+        if ranks[column].is_none() && cipher.starts_with(text) {
+            ranks[column] = Some(rank);
+            if match_columns(columns, &cipher[text.len()..], rank + 1, ranks) {
+                return true;
+            }
+            ranks[column] = None;
+        }
+    }
+    false
+}
+
+impl Cipher for Columnar {
+    type Key = ColumnarKey;
+
+    fn is_valid_key(&self, key: &ColumnarKey) -> bool {
+        column_ranks(&key.keyword).is_some()
+            && key.second_keyword.as_ref().is_none_or(|second| column_ranks(second).is_some())
+    }
+
+    fn encrypt(&self, plaintext: &str, key: &ColumnarKey) -> Result<String, CipherError> {
+        encrypt(plaintext, key)
+    }
+
+    fn decrypt(&self, ciphertext: &str, key: &ColumnarKey) -> Result<String, CipherError> {
+        decrypt(ciphertext, key)
+    }
+
+    fn find_key(&self, plaintext: &str, ciphertext: &str) -> Result<ColumnarKey, CipherError> {
+        find_key(plaintext, ciphertext)
+    }
+}
@@ -0,0 +1,65 @@
+"""Counts the quadgrams in quadgrams.txt from the prose of the documentation
+that ships with Rust (the standard library docs and the books listed below),
+which is licensed under MIT or Apache-2.0. quadgrams.txt was made from the docs
+for Rust 1.95.0 with
+
+    python3 quadgrams.py "$(rustc --print sysroot)/share/doc/rust/html" > quadgrams.txt
+"""
+
+import collections
+import glob
+import re
+import sys
+from html.parser import HTMLParser
+
+BOOKS = ['std', 'book', 'nomicon', 'reference', 'rust-by-example', 'edition-guide', 'cargo', 'rustc']
+
+
+class Prose(HTMLParser):
+    """Collects the text of paragraphs and list items, leaving out code."""
+
+    def __init__(self):
+        super().__init__()
+        self.in_code = 0
+        self.in_prose = 0
+        self.text = []
+
+    def handle_starttag(self, tag, attrs):
+        if tag in ('pre', 'code', 'script', 'style', 'nav'):
+            self.in_code += 1
+        if tag in ('p', 'li'):
+            self.in_prose += 1
+
+    def handle_endtag(self, tag):
+        if tag in ('pre', 'code', 'script', 'style', 'nav'):
+            self.in_code -= 1
+        if tag in ('p', 'li'):
+            self.in_prose -= 1
+            self.text.append(' ')
+
+    def handle_data(self, data):
+        if self.in_prose and not self.in_code:
+            self.text.append(data)
+
+
+def main(root):
+    files = [f for book in BOOKS for f in sorted(glob.glob(f'{root}/{book}/**/*.html', recursive=True))]
+    # Skip whole-book pages, translations and the old editions of the book.
+    files = [
+        f for f in files
+        if not f.endswith(('print.html', '404.html', 'toc.html'))
+        and not re.search(r'/(ja|ko|es|zh|2018-edition|first-edition|second-edition)/', f)
+    ]
+    text = []
+    for f in files:
+        parser = Prose()
+        parser.feed(open(f, encoding='utf-8').read())
+        text.append(''.join(parser.text))
+    letters = re.sub('[^a-z]', '', ' '.join(text).lower())
+    counts = collections.Counter(letters[i:i + 4] for i in range(len(letters) - 3))
+    for quadgram, count in sorted(counts.items(), key=lambda item: (-item[1], item[0])):
+        print(quadgram.upper(), count)
+
+
+if __name__ == '__main__':
+    main(sys.argv[1])
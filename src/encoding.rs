@@ -0,0 +1,43 @@
+use std::fmt;
+
+/// Why some text couldn't be decoded, and where.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DecodeError {
+    /// The index of the character at fault, counting from 0, if the problem
+    /// is with one character rather than the text as a whole.
+    pub position: Option<usize>,
+    pub reason: String,
+}
+
+impl DecodeError {
+    pub(crate) fn at(position: usize, reason: impl Into<String>) -> Self {
+        DecodeError {
+            position: Some(position),
+            reason: reason.into(),
+        }
+    }
+
+    pub(crate) fn whole(reason: impl Into<String>) -> Self {
+        DecodeError {
+            position: None,
+            reason: reason.into(),
+        }
+    }
+}
+
+/// Reads on from the name of the encoding, as in "invalid Base64 at
+/// character 3: ..." or "invalid Base58 text: ...".
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.position {
+            Some(position) => write!(f, "at character {}: {}", position + 1, self.reason),
+            None => write!(f, "text: {}", self.reason),
+        }
+    }
+}
+
+/// The characters of `text` that aren't whitespace, along with their indices,
+/// so that errors can point at the right character.
+pub(crate) fn without_whitespace(text: &str) -> Vec<(usize, char)> {
+    text.chars().enumerate().filter(|(_, c)| !c.is_whitespace()).collect()
+}
@@ -0,0 +1,331 @@
+use crate::{shift::letter_index, Cipher, CipherError};
+
+/// The Enigma machine. Encrypting and decrypting are the same thing, since
+/// the reflector makes every setting its own inverse.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Enigma;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Model {
+    /// The army and air force Enigma I, with rotors I to V.
+    EnigmaI,
+    /// The naval M3, which added rotors VI to VIII.
+    M3,
+    /// The naval M4, with a fourth rotor that doesn't step next to a thin
+    /// reflector.
+    M4,
+}
+
+pub struct RotorSpec {
+    pub name: &'static str,
+    wiring: &'static [u8; 26],
+    /// The letters in the window when this rotor turns the next one over.
+    notches: &'static [u8],
+}
+
+/// Rotors I to VIII, followed by the M4's Beta and Gamma, which have no notches.
+pub const ROTORS: [RotorSpec; 10] = [
+    RotorSpec { name: "I", wiring: b"EKMFLGDQVZNTOWYHXUSPAIBRCJ", notches: b"Q" },
+    RotorSpec { name: "II", wiring: b"AJDKSIRUXBLHWTMCQGZNPYFVOE", notches: b"E" },
+    RotorSpec { name: "III", wiring: b"BDFHJLCPRTXVZNYEIWGAKMUSQO", notches: b"V" },
+    RotorSpec { name: "IV", wiring: b"ESOVPZJAYQUIRHXLNFTGKDCMWB", notches: b"J" },
+    RotorSpec { name: "V", wiring: b"VZBRGITYUPSDNHLXAWMJQOFECK", notches: b"Z" },
+    RotorSpec { name: "VI", wiring: b"JPGVOUMFYQBENHZRDKASXLICTW", notches: b"ZM" },
+    RotorSpec { name: "VII", wiring: b"NZJHGRCXMYSWBOUFAIVLPEKQDT", notches: b"ZM" },
+    RotorSpec { name: "VIII", wiring: b"FKQHTLXOCBJSPDZRAMEWNIUYGV", notches: b"ZM" },
+    RotorSpec { name: "Beta", wiring: b"LEYJVCNIXWPBQMDRTAKZGFUHOS", notches: b"" },
+    RotorSpec { name: "Gamma", wiring: b"FSOKANUERHMBTIYCWLQPZXVGJD", notches: b"" },
+];
+
+/// Where Beta and Gamma are in [`ROTORS`].
+pub const GREEK_ROTORS: [usize; 2] = [8, 9];
+
+pub struct ReflectorSpec {
+    pub name: &'static str,
+    wiring: &'static [u8; 26],
+    thin: bool,
+}
+
+pub const REFLECTORS: [ReflectorSpec; 5] = [
+    ReflectorSpec { name: "A", wiring: b"EJMZALYXVBWFCRQUONTSPIKHGD", thin: false },
+    ReflectorSpec { name: "B", wiring: b"YRUHQSLDPXNGOKMIEBFZCWVJAT", thin: false },
+    ReflectorSpec { name: "C", wiring: b"FVPJIAOYEDRZXWGCTKUQSBNMHL", thin: false },
+    ReflectorSpec { name: "B thin", wiring: b"ENKQAUYWJICOPBLMDXZVFTHRGS", thin: true },
+    ReflectorSpec { name: "C thin", wiring: b"RDOBJNTKVEHMLFCWZAXGYIPSUQ", thin: true },
+];
+
+/// One rotor in the machine, by its index in [`ROTORS`]. The ring setting and
+/// position count from 0 for A.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RotorSetting {
+    pub rotor: usize,
+    pub ring: u32,
+    pub position: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EnigmaKey {
+    pub model: Model,
+    /// The reflector, by its index in [`REFLECTORS`].
+    pub reflector: usize,
+    /// The rotors from left to right: three, or four on the M4.
+    pub rotors: Vec<RotorSetting>,
+    /// Pairs of letters swapped by the plugboard, separated by spaces, like
+    /// `AV BS CG`.
+    pub plugboard: String,
+}
+
+/// Parses the plugboard pairs into the letter each letter is swapped with.
+pub fn parse_plugboard(plugboard: &str) -> Option<[u32; 26]> {
+    let mut swaps = [0; 26];
+    for (x, swap) in swaps.iter_mut().enumerate() {
+        *swap = x as u32;
+    }
+    for pair in plugboard.split_whitespace() {
+        let letters = pair.chars().map(letter_index).collect::<Option<Vec<_>>>()?;
+        match letters[..] {
+            [a, b] if a != b && swaps[a as usize] == a && swaps[b as usize] == b => {
+                swaps[a as usize] = b;
+                swaps[b as usize] = a;
+            },
+            _ => return None,
+        }
+    }
+    Some(swaps)
+}
+
+/// Parses a key from its settings written out as text: the model as `I`, `M3`
+/// or `M4`, the reflector and rotors by name with the rotors separated by
+/// commas, and the rings and positions as a letter for each rotor, all A if
+/// they are left out.
+pub fn parse_key(
+    model: &str,
+    reflector: &str,
+    rotors: &str,
+    rings: Option<&str>,
+    positions: Option<&str>,
+    plugboard: &str,
+) -> Result<EnigmaKey, CipherError> {
+    let model = match model {
+        "I" => Model::EnigmaI,
+        "M3" => Model::M3,
+        "M4" => Model::M4,
+        _ => return Err(CipherError::InvalidKey(String::from("the model must be I, M3 or M4"))),
+    };
+    let reflector_name = reflector.replace('-', " ");
+    let reflector = REFLECTORS
+        .iter()
+        .position(|reflector| reflector.name.eq_ignore_ascii_case(&reflector_name))
+        .ok_or_else(|| CipherError::InvalidKey(String::from("the reflector must be A, B, C, B-thin or C-thin")))?;
+    let rotors = rotors
+        .split(',')
+        .map(|name| {
+            ROTORS
+                .iter()
+                .position(|rotor| rotor.name.eq_ignore_ascii_case(name.trim()))
+                .ok_or_else(|| CipherError::InvalidKey(format!("there is no rotor called {}", name.trim())))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let letters = |letters: Option<&str>, name: &str| -> Result<Vec<u32>, CipherError> {
+        let letters = match letters {
+            Some(letters) => letters.chars().filter_map(letter_index).collect(),
+            None => vec![0; rotors.len()],
+        };
+        if letters.len() == rotors.len() {
+            Ok(letters)
+        } else {
+            Err(CipherError::InvalidKey(format!("the {} must be one letter for each rotor", name)))
+        }
+    };
+    let (rings, positions) = (letters(rings, "rings")?, letters(positions, "positions")?);
+    let key = EnigmaKey {
+        model,
+        reflector,
+        rotors: (0..rotors.len())
+            .map(|i| RotorSetting {
+                rotor: rotors[i],
+                ring: rings[i],
+                position: positions[i],
+            })
+            .collect(),
+        plugboard: String::from(plugboard),
+    };
+    match key_error(&key) {
+        Some(error) => Err(CipherError::InvalidKey(error)),
+        None => Ok(key),
+    }
+}
+
+/// Why a key can't be set up on its model of Enigma, in words.
+pub fn key_error(key: &EnigmaKey) -> Option<String> {
+    let (rotor_count, rotor_limit, reflector_names) = match key.model {
+        Model::EnigmaI => (3, 5, "A, B or C"),
+        Model::M3 => (3, 8, "B or C"),
+        Model::M4 => (4, 8, "B thin or C thin"),
+    };
+    if key.rotors.len() != rotor_count {
+        return Some(format!("this model takes {} rotors", rotor_count));
+    }
+    let reflector = match REFLECTORS.get(key.reflector) {
+        Some(reflector) => reflector,
+        None => return Some(String::from("there is no such reflector")),
+    };
+    let reflector_fits = match key.model {
+        Model::EnigmaI => !reflector.thin,
+        Model::M3 => !reflector.thin && reflector.name != "A",
+        Model::M4 => reflector.thin,
+    };
+    if !reflector_fits {
+        return Some(format!("the reflector must be {}", reflector_names));
+    }
+
+    let (greek, rotors) = key.rotors.split_at(rotor_count - 3);
+    if greek.iter().any(|setting| !GREEK_ROTORS.contains(&setting.rotor)) {
+        return Some(String::from("the leftmost rotor must be Beta or Gamma"));
+    }
+    if rotors.iter().any(|setting| setting.rotor >= rotor_limit) {
+        return Some(format!("the rotors must be I to {}", ROTORS[rotor_limit - 1].name));
+    }
+    for (i, setting) in rotors.iter().enumerate() {
+        if rotors[..i].iter().any(|other| other.rotor == setting.rotor) {
+            return Some(format!("rotor {} can only be used once", ROTORS[setting.rotor].name));
+        }
+    }
+    if key.rotors.iter().any(|setting| setting.ring >= 26 || setting.position >= 26) {
+        return Some(String::from("ring settings and positions must be A to Z"));
+    }
+    if parse_plugboard(&key.plugboard).is_none() {
+        return Some(String::from(
+            "the plugboard must be pairs of letters, like AV BS CG, with no letter used twice",
+        ));
+    }
+    None
+}
+
+/// An Enigma machine partway through a message.
+#[derive(Clone, Debug)]
+pub struct Machine {
+    rotors: Vec<RotorSetting>,
+    reflector: usize,
+    plugboard: [u32; 26],
+}
+
+impl Machine {
+    pub fn new(key: &EnigmaKey) -> Option<Self> {
+        if key_error(key).is_some() {
+            return None;
+        }
+        Some(Machine {
+            rotors: key.rotors.clone(),
+            reflector: key.reflector,
+            plugboard: parse_plugboard(&key.plugboard)?,
+        })
+    }
+
+    /// The letters showing in the rotor windows, from left to right, counting
+    /// from 0 for A.
+    pub fn positions(&self) -> Vec<u32> {
+        self.rotors.iter().map(|setting| setting.position).collect()
+    }
+
+    fn at_notch(&self, rotor: usize) -> bool {
+        let setting = self.rotors[rotor];
+        ROTORS[setting.rotor].notches.contains(&(setting.position as u8 + b'A'))
+    }
+
+    fn advance(&mut self, rotor: usize) {
+        let setting = &mut self.rotors[rotor];
+        setting.position = (setting.position + 1) % 26;
+    }
+
+    /// Steps the rotors as a key is pressed. The middle rotor steps whenever
+    /// it turns over the left one, so it steps twice in a row when the right
+    /// rotor brings it to its notch.
+    fn step(&mut self) {
+        let right = self.rotors.len() - 1;
+        let (middle, left) = (right - 1, right - 2);
+        if self.at_notch(middle) {
+            self.advance(left);
+            self.advance(middle);
+        } else if self.at_notch(right) {
+            self.advance(middle);
+        }
+        self.advance(right);
+    }
+
+    fn through_rotor(&self, rotor: usize, x: u32, backwards: bool) -> u32 {
+        let setting = self.rotors[rotor];
+        let wiring = ROTORS[setting.rotor].wiring;
+        let offset = (setting.position + 26 - setting.ring) % 26;
+        let contact = (x + offset) % 26;
+        let out = if backwards {
+            wiring.iter().position(|&c| (c - b'A') as u32 == contact).unwrap() as u32
+        } else {
+            (wiring[contact as usize] - b'A') as u32
+        };
+        (out + 26 - offset) % 26
+    }
+
+    /// Presses the key for the letter `x`, returning the letter that lights up.
+    pub fn press(&mut self, x: u32) -> u32 {
+        self.step();
+        let mut x = self.plugboard[x as usize];
+        for rotor in (0..self.rotors.len()).rev() {
+            x = self.through_rotor(rotor, x, false);
+        }
+        x = (REFLECTORS[self.reflector].wiring[x as usize] - b'A') as u32;
+        for rotor in 0..self.rotors.len() {
+            x = self.through_rotor(rotor, x, true);
+        }
+        self.plugboard[x as usize]
+    }
+
+    /// Types in `text`, keeping its case. Anything that isn't a letter is left
+    /// as it is, without stepping the rotors.
+    pub fn type_text(&mut self, text: &str) -> String {
+        text.chars()
+            .map(|c| match letter_index(c) {
+                Some(x) => {
+                    let base = if c.is_ascii_uppercase() { 65 } else { 97 };
+                    std::char::from_u32(self.press(x) + base).unwrap()
+                },
+                None => c,
+            })
+            .collect()
+    }
+}
+
+pub fn encrypt(plaintext: &str, key: &EnigmaKey) -> Result<String, CipherError> {
+    if let Some(error) = key_error(key) {
+        return Err(CipherError::InvalidKey(error));
+    }
+    // Any key that passes the check makes a machine.
+    Ok(Machine::new(key).unwrap().type_text(plaintext))
+}
+
+/// Decrypts `ciphertext`, which is the same as encrypting it, since the
+/// reflector makes the machine its own inverse.
+pub fn decrypt(ciphertext: &str, key: &EnigmaKey) -> Result<String, CipherError> {
+    encrypt(ciphertext, key)
+}
+
+impl Cipher for Enigma {
+    type Key = EnigmaKey;
+
+    fn is_valid_key(&self, key: &EnigmaKey) -> bool {
+        key_error(key).is_none()
+    }
+
+    fn encrypt(&self, plaintext: &str, key: &EnigmaKey) -> Result<String, CipherError> {
+        encrypt(plaintext, key)
+    }
+
+    fn decrypt(&self, ciphertext: &str, key: &EnigmaKey) -> Result<String, CipherError> {
+        decrypt(ciphertext, key)
+    }
+
+    /// There are far too many settings to search, so this never finds a key.
+    fn find_key(&self, _plaintext: &str, _ciphertext: &str) -> Result<EnigmaKey, CipherError> {
+        Err(CipherError::Unsupported)
+    }
+}
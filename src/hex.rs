@@ -0,0 +1,113 @@
+use crate::{
+    cipher::{match_encoding, utf8},
+    encoding::{without_whitespace, DecodeError},
+    Cipher,
+    CipherError,
+};
+
+/// Hexadecimal, two digits per byte. There is no key, so the key type is `()`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Hex {
+    pub options: HexOptions,
+}
+
+/// What goes between the bytes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Separator {
+    #[default]
+    None,
+    Space,
+    Colon,
+    Dash,
+}
+
+impl Separator {
+    pub fn text(&self) -> &'static str {
+        match self {
+            Separator::None => "",
+            Separator::Space => " ",
+            Separator::Colon => ":",
+            Separator::Dash => "-",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct HexOptions {
+    pub separator: Separator,
+    /// Use `A` to `F` rather than `a` to `f`.
+    pub upper: bool,
+}
+
+impl HexOptions {
+    /// Lowercase bytes separated by spaces, for showing bytes to people.
+    pub const SPACED: HexOptions = HexOptions {
+        separator: Separator::Space,
+        upper: false,
+    };
+}
+
+pub fn encode_bytes(bytes: &[u8], options: &HexOptions) -> String {
+    bytes
+        .iter()
+        .map(|byte| match options.upper {
+            true => format!("{:02X}", byte),
+            false => format!("{:02x}", byte),
+        })
+        .collect::<Vec<_>>()
+        .join(options.separator.text())
+}
+
+pub fn encode(plaintext: &str, options: &HexOptions) -> String {
+    encode_bytes(plaintext.as_bytes(), options)
+}
+
+/// Reads pairs of hex digits in either case. Whitespace and any of the
+/// separators can go between bytes, but not between the digits of one byte.
+pub fn decode_bytes(hex: &str) -> Result<Vec<u8>, DecodeError> {
+    let mut bytes = Vec::new();
+    let mut high = None;
+    for (position, c) in without_whitespace(hex) {
+        match c.to_digit(16) {
+            Some(x) => match high.take() {
+                Some(high) => bytes.push((high << 4 | x) as u8),
+                None => high = Some(x),
+            },
+            None if ":-".contains(c) => {
+                if high.is_some() {
+                    return Err(DecodeError::at(position, format!("{} splits a byte in half", c)));
+                }
+            },
+            None => return Err(DecodeError::at(position, format!("{} isn't a hex digit", c))),
+        }
+    }
+    if high.is_some() {
+        return Err(DecodeError::at(hex.chars().count(), "the last byte only has one digit"));
+    }
+    Ok(bytes)
+}
+
+/// Returns `None` if the input isn't valid hex or doesn't decode to UTF-8.
+pub fn decode(hex: &str) -> Option<String> {
+    String::from_utf8(decode_bytes(hex).ok()?).ok()
+}
+
+impl Cipher for Hex {
+    type Key = ();
+
+    fn is_valid_key(&self, _key: &()) -> bool {
+        true
+    }
+
+    fn encrypt(&self, plaintext: &str, _key: &()) -> Result<String, CipherError> {
+        Ok(encode(plaintext, &self.options))
+    }
+
+    fn decrypt(&self, ciphertext: &str, _key: &()) -> Result<String, CipherError> {
+        utf8(decode_bytes(ciphertext)?)
+    }
+
+    fn find_key(&self, plaintext: &str, ciphertext: &str) -> Result<(), CipherError> {
+        match_encoding(&encode(plaintext, &self.options), ciphertext)
+    }
+}
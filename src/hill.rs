@@ -0,0 +1,330 @@
+use crate::{
+    affine::mod_inverse,
+    shift::{letter_index, letter_pairs},
+    Cipher,
+    CipherError,
+};
+use std::fmt;
+
+/// The Hill cipher, which encrypts blocks of letters by multiplying them by a
+/// square matrix mod 26.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Hill;
+
+/// The largest key matrix that [`Hill::find_key`](Cipher::find_key) looks for.
+pub const MAX_FIND_SIZE: usize = 5;
+
+/// A square matrix of numbers mod 26, stored row by row.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Matrix {
+    pub size: usize,
+    pub entries: Vec<u32>,
+}
+
+impl fmt::Display for Matrix {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let rows = self
+            .entries
+            .chunks(self.size.max(1))
+            .map(|row| row.iter().map(ToString::to_string).collect::<Vec<_>>().join(","))
+            .collect::<Vec<_>>();
+        write!(f, "{}", rows.join(";"))
+    }
+}
+
+impl Matrix {
+    /// Returns `None` if the matrix would have too many entries to count.
+    pub fn identity(size: usize) -> Option<Self> {
+        let entries = (0..size.checked_mul(size)?).map(|i| (i / size == i % size) as u32).collect();
+        Some(Matrix { size, entries })
+    }
+
+    /// Parses rows separated by semicolons, each of numbers separated by commas,
+    /// like `3,3;2,5`.
+    pub fn parse(text: &str) -> Option<Self> {
+        let rows = text
+            .split(';')
+            .map(|row| {
+                row.split(',')
+                    .map(|x| x.trim().parse::<u32>().ok().map(|x| x % 26))
+                    .collect::<Option<Vec<_>>>()
+            })
+            .collect::<Option<Vec<_>>>()?;
+        let size = rows.len();
+        if rows.iter().any(|row| row.len() != size) {
+            return None;
+        }
+        Some(Matrix {
+            size,
+            entries: rows.concat(),
+        })
+    }
+
+    /// Fills a matrix row by row with the letters of `keyword`, which must have
+    /// at least `size * size` of them.
+    pub fn from_keyword(keyword: &str, size: usize) -> Option<Self> {
+        let area = size.checked_mul(size)?;
+        let entries = keyword.chars().filter_map(letter_index).take(area).collect::<Vec<_>>();
+        if size == 0 || entries.len() < area {
+            return None;
+        }
+        Some(Matrix { size, entries })
+    }
+
+    fn get(&self, row: usize, column: usize) -> u32 {
+        self.entries[row * self.size + column]
+    }
+
+    /// Gauss-Jordan elimination mod the prime `p`, giving the determinant and,
+    /// if it isn't zero, the inverse.
+    fn eliminate(&self, p: u32) -> (u32, Option<Vec<u32>>) {
+        let n = self.size;
+        let mut left = self.entries.iter().map(|x| x % p).collect::<Vec<_>>();
+        // The identity, with as many entries as the matrix has.
+        let mut right = (0..left.len()).map(|i| (i / n == i % n) as u32).collect::<Vec<_>>();
+        let mut determinant = 1;
+        for column in 0..n {
+            let pivot = match (column..n).find(|&r| left[r * n + column] != 0) {
+                Some(pivot) => pivot,
+                None => return (0, None),
+            };
+            if pivot != column {
+                for c in 0..n {
+                    left.swap(pivot * n + c, column * n + c);
+                    right.swap(pivot * n + c, column * n + c);
+                }
+                determinant = (p - determinant) % p;
+            }
+            let value = left[column * n + column];
+            determinant = determinant * value % p;
+            // p is prime, so the pivot's inverse is value^(p - 2).
+            let scale = (0..p - 2).fold(1, |x, _| x * value % p);
+            for c in 0..n {
+                left[column * n + c] = left[column * n + c] * scale % p;
+                right[column * n + c] = right[column * n + c] * scale % p;
+            }
+            for r in (0..n).filter(|&r| r != column) {
+                let factor = left[r * n + column];
+                for c in 0..n {
+                    left[r * n + c] = (left[r * n + c] + (p - factor) * left[column * n + c]) % p;
+                    right[r * n + c] = (right[r * n + c] + (p - factor) * right[column * n + c]) % p;
+                }
+            }
+        }
+        (determinant, Some(right))
+    }
+
+    /// The determinant mod 26, found mod 2 and mod 13 and put back together.
+    pub fn determinant(&self) -> u32 {
+        chinese_remainder(self.eliminate(2).0, self.eliminate(13).0)
+    }
+
+    /// The inverse mod 26, which only exists if the determinant is coprime to 26.
+    pub fn inverse(&self) -> Option<Matrix> {
+        let two = self.eliminate(2).1?;
+        let thirteen = self.eliminate(13).1?;
+        let entries = two.iter().zip(&thirteen).map(|(&a, &b)| chinese_remainder(a, b)).collect();
+        Some(Matrix {
+            size: self.size,
+            entries,
+        })
+    }
+
+    fn multiply_vector(&self, vector: &[u32]) -> Vec<u32> {
+        (0..self.size)
+            .map(|r| (0..self.size).map(|c| self.get(r, c) * vector[c]).sum::<u32>() % 26)
+            .collect()
+    }
+
+    fn multiply(&self, other: &Matrix) -> Matrix {
+        let entries = (0..self.size * self.size)
+            .map(|i| {
+                let (r, c) = (i / self.size, i % self.size);
+                (0..self.size).map(|k| self.get(r, k) * other.get(k, c)).sum::<u32>() % 26
+            })
+            .collect();
+        Matrix {
+            size: self.size,
+            entries,
+        }
+    }
+}
+
+/// The number mod 26 that is `two` mod 2 and `thirteen` mod 13.
+fn chinese_remainder(two: u32, thirteen: u32) -> u32 {
+    thirteen + 13 * ((two + thirteen) % 2)
+}
+
+/// Why a matrix can't be used as a key, in words.
+pub fn key_error(key: &Matrix) -> Option<String> {
+    if key.size == 0 || key.size.checked_mul(key.size) != Some(key.entries.len()) {
+        return Some(String::from("the key must be a square matrix"));
+    }
+    let determinant = key.determinant();
+    if mod_inverse(determinant as u8).is_some() {
+        return None;
+    }
+    let factor = if determinant.is_multiple_of(2) { 2 } else { 13 };
+    Some(format!(
+        "the determinant is {}, which shares a factor of {} with 26, so the key can't be inverted",
+        determinant, factor
+    ))
+}
+
+/// Multiplies each block of letters by `key`, keeping everything else where it
+/// is. Letters keep the case of the letter they replace, and the last block is
+/// padded with X's.
+fn transform(text: &str, key: &Matrix, pad: bool) -> Result<String, CipherError> {
+    let mut letters = text.chars().filter_map(letter_index).collect::<Vec<_>>();
+    let mut output = text.to_string();
+    let remainder = letters.len() % key.size;
+    if remainder != 0 {
+        if !pad {
+            return Err(CipherError::InvalidText(format!(
+                "the number of letters must be a multiple of {}",
+                key.size
+            )));
+        }
+        let lowercase = text
+            .chars()
+            .rev()
+            .find(|c| letter_index(*c).is_some())
+            .is_some_and(|c| c.is_ascii_lowercase());
+        for _ in remainder..key.size {
+            letters.push(23);
+            output.push(if lowercase { 'x' } else { 'X' });
+        }
+    }
+
+    let mut transformed = letters.chunks(key.size).flat_map(|block| key.multiply_vector(block));
+    Ok(
+        output
+            .chars()
+            .map(|c| {
+                if letter_index(c).is_none() {
+                    return c;
+                }
+                let base = if c.is_ascii_uppercase() { 65 } else { 97 };
+                std::char::from_u32(transformed.next().unwrap() + base).unwrap()
+            })
+            .collect(),
+    )
+}
+
+fn check_key(key: &Matrix) -> Result<(), CipherError> {
+    match key_error(key) {
+        Some(error) => Err(CipherError::InvalidKey(error)),
+        None => Ok(()),
+    }
+}
+
+pub fn encrypt(plaintext: &str, key: &Matrix) -> Result<String, CipherError> {
+    check_key(key)?;
+    transform(plaintext, key, true)
+}
+
+/// Decrypts `ciphertext`, which must have a whole number of blocks of letters.
+pub fn decrypt(ciphertext: &str, key: &Matrix) -> Result<String, CipherError> {
+    check_key(key)?;
+    // Any key that passes the check can be inverted.
+    transform(ciphertext, &key.inverse().unwrap(), false)
+}
+
+/// Finds the `size` by `size` key that encrypts `plaintext` to `ciphertext`.
+/// This needs `size` blocks of plaintext that are independent of each other,
+/// and any more blocks there are are used to check the key.
+pub fn find_key(plaintext: &str, ciphertext: &str, size: usize) -> Result<Matrix, CipherError> {
+    if size == 0 {
+        return Err(CipherError::InvalidKey(String::from("the key must be at least 1 by 1")));
+    }
+    // Encrypting pads the last block, so the ciphertext can be longer.
+    let ciphertext = ciphertext.chars().take(plaintext.chars().count()).collect::<String>();
+    let pairs = letter_pairs(plaintext, &ciphertext)?
+        .into_iter()
+        .map(|(_, plain, cipher)| (letter_index(plain).unwrap(), letter_index(cipher).unwrap()))
+        .collect::<Vec<_>>();
+    let blocks = pairs
+        .chunks_exact(size)
+        .map(|block| {
+            let plain = block.iter().map(|&(p, _)| p).collect::<Vec<_>>();
+            let cipher = block.iter().map(|&(_, c)| c).collect::<Vec<_>>();
+            (plain, cipher)
+        })
+        .collect::<Vec<_>>();
+
+    let too_few = || CipherError::InvalidText(format!("there aren't enough independent blocks of {} letters", size));
+    if blocks.len() < size {
+        return Err(too_few());
+    }
+    // With the blocks as columns, C = KP, so K = CP^-1 for any choice of
+    // blocks that makes P invertible.
+    let chosen = choose_invertible(&blocks, size, &mut Vec::new(), 0).ok_or_else(too_few)?;
+    let plain = chosen.iter().map(|&i| blocks[i].0.clone()).collect::<Vec<_>>();
+    let cipher = chosen.iter().map(|&i| blocks[i].1.clone()).collect::<Vec<_>>();
+    // The chosen plaintext blocks were picked to be invertible.
+    let key = from_columns(&cipher).multiply(&from_columns(&plain).inverse().unwrap());
+
+    let consistent = blocks.iter().all(|(plain, cipher)| &key.multiply_vector(plain) == cipher);
+    if consistent && key.inverse().is_some() {
+        Ok(key)
+    } else {
+        Err(CipherError::NoKey)
+    }
+}
+
+/// The matrix with `columns` as its columns, each as long as there are columns.
+fn from_columns(columns: &[Vec<u32>]) -> Matrix {
+    let size = columns.len();
+    let entries = (0..size).flat_map(|r| columns.iter().map(move |column| column[r])).collect();
+    Matrix { size, entries }
+}
+
+/// Picks `size` blocks whose plaintexts, as columns, make an invertible matrix.
+fn choose_invertible(
+    blocks: &[(Vec<u32>, Vec<u32>)],
+    size: usize,
+    chosen: &mut Vec<usize>,
+    start: usize,
+) -> Option<Vec<usize>> {
+    if chosen.len() == size {
+        let plain = chosen.iter().map(|&i| blocks[i].0.clone()).collect::<Vec<_>>();
+        return from_columns(&plain).inverse().map(|_| chosen.clone());
+    }
+    // Only look so far, since the number of choices grows very quickly.
+    for i in start..blocks.len().min(start + 2 * size + 4) {
+        chosen.push(i);
+        if let Some(found) = choose_invertible(blocks, size, chosen, i + 1) {
+            return Some(found);
+        }
+        chosen.pop();
+    }
+    None
+}
+
+impl Cipher for Hill {
+    type Key = Matrix;
+
+    fn is_valid_key(&self, key: &Matrix) -> bool {
+        key_error(key).is_none()
+    }
+
+    fn encrypt(&self, plaintext: &str, key: &Matrix) -> Result<String, CipherError> {
+        encrypt(plaintext, key)
+    }
+
+    fn decrypt(&self, ciphertext: &str, key: &Matrix) -> Result<String, CipherError> {
+        decrypt(ciphertext, key)
+    }
+
+    /// Tries each size of key up to [`MAX_FIND_SIZE`], smallest first. If
+    /// none fit, the error is the one for a 1 by 1 key.
+    fn find_key(&self, plaintext: &str, ciphertext: &str) -> Result<Matrix, CipherError> {
+        let error = match find_key(plaintext, ciphertext, 1) {
+            Ok(key) => return Ok(key),
+            Err(error) => error,
+        };
+        (2..=MAX_FIND_SIZE)
+            .find_map(|size| find_key(plaintext, ciphertext, size).ok())
+            .ok_or(error)
+    }
+}
@@ -0,0 +1,328 @@
+//! Guessing which kind of cipher or encoding a text came from, using
+//! statistics that tell them apart.
+
+use crate::{
+    affine,
+    alphabet::Alphabet,
+    analysis::{chi_squared, index_of_coincidence},
+    ascii85::{self, Ascii85Options},
+    base32, base58, base64, hex,
+    morse::{self, MorseOptions},
+    shift::{self, letter_index},
+    vigenere,
+};
+use std::collections::HashMap;
+
+/// The longest key that the periodic index of coincidence is worked out for.
+pub const MAX_PERIOD: usize = 12;
+
+/// An index of coincidence at least this high means the letters have been
+/// substituted one for one, or not at all.
+const MONOALPHABETIC_IOC: f64 = 0.055;
+
+/// A chi-squared statistic per letter below this means the letters have
+/// English frequencies.
+const ENGLISH_FIT: f64 = 0.5;
+
+/// Texts with fewer letters than this are too short for their statistics to
+/// mean much, so guesses based on them are less sure.
+const SHORT_TEXT: usize = 30;
+
+/// Statistics about a text, not counting whitespace.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Statistics {
+    pub length: usize,
+    /// How many English letters there are.
+    pub letters: usize,
+    /// The index of coincidence of the letters.
+    pub ioc: f64,
+    /// The Shannon entropy of the characters, in bits per character.
+    pub entropy: f64,
+    /// How many different characters there are.
+    pub distinct: usize,
+    pub has_upper: bool,
+    pub has_lower: bool,
+    pub has_digits: bool,
+    /// Whether there's anything other than letters and digits.
+    pub has_symbols: bool,
+    pub digits_only: bool,
+    pub has_j: bool,
+    pub has_z: bool,
+    /// The numbers from 2 to 10 that divide the number of letters.
+    pub divisors: Vec<usize>,
+    /// How many letters are followed by the same letter.
+    pub doubled_letters: usize,
+    /// How many of the pairs of letters, taken two at a time from the start,
+    /// are the same letter twice, which Playfair never gives.
+    pub doubled_pairs: usize,
+    /// The average index of coincidence of the columns for each key length,
+    /// starting from 1.
+    pub periodic_ioc: Vec<f64>,
+    /// The chi-squared statistic of the letters against English.
+    pub chi_squared: f64,
+}
+
+impl Statistics {
+    pub fn even_length(&self) -> bool {
+        self.letters.is_multiple_of(2)
+    }
+
+    /// The kinds of character in the text, in words.
+    pub fn charset(&self) -> String {
+        let kinds = [
+            (self.has_upper, "upper case letters"),
+            (self.has_lower, "lower case letters"),
+            (self.has_digits, "digits"),
+            (self.has_symbols, "symbols"),
+        ];
+        let kinds = kinds.iter().filter(|(present, _)| *present).map(|(_, kind)| *kind).collect::<Vec<_>>();
+        if kinds.is_empty() {
+            String::from("nothing")
+        } else {
+            kinds.join(", ")
+        }
+    }
+
+    /// The shortest key length whose columns look like English, if there is
+    /// one with enough letters in each column to tell.
+    pub fn period(&self) -> Option<usize> {
+        (2..=self.periodic_ioc.len())
+            .filter(|&len| self.letters / len >= 10)
+            .find(|&len| self.periodic_ioc[len - 1] >= MONOALPHABETIC_IOC)
+    }
+}
+
+pub fn statistics(text: &str) -> Statistics {
+    let chars = text.chars().filter(|c| !c.is_whitespace()).collect::<Vec<_>>();
+    let letters = chars.iter().copied().filter_map(letter_index).collect::<Vec<_>>();
+    let letter_text = letters.iter().map(|&x| (b'a' + x as u8) as char).collect::<String>();
+
+    let mut counts = HashMap::new();
+    for &c in &chars {
+        *counts.entry(c).or_insert(0) += 1;
+    }
+    let entropy = counts.values().fold(0.0, |entropy, &count| {
+        let p = count as f64 / chars.len() as f64;
+        entropy - p * p.log2()
+    });
+    let alphabet = Alphabet::english();
+
+    Statistics {
+        length: chars.len(),
+        letters: letters.len(),
+        ioc: index_of_coincidence(&letter_text),
+        entropy,
+        distinct: counts.len(),
+        has_upper: chars.iter().any(char::is_ascii_uppercase),
+        has_lower: chars.iter().any(char::is_ascii_lowercase),
+        has_digits: chars.iter().any(char::is_ascii_digit),
+        has_symbols: chars.iter().any(|c| !c.is_ascii_alphanumeric()),
+        digits_only: !chars.is_empty() && chars.iter().all(char::is_ascii_digit),
+        has_j: letters.contains(&9),
+        has_z: letters.contains(&25),
+        divisors: (2..=10).filter(|&n| !letters.is_empty() && letters.len().is_multiple_of(n)).collect(),
+        doubled_letters: letters.windows(2).filter(|pair| pair[0] == pair[1]).count(),
+        doubled_pairs: letters.chunks_exact(2).filter(|pair| pair[0] == pair[1]).count(),
+        periodic_ioc: (1..=MAX_PERIOD)
+            .map(|len| vigenere::column_ioc(&letter_text, len, &alphabet))
+            .collect(),
+        chi_squared: chi_squared(&letter_text),
+    }
+}
+
+/// The kinds of cipher and encoding that [`guess`] can tell apart.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Family {
+    Shift,
+    Affine,
+    Substitution,
+    Vigenere,
+    Playfair,
+    RailFence,
+    Columnar,
+    Base64,
+    Hex,
+    Base32,
+    Base58,
+    Ascii85,
+    Morse,
+}
+
+pub const FAMILIES: [Family; 13] = [
+    Family::Shift,
+    Family::Affine,
+    Family::Substitution,
+    Family::Vigenere,
+    Family::Playfair,
+    Family::RailFence,
+    Family::Columnar,
+    Family::Base64,
+    Family::Hex,
+    Family::Base32,
+    Family::Base58,
+    Family::Ascii85,
+    Family::Morse,
+];
+
+impl Family {
+    pub fn name(self) -> &'static str {
+        match self {
+            Family::Shift => "Shift (Caesar)",
+            Family::Affine => "Affine",
+            Family::Substitution => "Substitution",
+            Family::Vigenere => "Vigenère",
+            Family::Playfair => "Playfair",
+            Family::RailFence => "Rail fence",
+            Family::Columnar => "Columnar transposition",
+            Family::Base64 => "Base64",
+            Family::Hex => "Hex",
+            Family::Base32 => "Base32",
+            Family::Base58 => "Base58",
+            Family::Ascii85 => "Ascii85",
+            Family::Morse => "Morse code",
+        }
+    }
+}
+
+/// A kind of cipher that a text might have come from, as found by [`guess`].
+#[derive(Clone, Debug)]
+pub struct Guess {
+    pub family: Family,
+    /// How likely this is, from 0 to 1.
+    pub score: f64,
+    /// What the guess is based on, in words.
+    pub reason: String,
+}
+
+/// How much of `bytes` is readable text, from 0 to 1.
+fn readable(bytes: &[u8]) -> f64 {
+    let text = String::from_utf8_lossy(bytes);
+    let total = text.chars().count();
+    if total == 0 {
+        return 0.0;
+    }
+    let good = text
+        .chars()
+        .filter(|&c| c != char::REPLACEMENT_CHARACTER && (!c.is_control() || c.is_whitespace()))
+        .count();
+    good as f64 / total as f64
+}
+
+/// Ranks the kinds of cipher and encoding that `text` might have come from,
+/// most likely first. Encodings are tried by decoding the text, and ciphers
+/// are judged by its statistics.
+pub fn guess(text: &str, statistics: &Statistics) -> Vec<Guess> {
+    let mut guesses = Vec::new();
+    if statistics.length == 0 {
+        return guesses;
+    }
+    let guess = |family, score: f64, reason: String| Guess {
+        family,
+        score: score.clamp(0.0, 1.0),
+        reason,
+    };
+
+    let compact = text.chars().filter(|c| !c.is_whitespace()).collect::<String>();
+    if compact.contains(['.', '-']) && morse::decode(text.trim(), &MorseOptions::default()).is_ok() {
+        guesses.push(guess(Family::Morse, 0.95, String::from("it's only dots, dashes and separators")));
+    }
+    let ascii85 = match compact.strip_prefix("<~").and_then(|rest| rest.strip_suffix("~>")) {
+        Some(_) => ascii85::decode_bytes(&compact, &Ascii85Options { delimiters: true, ..Default::default() }),
+        None => ascii85::decode_bytes(&compact, &Ascii85Options::default()),
+    };
+    let decodings = [
+        (Family::Hex, hex::decode_bytes(text).ok()),
+        (Family::Base64, base64::decode_bytes(&compact, &Default::default()).ok()),
+        (Family::Base32, base32::decode_bytes(&compact, &Default::default()).ok()),
+        (Family::Base58, base58::decode_bytes(&compact, false).ok()),
+        (Family::Ascii85, ascii85.ok()),
+    ];
+    for (family, bytes) in decodings.iter() {
+        if let Some(bytes) = bytes {
+            let readable = readable(bytes);
+            // Random bytes are about a third readable, so this keeps them low.
+            let reason = format!("it decodes to {} bytes, {:.0}% readable", bytes.len(), readable * 100.0);
+            guesses.push(guess(*family, 0.95 * readable * readable, reason));
+        }
+    }
+
+    // The rest are ciphers on letters, which readable decodings rule out.
+    if statistics.letters * 2 < statistics.length || guesses.iter().any(|guess| guess.score >= 0.8) {
+        return rank(guesses);
+    }
+    let sure = if statistics.letters < SHORT_TEXT { 0.6 } else { 1.0 };
+    let fit = |chi_squared: f64| chi_squared / statistics.letters.max(1) as f64;
+    let letters_only = !statistics.has_digits && compact.chars().all(|c| letter_index(c).is_some());
+    let playfair_like = letters_only && statistics.even_length() && statistics.doubled_pairs == 0;
+
+    let english = fit(statistics.chi_squared) < ENGLISH_FIT;
+    // A key that gives English letter frequencies settles it, even when the
+    // text is too short for its index of coincidence to tell.
+    let (shifted, affined) = if english {
+        (None, None)
+    } else {
+        let shifted = shift::crack(text, &Alphabet::english()).into_iter().next();
+        let affined = affine::crack(text).into_iter().next();
+        (
+            shifted.filter(|best| fit(best.score) < ENGLISH_FIT),
+            affined.filter(|best| fit(best.score) < ENGLISH_FIT),
+        )
+    };
+    if let Some(best) = &shifted {
+        let reason = format!("shifting by {} gives English letter frequencies", best.count);
+        guesses.push(guess(Family::Shift, 0.9 * sure, reason));
+    }
+    if let (None, Some(best)) = (&shifted, &affined) {
+        let reason = format!("the affine key {} gives English letter frequencies", best.key);
+        guesses.push(guess(Family::Affine, 0.85 * sure, reason));
+    }
+
+    if statistics.ioc >= MONOALPHABETIC_IOC {
+        let ioc = format!("the index of coincidence is {:.3}, like English", statistics.ioc);
+        if english {
+            let reason = format!("{}, and so are the letter frequencies, so they may just be rearranged", ioc);
+            guesses.push(guess(Family::RailFence, 0.7 * sure, reason.clone()));
+            let columnar = if statistics.divisors.iter().any(|&n| n > 2) { 0.75 } else { 0.65 };
+            guesses.push(guess(Family::Columnar, columnar * sure, reason));
+        } else {
+            let substitution = if shifted.is_some() || affined.is_some() { 0.4 } else { 0.8 };
+            let reason = format!("{}, but the letters have been swapped around", ioc);
+            guesses.push(guess(Family::Substitution, substitution * sure, reason));
+        }
+    } else {
+        match statistics.period() {
+            Some(period) => {
+                let reason = format!(
+                    "the index of coincidence is {:.3}, but every {} letters it's {:.3}, like English",
+                    statistics.ioc,
+                    period,
+                    statistics.periodic_ioc[period - 1],
+                );
+                // Playfair also makes every other letter look more English.
+                let score = if period == 2 && playfair_like { 0.6 } else { 0.85 };
+                guesses.push(guess(Family::Vigenere, score * sure, reason));
+            },
+            None => {
+                let reason = format!("the index of coincidence is {:.3}, so the letters are spread out", statistics.ioc);
+                guesses.push(guess(Family::Vigenere, 0.45 * sure, reason));
+            },
+        }
+
+        if playfair_like {
+            let signs = [
+                (statistics.even_length(), "an even number of letters"),
+                (!statistics.has_j, "no J"),
+                (statistics.doubled_pairs == 0, "no pairs of the same letter"),
+            ];
+            let found = signs.iter().filter(|(present, _)| *present).map(|(_, sign)| *sign).collect::<Vec<_>>();
+            let reason = format!("there are {}", found.join(", "));
+            guesses.push(guess(Family::Playfair, (0.3 + 0.2 * found.len() as f64) * sure, reason));
+        }
+    }
+    rank(guesses)
+}
+
+fn rank(mut guesses: Vec<Guess>) -> Vec<Guess> {
+    guesses.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap());
+    guesses
+}
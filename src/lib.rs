@@ -0,0 +1,28 @@
+//! The ciphers and encodings behind CipherTools, usable without the GUI.
+
+mod cipher;
+
+pub mod affine;
+pub mod alphabet;
+pub mod analysis;
+pub mod ascii85;
+pub mod base32;
+pub mod base58;
+pub mod base64;
+pub mod columnar;
+pub mod encoding;
+pub mod enigma;
+pub mod hex;
+pub mod hill;
+pub mod identify;
+pub mod morse;
+pub mod playfair;
+pub mod rail_fence;
+pub mod recipe;
+pub mod shift;
+pub mod substitution;
+pub mod text_options;
+pub mod vigenere;
+pub mod wav;
+
+pub use cipher::{Cipher, CipherError};
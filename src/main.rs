@@ -1,18 +1,210 @@
+use ciphertools::identify::Family;
 use druid::{
-    widget::{Label, ViewSwitcher},
+    widget::{CrossAxisAlignment, Flex, Label, ViewSwitcher},
     AppLauncher, Data, Lens, Widget, WidgetExt, WindowDesc,
 };
+use serde::{Deserialize, Serialize};
 
+mod session;
 mod theme;
 mod tools;
 mod widget;
 
-#[derive(Clone, Data, Lens, Default)]
+#[derive(Clone, Data, Lens, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct State {
     shift: tools::shift::ShiftState,
     vigenere: tools::vigenere::VigenereState,
+    affine: tools::affine::AffineState,
+    substitution: tools::substitution::SubstitutionState,
+    playfair: tools::playfair::PlayfairState,
+    hill: tools::hill::HillState,
+    enigma: tools::enigma::EnigmaState,
+    rail_fence: tools::rail_fence::RailFenceState,
+    columnar: tools::columnar::ColumnarState,
     base64: tools::base64::Base64State,
+    hex: tools::hex::HexState,
+    base32: tools::base32::Base32State,
+    base58: tools::base58::Base58State,
+    ascii85: tools::ascii85::Ascii85State,
+    morse: tools::morse::MorseState,
+    frequency: tools::frequency::FrequencyState,
+    identify: tools::identify::IdentifyState,
+    recipe: tools::recipe::RecipeState,
     selected_tab: usize,
+    /// Why the session couldn't be opened or saved, if it couldn't.
+    #[serde(skip)]
+    session_status: String,
+}
+
+/// The tabs, in the order they are listed. `State::selected_tab` is an index
+/// into [`TABS`].
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Tab {
+    Shift,
+    Vigenere,
+    Affine,
+    Substitution,
+    Playfair,
+    Hill,
+    Enigma,
+    RailFence,
+    Columnar,
+    Base64,
+    Hex,
+    Base32,
+    Base58,
+    Ascii85,
+    Morse,
+    Frequency,
+    Identify,
+    Recipe,
+}
+
+const TABS: [Tab; 18] = [
+    Tab::Shift,
+    Tab::Vigenere,
+    Tab::Affine,
+    Tab::Substitution,
+    Tab::Playfair,
+    Tab::Hill,
+    Tab::Enigma,
+    Tab::RailFence,
+    Tab::Columnar,
+    Tab::Base64,
+    Tab::Hex,
+    Tab::Base32,
+    Tab::Base58,
+    Tab::Ascii85,
+    Tab::Morse,
+    Tab::Frequency,
+    Tab::Identify,
+    Tab::Recipe,
+];
+
+impl Tab {
+    fn index(self) -> usize {
+        TABS.iter().position(|&tab| tab == self).unwrap()
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Tab::Shift => "Shift",
+            Tab::Vigenere => "Vigenère",
+            Tab::Affine => "Affine",
+            Tab::Substitution => "Substitution",
+            Tab::Playfair => "Playfair",
+            Tab::Hill => "Hill",
+            Tab::Enigma => "Enigma",
+            Tab::RailFence => "Rail Fence",
+            Tab::Columnar => "Columnar",
+            Tab::Base64 => "Base64",
+            Tab::Hex => "Hex",
+            Tab::Base32 => "Base32",
+            Tab::Base58 => "Base58",
+            Tab::Ascii85 => "Ascii85",
+            Tab::Morse => "Morse",
+            Tab::Frequency => "Frequency",
+            Tab::Identify => "Identify",
+            Tab::Recipe => "Recipe",
+        }
+    }
+
+    /// The heading listed above this tab, if it is the first of its kind.
+    fn heading(self) -> Option<&'static str> {
+        match self {
+            Tab::Shift => Some("CIPHERS"),
+            Tab::RailFence => Some("TRANSPOSITION"),
+            Tab::Base64 => Some("ENCODING"),
+            Tab::Frequency => Some("ANALYSIS"),
+            Tab::Recipe => Some("WORKFLOW"),
+            _ => None,
+        }
+    }
+
+    fn widget(self) -> Box<dyn Widget<State>> {
+        match self {
+            Tab::Shift => tools::shift::build_shift_widget().lens(State::shift).boxed(),
+            Tab::Vigenere => tools::vigenere::build_vigenere_widget().lens(State::vigenere).boxed(),
+            Tab::Affine => tools::affine::build_affine_widget().lens(State::affine).boxed(),
+            Tab::Substitution => tools::substitution::build_substitution_widget().lens(State::substitution).boxed(),
+            Tab::Playfair => tools::playfair::build_playfair_widget().lens(State::playfair).boxed(),
+            Tab::Hill => tools::hill::build_hill_widget().lens(State::hill).boxed(),
+            Tab::Enigma => tools::enigma::build_enigma_widget().lens(State::enigma).boxed(),
+            Tab::RailFence => tools::rail_fence::build_rail_fence_widget().lens(State::rail_fence).boxed(),
+            Tab::Columnar => tools::columnar::build_columnar_widget().lens(State::columnar).boxed(),
+            Tab::Base64 => tools::base64::build_base64_widget().lens(State::base64).boxed(),
+            Tab::Hex => tools::hex::build_hex_widget().lens(State::hex).boxed(),
+            Tab::Base32 => tools::base32::build_base32_widget().lens(State::base32).boxed(),
+            Tab::Base58 => tools::base58::build_base58_widget().lens(State::base58).boxed(),
+            Tab::Ascii85 => tools::ascii85::build_ascii85_widget().lens(State::ascii85).boxed(),
+            Tab::Morse => tools::morse::build_morse_widget().lens(State::morse).boxed(),
+            Tab::Frequency => tools::frequency::build_frequency_widget().lens(State::frequency).boxed(),
+            Tab::Identify => tools::identify::build_identify_widget().lens(State::identify).boxed(),
+            Tab::Recipe => tools::recipe::build_recipe_widget().lens(State::recipe).boxed(),
+        }
+    }
+}
+
+impl State {
+    /// Opens `text` in the tab for `family`, as the Identify tab asks.
+    fn hand_off(&mut self, family: Family, text: String) {
+        let tab = match family {
+            Family::Shift => {
+                self.shift.hand_off(text);
+                Tab::Shift
+            },
+            Family::Vigenere => {
+                self.vigenere.hand_off(text);
+                Tab::Vigenere
+            },
+            Family::Affine => {
+                self.affine.hand_off(text);
+                Tab::Affine
+            },
+            Family::Substitution => {
+                self.substitution.hand_off(text);
+                Tab::Substitution
+            },
+            Family::Playfair => {
+                self.playfair.hand_off(text);
+                Tab::Playfair
+            },
+            Family::RailFence => {
+                self.rail_fence.hand_off(text);
+                Tab::RailFence
+            },
+            Family::Columnar => {
+                self.columnar.hand_off(text);
+                Tab::Columnar
+            },
+            Family::Base64 => {
+                self.base64.hand_off(text);
+                Tab::Base64
+            },
+            Family::Hex => {
+                self.hex.hand_off(text);
+                Tab::Hex
+            },
+            Family::Base32 => {
+                self.base32.hand_off(text);
+                Tab::Base32
+            },
+            Family::Base58 => {
+                self.base58.hand_off(text);
+                Tab::Base58
+            },
+            Family::Ascii85 => {
+                self.ascii85.hand_off(text);
+                Tab::Ascii85
+            },
+            Family::Morse => {
+                self.morse.hand_off(text);
+                Tab::Morse
+            },
+        };
+        self.selected_tab = tab.index();
+    }
 }
 
 fn main() {
@@ -20,28 +212,40 @@ fn main() {
 
     let window = WindowDesc::new(app)
         .title("CipherTools")
-        .window_size((800., 600.));
+        .window_size((800., 600.))
+        .menu(session::menu());
 
     AppLauncher::with_window(window)
+        .delegate(session::Delegate::default())
         .configure_env(theme::theme)
-        .launch(Default::default())
+        .launch(session::restore())
         .unwrap();
 }
 
 fn app() -> impl Widget<State> {
     use widget::tab_selector::{Entry, tab_selector};
-    tab_selector(
-        vec![Entry::Category("CIPHERS"), Entry::Tab("Shift"), Entry::Tab("Vigenère"), Entry::Category("ENCODING"), Entry::Tab("Base64")],
+    let mut entries = Vec::new();
+    for tab in &TABS {
+        if let Some(heading) = tab.heading() {
+            entries.push(Entry::Category(heading));
+        }
+        entries.push(Entry::Tab(tab.name()));
+    }
+    let tabs = tab_selector(
+        entries,
         State::selected_tab,
         ViewSwitcher::new(|data: &State, _env| {
             data.selected_tab
         }, |value, _data, _env| {
-            match value {
-                0 => tools::shift::build_shift_widget().lens(State::shift).boxed(),
-                1 => tools::vigenere::build_vigenere_widget().lens(State::vigenere).boxed(),
-                2 => tools::base64::build_base64_widget().lens(State::base64).boxed(),
-                _ => Label::new("Unimplemented").boxed(),
+            match TABS.get(*value) {
+                Some(tab) => tab.widget(),
+                None => Label::new("Unimplemented").boxed(),
             }
         }).expand_height()
-    ).padding(4.0)
+    );
+    Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_flex_child(tabs.expand_width(), 1.0)
+        .with_child(Label::dynamic(|status: &String, _| status.clone()).lens(State::session_status))
+        .padding(4.0)
 }
@@ -0,0 +1,417 @@
+use crate::{cipher::match_encoding, encoding::DecodeError, wav::Wav, Cipher, CipherError};
+use std::fmt;
+
+/// International Morse code, as the ITU defines it, plus `!`, `$` and `_`.
+const CODES: [(char, &str); 54] = [
+    ('A', ".-"), ('B', "-..."), ('C', "-.-."), ('D', "-.."), ('E', "."), ('F', "..-."),
+    ('G', "--."), ('H', "...."), ('I', ".."), ('J', ".---"), ('K', "-.-"), ('L', ".-.."),
+    ('M', "--"), ('N', "-."), ('O', "---"), ('P', ".--."), ('Q', "--.-"), ('R', ".-."),
+    ('S', "..."), ('T', "-"), ('U', "..-"), ('V', "...-"), ('W', ".--"), ('X', "-..-"),
+    ('Y', "-.--"), ('Z', "--.."),
+    ('0', "-----"), ('1', ".----"), ('2', "..---"), ('3', "...--"), ('4', "....-"),
+    ('5', "....."), ('6', "-...."), ('7', "--..."), ('8', "---.."), ('9', "----."),
+    ('.', ".-.-.-"), (',', "--..--"), ('?', "..--.."), ('\'', ".----."), ('!', "-.-.--"),
+    ('/', "-..-."), ('(', "-.--."), (')', "-.--.-"), ('&', ".-..."), (':', "---..."),
+    (';', "-.-.-."), ('=', "-...-"), ('+', ".-.-."), ('-', "-....-"), ('_', "..--.-"),
+    ('"', ".-..-."), ('$', "...-..-"), ('@', ".--.-."),
+];
+
+/// Prosigns whose codes aren't also characters, so that they can be decoded.
+/// Others, like AR, are decoded as the character they share a code with.
+const PROSIGNS: [(&str, &str); 6] = [
+    ("SK", "...-.-"),
+    ("SOS", "...---..."),
+    ("CT", "-.-.-"),
+    ("SN", "...-."),
+    ("AA", ".-.-"),
+    ("HH", "........"),
+];
+
+/// How Morse code is written down.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MorseOptions {
+    pub dot: char,
+    pub dash: char,
+    pub letter_separator: String,
+    pub word_separator: String,
+}
+
+impl Default for MorseOptions {
+    fn default() -> Self {
+        MorseOptions {
+            dot: '.',
+            dash: '-',
+            letter_separator: String::from(" "),
+            word_separator: String::from(" / "),
+        }
+    }
+}
+
+/// Why Morse can't be written with `options`, in words.
+pub fn options_error(options: &MorseOptions) -> Option<String> {
+    if options.dot == options.dash {
+        return Some(String::from("dots and dashes must be different"));
+    }
+    if options.letter_separator.is_empty() || options.word_separator.is_empty() {
+        return Some(String::from("the separators can't be empty"));
+    }
+    if options.letter_separator == options.word_separator {
+        return Some(String::from("letters and words must be separated differently"));
+    }
+    let separators = [&options.letter_separator, &options.word_separator];
+    if separators.iter().any(|x| x.contains(options.dot) || x.contains(options.dash)) {
+        return Some(String::from("the separators can't have dots or dashes in them"));
+    }
+    None
+}
+
+fn lookup(c: char) -> Option<&'static str> {
+    let c = c.to_ascii_uppercase();
+    CODES.iter().find(|(x, _)| *x == c).map(|(_, code)| *code)
+}
+
+/// Translates `text` into Morse. Letters between `<` and `>` are run together
+/// into a prosign, like `<SK>`. Whitespace separates words.
+pub fn encode(text: &str, options: &MorseOptions) -> Result<String, DecodeError> {
+    let write = |code: &str| -> String {
+        code.chars().map(|x| if x == '.' { options.dot } else { options.dash }).collect()
+    };
+    let mut words = Vec::new();
+    let mut letters = Vec::new();
+    let mut prosign: Option<(usize, String)> = None;
+    for (position, c) in text.chars().enumerate() {
+        if let Some((start, code)) = &mut prosign {
+            match c {
+                '>' if code.is_empty() => return Err(DecodeError::at(*start, "the prosign is empty")),
+                '>' => {
+                    letters.push(write(code));
+                    prosign = None;
+                },
+                _ => match lookup(c) {
+                    Some(x) if c != '<' => code.push_str(x),
+                    _ => return Err(DecodeError::at(position, format!("{} can't go in a prosign", c))),
+                },
+            }
+        } else if c == '<' {
+            prosign = Some((position, String::new()));
+        } else if c.is_whitespace() {
+            if !letters.is_empty() {
+                words.push(letters.join(&options.letter_separator));
+                letters.clear();
+            }
+        } else {
+            match lookup(c) {
+                Some(code) => letters.push(write(code)),
+                None => return Err(DecodeError::at(position, format!("{} has no Morse code", c))),
+            }
+        }
+    }
+    if let Some((start, _)) = prosign {
+        return Err(DecodeError::at(start, "the prosign has no closing >"));
+    }
+    if !letters.is_empty() {
+        words.push(letters.join(&options.letter_separator));
+    }
+    Ok(words.join(&options.word_separator))
+}
+
+/// Splits `morse` into words, and the words into letters, each written with
+/// `.` and `-` and paired with the position it started at.
+fn parse(morse: &str, options: &MorseOptions) -> Result<Vec<Vec<(usize, String)>>, DecodeError> {
+    let chars = morse.chars().collect::<Vec<_>>();
+    let word_separator = options.word_separator.chars().collect::<Vec<_>>();
+    let letter_separator = options.letter_separator.chars().collect::<Vec<_>>();
+    // Check for the longer separator first, in case one starts with the other.
+    let mut separators = [(word_separator, true), (letter_separator, false)];
+    separators.sort_by_key(|(x, _)| std::cmp::Reverse(x.len()));
+
+    let mut words = vec![Vec::new()];
+    let mut letter: Option<(usize, String)> = None;
+    let mut i = 0;
+    while i < chars.len() {
+        let separator = separators.iter().find(|(x, _)| chars[i..].starts_with(x));
+        // Line breaks separate words too, so that Morse can be laid out in lines.
+        let separator = match (separator, chars[i]) {
+            (Some((x, new_word)), _) => Some((x.len(), *new_word)),
+            (None, '\n') | (None, '\r') => Some((1, true)),
+            _ => None,
+        };
+        if let Some((len, new_word)) = separator {
+            words.last_mut().unwrap().extend(letter.take());
+            if new_word && !words.last().unwrap().is_empty() {
+                words.push(Vec::new());
+            }
+            i += len;
+            continue;
+        }
+        let c = chars[i];
+        let symbol = match c {
+            _ if c == options.dot => '.',
+            _ if c == options.dash => '-',
+            _ => return Err(DecodeError::at(i, format!("{} isn't a dot, a dash or a separator", c))),
+        };
+        letter.get_or_insert_with(|| (i, String::new())).1.push(symbol);
+        i += 1;
+    }
+    words.last_mut().unwrap().extend(letter);
+    words.retain(|word| !word.is_empty());
+    Ok(words)
+}
+
+/// Translates Morse back into text. Prosigns that don't share a code with a
+/// character come out between `<` and `>`.
+pub fn decode(morse: &str, options: &MorseOptions) -> Result<String, DecodeError> {
+    let mut words = Vec::new();
+    for word in parse(morse, options)? {
+        let mut text = String::new();
+        for (position, letter) in word {
+            if let Some((c, _)) = CODES.iter().find(|(_, code)| *code == letter) {
+                text.push(*c);
+            } else if let Some((name, _)) = PROSIGNS.iter().find(|(_, code)| *code == letter) {
+                text.push_str(&format!("<{}>", name));
+            } else {
+                return Err(DecodeError::at(position, format!("{} isn't a Morse letter", letter)));
+            }
+        }
+        words.push(text);
+    }
+    Ok(words.join(" "))
+}
+
+/// Morse code, written down as text. There is no key, so the key type is
+/// `()`. The options should be ones that [`options_error`] accepts.
+#[derive(Clone, Debug, Default)]
+pub struct Morse {
+    pub options: MorseOptions,
+}
+
+impl Cipher for Morse {
+    type Key = ();
+
+    fn is_valid_key(&self, _key: &()) -> bool {
+        true
+    }
+
+    fn encrypt(&self, plaintext: &str, _key: &()) -> Result<String, CipherError> {
+        Ok(encode(plaintext, &self.options)?)
+    }
+
+    fn decrypt(&self, ciphertext: &str, _key: &()) -> Result<String, CipherError> {
+        Ok(decode(ciphertext, &self.options)?)
+    }
+
+    fn find_key(&self, plaintext: &str, ciphertext: &str) -> Result<(), CipherError> {
+        match_encoding(&self.encrypt(plaintext, &())?, ciphertext)
+    }
+}
+
+/// How Morse is keyed as a tone.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Keying {
+    /// The speed of the letters themselves, in words per minute.
+    pub wpm: u32,
+    /// The overall speed, with longer gaps between letters and words. If it
+    /// isn't slower than `wpm`, the gaps are the usual length.
+    pub farnsworth_wpm: u32,
+    /// The pitch of the tone, in hertz.
+    pub frequency: u32,
+    pub sample_rate: u32,
+}
+
+impl Default for Keying {
+    fn default() -> Self {
+        Keying {
+            wpm: 20,
+            farnsworth_wpm: 20,
+            frequency: 600,
+            sample_rate: 8000,
+        }
+    }
+}
+
+impl Keying {
+    /// The length of a dot, and of the gaps between letters and between
+    /// words, in seconds. A dot is 1.2 / WPM seconds, since PARIS is 50 dots
+    /// long. Farnsworth timing stretches the gaps as the ARRL describes.
+    fn timing(&self) -> (f32, f32, f32) {
+        let dot = 1.2 / self.wpm as f32;
+        if self.farnsworth_wpm == 0 || self.farnsworth_wpm >= self.wpm {
+            return (dot, 3.0 * dot, 7.0 * dot);
+        }
+        let (c, s) = (self.wpm as f32, self.farnsworth_wpm as f32);
+        let delay = (60.0 * c - 37.2 * s) / (s * c);
+        (dot, 3.0 * delay / 19.0, 7.0 * delay / 19.0)
+    }
+}
+
+/// Why Morse can't be keyed with `keying`, in words.
+pub fn keying_error(keying: &Keying) -> Option<String> {
+    if keying.wpm == 0 {
+        return Some(String::from("the speed must be above 0 words per minute"));
+    }
+    if keying.sample_rate == 0 {
+        return Some(String::from("the sample rate must be above 0"));
+    }
+    None
+}
+
+/// Why Morse couldn't be keyed as audio, or heard in it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AudioError {
+    /// The keying can't be used, for the reason given.
+    InvalidKeying(String),
+    /// The Morse to key isn't valid.
+    InvalidMorse(DecodeError),
+    /// The audio has no sound in it.
+    Silent,
+    /// No tones could be picked out of the sound.
+    NoTones,
+}
+
+impl fmt::Display for AudioError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AudioError::InvalidKeying(reason) => write!(f, "invalid keying: {}", reason),
+            AudioError::InvalidMorse(e) => write!(f, "invalid Morse {}", e),
+            AudioError::Silent => write!(f, "the audio is silent"),
+            AudioError::NoTones => write!(f, "no tones could be heard in the audio"),
+        }
+    }
+}
+
+impl From<DecodeError> for AudioError {
+    fn from(e: DecodeError) -> Self {
+        AudioError::InvalidMorse(e)
+    }
+}
+
+/// Keys `morse` as a sine tone, with a word's gap of silence at each end.
+pub fn to_audio(morse: &str, options: &MorseOptions, keying: &Keying) -> Result<Wav, AudioError> {
+    if let Some(error) = keying_error(keying) {
+        return Err(AudioError::InvalidKeying(error));
+    }
+    let words = parse(morse, options)?;
+    let (dot, letter_gap, word_gap) = keying.timing();
+    // Each stretch of tone or silence, in seconds.
+    let mut spans = vec![(false, word_gap)];
+    for (i, word) in words.iter().enumerate() {
+        if i > 0 {
+            spans.push((false, word_gap));
+        }
+        for (j, (_, letter)) in word.iter().enumerate() {
+            if j > 0 {
+                spans.push((false, letter_gap));
+            }
+            for (k, symbol) in letter.chars().enumerate() {
+                if k > 0 {
+                    spans.push((false, dot));
+                }
+                spans.push((true, if symbol == '.' { dot } else { 3.0 * dot }));
+            }
+        }
+    }
+    spans.push((false, word_gap));
+
+    let rate = keying.sample_rate as f32;
+    // Fade each tone in and out over 5ms, so that it doesn't click.
+    let ramp = (rate * 0.005).min(rate * dot / 4.0).max(1.0);
+    let mut samples = Vec::new();
+    for (on, seconds) in spans {
+        let len = (seconds * rate).round() as usize;
+        if !on {
+            samples.extend(std::iter::repeat_n(0.0, len));
+            continue;
+        }
+        for i in 0..len {
+            let t = samples.len() as f32 / rate;
+            let edge = (i as f32).min((len - 1 - i) as f32);
+            let envelope = if edge < ramp {
+                0.5 - 0.5 * (std::f32::consts::PI * edge / ramp).cos()
+            } else {
+                1.0
+            };
+            let phase = 2.0 * std::f32::consts::PI * keying.frequency as f32 * t;
+            samples.push(0.8 * envelope * phase.sin());
+        }
+    }
+    Ok(Wav {
+        sample_rate: keying.sample_rate,
+        samples,
+    })
+}
+
+/// Listens for Morse in `wav`, writing it down with `options`.
+///
+/// The tone is found by its loudness, so the audio should be fairly clean.
+/// The length of a dot is guessed from the shortest tone or gap, so a message
+/// made only of dashes separated by long gaps is read as dots.
+pub fn from_audio(wav: &Wav, options: &MorseOptions) -> Result<String, AudioError> {
+    // The loudest sample in each 5ms, which is long enough to catch the peak
+    // of any tone above 100Hz.
+    let frame = (wav.sample_rate as usize / 200).max(1);
+    let loudness = wav
+        .samples
+        .chunks(frame)
+        .map(|chunk| chunk.iter().fold(0.0f32, |max, x| max.max(x.abs())))
+        .collect::<Vec<_>>();
+    let peak = loudness.iter().cloned().fold(0.0, f32::max);
+    if peak < 0.001 {
+        return Err(AudioError::Silent);
+    }
+
+    // Stretches of tone and silence, in frames, with blips shorter than 10ms
+    // merged into what came before.
+    let mut spans: Vec<(bool, usize)> = Vec::new();
+    for on in loudness.iter().map(|&x| x > peak / 2.0) {
+        match spans.last_mut() {
+            Some((last, len)) if *last == on => *len += 1,
+            _ => spans.push((on, 1)),
+        }
+    }
+    let mut merged: Vec<(bool, usize)> = Vec::new();
+    for (on, len) in spans {
+        match merged.last_mut() {
+            Some((_, last_len)) if len < 2 => *last_len += len,
+            Some((last, last_len)) if *last == on => *last_len += len,
+            _ => merged.push((on, len)),
+        }
+    }
+    while merged.first().is_some_and(|(on, _)| !on) {
+        merged.remove(0);
+    }
+    while merged.last().is_some_and(|(on, _)| !on) {
+        merged.pop();
+    }
+    if merged.is_empty() {
+        return Err(AudioError::NoTones);
+    }
+
+    let shortest_tone = merged.iter().filter(|(on, _)| *on).map(|(_, len)| *len).min().unwrap();
+    let shortest_gap = merged.iter().filter(|(on, _)| !on).map(|(_, len)| *len).min();
+    let dot = shortest_gap.map_or(shortest_tone, |gap| gap.min(shortest_tone)) as f32;
+    // Farnsworth timing makes the gaps between letters much longer than three
+    // dots, so measure word gaps against the shortest letter gap instead.
+    let shortest_letter_gap = merged
+        .iter()
+        .filter(|&&(on, len)| !on && len as f32 >= 2.0 * dot)
+        .map(|(_, len)| *len as f32)
+        .fold(f32::INFINITY, f32::min);
+    let word_gap = if shortest_letter_gap > 4.0 * dot {
+        shortest_letter_gap * 5.0 / 3.0
+    } else {
+        5.0 * dot
+    };
+
+    let mut morse = String::new();
+    for (on, len) in merged {
+        let len = len as f32;
+        if on {
+            morse.push(if len > 2.0 * dot { options.dash } else { options.dot });
+        } else if len >= word_gap {
+            morse.push_str(&options.word_separator);
+        } else if len >= 2.0 * dot {
+            morse.push_str(&options.letter_separator);
+        }
+    }
+    Ok(morse)
+}
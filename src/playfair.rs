@@ -0,0 +1,223 @@
+use crate::{shift::letter_index, Cipher, CipherError};
+use std::fmt;
+
+/// The Playfair cipher, which encrypts pairs of letters using their positions
+/// in a 5x5 square of letters built from a keyword.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Playfair;
+
+/// How the alphabet is cut down to the 25 letters that fit in the square.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Merge {
+    /// J is written as I.
+    JIntoI,
+    /// Q is left out entirely.
+    OmitQ,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PlayfairKey {
+    pub keyword: String,
+    pub merge: Merge,
+    /// The letter put between doubled letters, and at the end of odd-length
+    /// text.
+    pub filler: char,
+}
+
+impl fmt::Display for PlayfairKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.keyword)
+    }
+}
+
+/// The 5x5 key square, read left to right and top to bottom.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Square {
+    letters: [char; 25],
+    merge: Merge,
+}
+
+impl Square {
+    /// Fills the square with the letters of `keyword`, without repeats,
+    /// followed by the rest of the alphabet.
+    pub fn new(keyword: &str, merge: Merge) -> Self {
+        let mut letters = ['A'; 25];
+        let mut len = 0;
+        let keyword = keyword.chars().filter_map(|c| normalise(c, merge));
+        for c in keyword.chain(('A'..='Z').filter_map(|c| normalise(c, merge))) {
+            if !letters[..len].contains(&c) {
+                letters[len] = c;
+                len += 1;
+            }
+        }
+        Square { letters, merge }
+    }
+
+    pub fn letters(&self) -> &[char; 25] {
+        &self.letters
+    }
+
+    /// Where `c` is in the square, counting left to right and top to bottom.
+    pub fn position(&self, c: char) -> Option<usize> {
+        let c = normalise(c, self.merge)?;
+        self.letters.iter().position(|&x| x == c)
+    }
+}
+
+/// Uppercases a letter and merges it as the square requires. Returns `None`
+/// for anything that can't be written in the square.
+fn normalise(c: char, merge: Merge) -> Option<char> {
+    letter_index(c)?;
+    match (c.to_ascii_uppercase(), merge) {
+        ('J', Merge::JIntoI) => Some('I'),
+        ('Q', Merge::OmitQ) => None,
+        (c, _) => Some(c),
+    }
+}
+
+/// Which of the three rules a pair of letters is encrypted with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rule {
+    /// Both letters are in the same row, so each is replaced with the letter
+    /// to its right.
+    Row,
+    /// Both letters are in the same column, so each is replaced with the
+    /// letter below it.
+    Column,
+    /// The letters are opposite corners of a rectangle, so each is replaced
+    /// with the other corner in its row.
+    Rectangle,
+}
+
+/// One pair of letters going through the square, and how.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Step {
+    pub input: [char; 2],
+    pub output: [char; 2],
+    /// The positions of the input letters in the square.
+    pub from: [usize; 2],
+    /// The positions of the output letters in the square.
+    pub to: [usize; 2],
+    pub rule: Rule,
+}
+
+fn apply(square: &Square, from: [usize; 2], decrypt: bool) -> Step {
+    let (r1, c1) = (from[0] / 5, from[0] % 5);
+    let (r2, c2) = (from[1] / 5, from[1] % 5);
+    // Moving back one is the same as moving forward four.
+    let step = if decrypt { 4 } else { 1 };
+    let (rule, to) = if r1 == r2 {
+        (Rule::Row, [r1 * 5 + (c1 + step) % 5, r2 * 5 + (c2 + step) % 5])
+    } else if c1 == c2 {
+        (Rule::Column, [(r1 + step) % 5 * 5 + c1, (r2 + step) % 5 * 5 + c2])
+    } else {
+        (Rule::Rectangle, [r1 * 5 + c2, r2 * 5 + c1])
+    };
+    Step {
+        input: [square.letters[from[0]], square.letters[from[1]]],
+        output: [square.letters[to[0]], square.letters[to[1]]],
+        from,
+        to,
+        rule,
+    }
+}
+
+/// Splits the letters of `plaintext` into pairs, putting the filler between
+/// doubled letters and after a lone last letter. If the filler itself is
+/// doubled, X is used instead, or Z if the filler is X.
+pub fn prepare(plaintext: &str, key: &PlayfairKey) -> Result<Vec<[char; 2]>, CipherError> {
+    let filler = normalise(key.filler, key.merge)
+        .ok_or_else(|| CipherError::InvalidKey(String::from("the filler must be a letter in the square")))?;
+    let other = if filler == 'X' { 'Z' } else { 'X' };
+    let fill = |c: char| if c == filler { other } else { filler };
+
+    let mut letters = plaintext.chars().filter_map(|c| normalise(c, key.merge)).peekable();
+    let mut pairs = Vec::new();
+    while let Some(first) = letters.next() {
+        let second = match letters.peek() {
+            Some(&second) if second != first => {
+                letters.next();
+                second
+            },
+            _ => fill(first),
+        };
+        pairs.push([first, second]);
+    }
+    Ok(pairs)
+}
+
+/// The steps that encrypt `plaintext`, pair by pair.
+pub fn encrypt_steps(plaintext: &str, key: &PlayfairKey) -> Result<Vec<Step>, CipherError> {
+    let square = Square::new(&key.keyword, key.merge);
+    let steps = prepare(plaintext, key)?
+        .into_iter()
+        .map(|[a, b]| apply(&square, [square.position(a).unwrap(), square.position(b).unwrap()], false))
+        .collect();
+    Ok(steps)
+}
+
+/// The steps that decrypt `ciphertext`, pair by pair. There must be an even
+/// number of letters, with no pair made of the same letter twice.
+pub fn decrypt_steps(ciphertext: &str, key: &PlayfairKey) -> Result<Vec<Step>, CipherError> {
+    let square = Square::new(&key.keyword, key.merge);
+    let positions = ciphertext
+        .chars()
+        .filter(|&c| letter_index(c).is_some())
+        .map(|c| {
+            square
+                .position(c)
+                .ok_or_else(|| CipherError::InvalidText(format!("{} isn't in the square", c)))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if positions.len() % 2 != 0 {
+        return Err(CipherError::InvalidText(String::from("there must be an even number of letters")));
+    }
+    positions
+        .chunks(2)
+        .map(|pair| {
+            if pair[0] == pair[1] {
+                Err(CipherError::InvalidText(String::from("a pair can't be the same letter twice")))
+            } else {
+                Ok(apply(&square, [pair[0], pair[1]], true))
+            }
+        })
+        .collect()
+}
+
+fn join(steps: &[Step]) -> String {
+    steps.iter().flat_map(|step| step.output.iter()).collect()
+}
+
+/// Encrypts the letters of `plaintext`, dropping everything else. The filler
+/// must be a letter that is in the square.
+pub fn encrypt(plaintext: &str, key: &PlayfairKey) -> Result<String, CipherError> {
+    Ok(join(&encrypt_steps(plaintext, key)?))
+}
+
+/// Decrypts the letters of `ciphertext`. Fillers are left in, since there's no
+/// telling them apart from real letters.
+pub fn decrypt(ciphertext: &str, key: &PlayfairKey) -> Result<String, CipherError> {
+    Ok(join(&decrypt_steps(ciphertext, key)?))
+}
+
+impl Cipher for Playfair {
+    type Key = PlayfairKey;
+
+    fn is_valid_key(&self, key: &PlayfairKey) -> bool {
+        normalise(key.filler, key.merge).is_some()
+    }
+
+    fn encrypt(&self, plaintext: &str, key: &PlayfairKey) -> Result<String, CipherError> {
+        encrypt(plaintext, key)
+    }
+
+    fn decrypt(&self, ciphertext: &str, key: &PlayfairKey) -> Result<String, CipherError> {
+        decrypt(ciphertext, key)
+    }
+
+    /// Recovering the square from a plaintext and ciphertext is a puzzle in
+    /// its own right, so this never finds a key.
+    fn find_key(&self, _plaintext: &str, _ciphertext: &str) -> Result<PlayfairKey, CipherError> {
+        Err(CipherError::Unsupported)
+    }
+}
@@ -0,0 +1,110 @@
+use crate::{Cipher, CipherError};
+use std::fmt;
+
+/// The rail fence cipher, which writes the text in a zigzag across a number of
+/// rails and then reads each rail off in turn.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RailFence;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RailFenceKey {
+    pub rails: usize,
+    /// How far into the zigzag the text starts.
+    pub offset: usize,
+}
+
+impl fmt::Display for RailFenceKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{},{}", self.rails, self.offset)
+    }
+}
+
+/// Parses a key written as `RAILS,OFFSET`, the way keys are displayed, or
+/// just `RAILS` for no offset.
+pub fn parse_key(text: &str) -> Result<RailFenceKey, CipherError> {
+    let mut parts = text.split(',').map(|part| part.trim().parse().ok());
+    match (parts.next().flatten(), parts.next(), parts.next()) {
+        (Some(rails), None, None) => Ok(RailFenceKey { rails, offset: 0 }),
+        (Some(rails), Some(Some(offset)), None) => Ok(RailFenceKey { rails, offset }),
+        _ => Err(CipherError::InvalidKey(String::from("must be RAILS or RAILS,OFFSET"))),
+    }
+}
+
+/// Which rail the character at `index` is written on.
+pub fn rail(index: usize, key: RailFenceKey) -> usize {
+    if key.rails <= 1 {
+        return 0;
+    }
+    let cycle = 2 * (key.rails - 1);
+    let position = (index + key.offset) % cycle;
+    if position < key.rails {
+        position
+    } else {
+        cycle - position
+    }
+}
+
+/// For each character of the ciphertext, where it came from in the plaintext.
+pub fn read_order(len: usize, key: RailFenceKey) -> Vec<usize> {
+    let mut order = (0..len).collect::<Vec<_>>();
+    order.sort_by_key(|&i| rail(i, key));
+    order
+}
+
+fn check_key(key: RailFenceKey) -> Result<(), CipherError> {
+    if key.rails == 0 {
+        return Err(CipherError::InvalidKey(String::from("there must be at least one rail")));
+    }
+    Ok(())
+}
+
+pub fn encrypt(plaintext: &str, key: RailFenceKey) -> Result<String, CipherError> {
+    check_key(key)?;
+    let chars = plaintext.chars().collect::<Vec<_>>();
+    Ok(read_order(chars.len(), key).into_iter().map(|i| chars[i]).collect())
+}
+
+pub fn decrypt(ciphertext: &str, key: RailFenceKey) -> Result<String, CipherError> {
+    check_key(key)?;
+    let chars = ciphertext.chars().collect::<Vec<_>>();
+    let mut plaintext = vec![' '; chars.len()];
+    for (&i, &c) in read_order(chars.len(), key).iter().zip(chars.iter()) {
+        plaintext[i] = c;
+    }
+    Ok(plaintext.into_iter().collect())
+}
+
+/// The most rails that [`find_key`] tries.
+pub const MAX_FIND_RAILS: usize = 50;
+
+/// Tries every number of rails up to [`MAX_FIND_RAILS`] with every offset,
+/// fewest rails first.
+pub fn find_key(plaintext: &str, ciphertext: &str) -> Result<RailFenceKey, CipherError> {
+    if plaintext.chars().count() != ciphertext.chars().count() {
+        return Err(CipherError::MismatchedLengths);
+    }
+    (1..=MAX_FIND_RAILS)
+        .flat_map(|rails| (0..(2 * rails - 2).max(1)).map(move |offset| RailFenceKey { rails, offset }))
+        .find(|&key| encrypt(plaintext, key).as_deref() == Ok(ciphertext))
+        .ok_or(CipherError::NoKey)
+}
+
+impl Cipher for RailFence {
+    type Key = RailFenceKey;
+
+    fn is_valid_key(&self, key: &RailFenceKey) -> bool {
+        key.rails > 0
+    }
+
+    fn encrypt(&self, plaintext: &str, key: &RailFenceKey) -> Result<String, CipherError> {
+        encrypt(plaintext, *key)
+    }
+
+    fn decrypt(&self, ciphertext: &str, key: &RailFenceKey) -> Result<String, CipherError> {
+        decrypt(ciphertext, *key)
+    }
+
+    fn find_key(&self, plaintext: &str, ciphertext: &str) -> Result<RailFenceKey, CipherError> {
+        find_key(plaintext, ciphertext)
+    }
+}
@@ -0,0 +1,508 @@
+//! Chains ciphers and encodings together, each step working on the output of
+//! the one before.
+//!
+//! A recipe is written one step per line: the tool, what to do with it, then
+//! any settings, like `vigenere decrypt key=lemon variant=beaufort`. Settings
+//! are `name=value`, or just `name` for ones that are on or off, and values
+//! with spaces go in double quotes. Blank lines and lines starting with `#`
+//! are ignored.
+
+use crate::{
+    affine::{self, Affine},
+    alphabet::Alphabet,
+    ascii85::{Ascii85, Ascii85Options, Ascii85Variant},
+    base32::{Base32, Base32Alphabet, Base32Options},
+    base58::Base58,
+    base64::{Base64, Base64Options},
+    columnar::{self, Columnar},
+    enigma::{self, Enigma, EnigmaKey},
+    hex::{Hex, HexOptions, Separator},
+    hill::{Hill, Matrix},
+    morse::{self, Morse, MorseOptions},
+    playfair::{Merge, Playfair, PlayfairKey},
+    rail_fence::{self, RailFence},
+    shift::Shift,
+    substitution::{self, Substitution},
+    text_options::{Case, TextOptions},
+    vigenere::{Variant, Vigenere},
+    Cipher,
+    CipherError,
+};
+use std::fmt;
+
+/// Something a step can do, and the settings it takes.
+pub struct Operation {
+    pub name: &'static str,
+    pub settings: &'static str,
+}
+
+const SHIFT_SETTINGS: &str = "key=N alphabet=NAME|LETTERS strip case=keep|upper|lower groups";
+const VIGENERE_SETTINGS: &str = "key=KEY variant=NAME alphabet=NAME|LETTERS strip case=keep|upper|lower groups key-on-letters";
+const AFFINE_SETTINGS: &str = "key=A,B";
+const SUBSTITUTION_SETTINGS: &str = "key=ALPHABET or keyword=KEYWORD";
+const PLAYFAIR_SETTINGS: &str = "key=KEYWORD filler=X omit-q";
+const HILL_SETTINGS: &str = "key=3,3;2,5 or keyword=KEYWORD size=N";
+const ENIGMA_SETTINGS: &str = "model=I|M3|M4 reflector=B rotors=I,II,III rings=AAA positions=AAA plugboard=\"AB CD\"";
+const RAIL_FENCE_SETTINGS: &str = "key=RAILS,OFFSET";
+const COLUMNAR_SETTINGS: &str = "key=KEYWORD,SECOND padding=X";
+const BASE64_SETTINGS: &str = "url-safe no-padding wrap";
+const HEX_SETTINGS: &str = "separator=none|space|colon|dash upper";
+const BASE32_SETTINGS: &str = "crockford no-padding";
+const BASE58_SETTINGS: &str = "check";
+const ASCII85_SETTINGS: &str = "z85 delimiters";
+const MORSE_SETTINGS: &str = "dot=. dash=- letter-separator=\" \" word-separator=\" / \"";
+
+pub const OPERATIONS: [Operation; 30] = [
+    Operation { name: "shift encrypt", settings: SHIFT_SETTINGS },
+    Operation { name: "shift decrypt", settings: SHIFT_SETTINGS },
+    Operation { name: "vigenere encrypt", settings: VIGENERE_SETTINGS },
+    Operation { name: "vigenere decrypt", settings: VIGENERE_SETTINGS },
+    Operation { name: "affine encrypt", settings: AFFINE_SETTINGS },
+    Operation { name: "affine decrypt", settings: AFFINE_SETTINGS },
+    Operation { name: "substitution encrypt", settings: SUBSTITUTION_SETTINGS },
+    Operation { name: "substitution decrypt", settings: SUBSTITUTION_SETTINGS },
+    Operation { name: "playfair encrypt", settings: PLAYFAIR_SETTINGS },
+    Operation { name: "playfair decrypt", settings: PLAYFAIR_SETTINGS },
+    Operation { name: "hill encrypt", settings: HILL_SETTINGS },
+    Operation { name: "hill decrypt", settings: HILL_SETTINGS },
+    Operation { name: "enigma encrypt", settings: ENIGMA_SETTINGS },
+    Operation { name: "enigma decrypt", settings: ENIGMA_SETTINGS },
+    Operation { name: "rail-fence encrypt", settings: RAIL_FENCE_SETTINGS },
+    Operation { name: "rail-fence decrypt", settings: RAIL_FENCE_SETTINGS },
+    Operation { name: "columnar encrypt", settings: COLUMNAR_SETTINGS },
+    Operation { name: "columnar decrypt", settings: COLUMNAR_SETTINGS },
+    Operation { name: "base64 encode", settings: BASE64_SETTINGS },
+    Operation { name: "base64 decode", settings: BASE64_SETTINGS },
+    Operation { name: "hex encode", settings: HEX_SETTINGS },
+    Operation { name: "hex decode", settings: HEX_SETTINGS },
+    Operation { name: "base32 encode", settings: BASE32_SETTINGS },
+    Operation { name: "base32 decode", settings: BASE32_SETTINGS },
+    Operation { name: "base58 encode", settings: BASE58_SETTINGS },
+    Operation { name: "base58 decode", settings: BASE58_SETTINGS },
+    Operation { name: "ascii85 encode", settings: ASCII85_SETTINGS },
+    Operation { name: "ascii85 decode", settings: ASCII85_SETTINGS },
+    Operation { name: "morse encode", settings: MORSE_SETTINGS },
+    Operation { name: "morse decode", settings: MORSE_SETTINGS },
+];
+
+/// One step of a recipe: an operation from [`OPERATIONS`] by name, and its
+/// settings as they are written.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Step {
+    pub operation: String,
+    pub settings: String,
+}
+
+/// Why a step can't be run.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StepError {
+    /// There is no operation with this name.
+    UnknownOperation(String),
+    /// A setting is unknown, missing or can't be used, for the reason given.
+    InvalidSetting(String),
+    /// The key can't be used, or the cipher or encoding failed.
+    Cipher(CipherError),
+}
+
+impl fmt::Display for StepError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StepError::UnknownOperation(name) => write!(f, "there is no operation called {}", name),
+            StepError::InvalidSetting(reason) => write!(f, "{}", reason),
+            StepError::Cipher(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl From<CipherError> for StepError {
+    fn from(e: CipherError) -> Self {
+        StepError::Cipher(e)
+    }
+}
+
+/// Why a recipe couldn't be read or run. Lines and steps count from 1.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RecipeError {
+    /// The step on this line of the recipe's text can't be run.
+    Line(usize, StepError),
+    /// This step failed when the recipe was run.
+    Step(usize, StepError),
+}
+
+impl fmt::Display for RecipeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RecipeError::Line(line, e) => write!(f, "line {}: {}", line, e),
+            RecipeError::Step(step, e) => write!(f, "step {}: {}", step, e),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Recipe {
+    pub steps: Vec<Step>,
+}
+
+impl fmt::Display for Recipe {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for step in &self.steps {
+            writeln!(f, "{}", format!("{} {}", step.operation, step.settings).trim())?;
+        }
+        Ok(())
+    }
+}
+
+impl Recipe {
+    /// Reads a recipe written as described in the [module docs](self),
+    /// checking that each step's settings and key can be used.
+    pub fn parse(text: &str) -> Result<Self, RecipeError> {
+        let mut steps = Vec::new();
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut words = line.splitn(3, char::is_whitespace);
+            let operation = format!("{} {}", words.next().unwrap(), words.next().unwrap_or_default());
+            if !OPERATIONS.iter().any(|x| x.name == operation) {
+                return Err(RecipeError::Line(i + 1, StepError::UnknownOperation(String::from(operation.trim()))));
+            }
+            let step = Step {
+                operation,
+                settings: String::from(words.next().unwrap_or_default().trim()),
+            };
+            if let Err(e) = step.prepare() {
+                return Err(RecipeError::Line(i + 1, e));
+            }
+            steps.push(step);
+        }
+        Ok(Recipe { steps })
+    }
+
+    /// Runs each step on the output of the one before, returning what each
+    /// step gave. Stops after the first step that fails.
+    pub fn run(&self, input: &str) -> Vec<Result<String, RecipeError>> {
+        let mut outputs = Vec::new();
+        let mut text = String::from(input);
+        for (i, step) in self.steps.iter().enumerate() {
+            match step.apply(&text).map_err(|e| RecipeError::Step(i + 1, e)) {
+                Ok(output) => {
+                    text = output.clone();
+                    outputs.push(Ok(output));
+                },
+                Err(e) => {
+                    outputs.push(Err(e));
+                    break;
+                },
+            }
+        }
+        outputs
+    }
+}
+
+/// The settings of a step, parsed.
+struct Settings(Vec<(String, Option<String>)>);
+
+impl Settings {
+    fn parse(text: &str) -> Result<Self, StepError> {
+        let mut settings = Vec::new();
+        let mut chars = text.chars().peekable();
+        loop {
+            while chars.peek().is_some_and(|c| c.is_whitespace()) {
+                chars.next();
+            }
+            if chars.peek().is_none() {
+                break;
+            }
+            let mut name = String::new();
+            while let Some(&c) = chars.peek() {
+                if c.is_whitespace() || c == '=' {
+                    break;
+                }
+                name.push(c);
+                chars.next();
+            }
+            if chars.peek() != Some(&'=') {
+                settings.push((name, None));
+                continue;
+            }
+            chars.next();
+            let mut value = String::new();
+            if chars.peek() == Some(&'"') {
+                chars.next();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some(c) => value.push(c),
+                        None => {
+                            let reason = format!("the value of {} has no closing quote", name);
+                            return Err(StepError::InvalidSetting(reason));
+                        },
+                    }
+                }
+            } else {
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() {
+                        break;
+                    }
+                    value.push(c);
+                    chars.next();
+                }
+            }
+            settings.push((name, Some(value)));
+        }
+        Ok(Settings(settings))
+    }
+
+    /// Fails if there's a setting that isn't in `known`.
+    fn check(&self, known: &[&str]) -> Result<(), StepError> {
+        match self.0.iter().find(|(name, _)| !known.contains(&name.as_str())) {
+            Some((name, _)) => Err(StepError::InvalidSetting(format!("there is no setting called {}", name))),
+            None => Ok(()),
+        }
+    }
+
+    fn value(&self, name: &str) -> Option<&str> {
+        self.0.iter().find(|(x, _)| x == name).and_then(|(_, value)| value.as_deref())
+    }
+
+    fn required(&self, name: &str) -> Result<&str, StepError> {
+        self.value(name).ok_or_else(|| StepError::InvalidSetting(format!("{} must be set", name)))
+    }
+
+    fn flag(&self, name: &str) -> bool {
+        self.0.iter().any(|(x, _)| x == name)
+    }
+
+    /// A setting that must be one character, if it is set.
+    fn char(&self, name: &str) -> Result<Option<char>, StepError> {
+        match self.value(name) {
+            Some(value) => {
+                let mut chars = value.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => Ok(Some(c)),
+                    _ => Err(StepError::InvalidSetting(format!("invalid {}: must be one character", name))),
+                }
+            },
+            None => Ok(None),
+        }
+    }
+
+    fn alphabet(&self) -> Result<Alphabet, StepError> {
+        Alphabet::parse(self.value("alphabet").unwrap_or("english"))
+            .map_err(|e| StepError::InvalidSetting(format!("invalid alphabet: {}", e)))
+    }
+
+    fn text_options(&self) -> Result<TextOptions, StepError> {
+        Ok(TextOptions {
+            key_on_letters: self.flag("key-on-letters"),
+            strip: self.flag("strip"),
+            case: match self.value("case") {
+                None | Some("keep") => Case::Keep,
+                Some("upper") => Case::Upper,
+                Some("lower") => Case::Lower,
+                Some(_) => {
+                    return Err(StepError::InvalidSetting(String::from("invalid case: must be keep, upper or lower")))
+                },
+            },
+            groups: self.flag("groups"),
+        })
+    }
+}
+
+const TEXT_OPTIONS: [&str; 3] = ["strip", "case", "groups"];
+
+/// A step that is ready to run on some text.
+type Prepared = Box<dyn Fn(&str) -> Result<String, CipherError>>;
+
+fn cipher_step<C>(cipher: C, key: C::Key, decrypt: bool) -> Prepared
+where
+    C: Cipher + 'static,
+    C::Key: 'static,
+{
+    Box::new(move |text| {
+        if decrypt {
+            cipher.decrypt(text, &key)
+        } else {
+            cipher.encrypt(text, &key)
+        }
+    })
+}
+
+fn enigma_key(settings: &Settings) -> Result<EnigmaKey, CipherError> {
+    enigma::parse_key(
+        settings.value("model").unwrap_or("I"),
+        settings.value("reflector").unwrap_or("B"),
+        settings.value("rotors").unwrap_or("I,II,III"),
+        settings.value("rings"),
+        settings.value("positions"),
+        settings.value("plugboard").unwrap_or_default(),
+    )
+}
+
+impl Step {
+    /// Runs this step on `text`.
+    pub fn apply(&self, text: &str) -> Result<String, StepError> {
+        Ok(self.prepare()?(text)?)
+    }
+
+    /// Reads the step's settings and key, without running it.
+    fn prepare(&self) -> Result<Prepared, StepError> {
+        let settings = Settings::parse(&self.settings)?;
+        let (tool, action) = self.operation.split_once(' ').unwrap_or((&self.operation, ""));
+        let decrypt = action == "decrypt" || action == "decode";
+        match tool {
+            "shift" => {
+                settings.check(&[&["key", "alphabet"][..], &TEXT_OPTIONS].concat())?;
+                let cipher = Shift {
+                    alphabet: settings.alphabet()?,
+                    options: settings.text_options()?,
+                };
+                let key = settings
+                    .required("key")?
+                    .parse()
+                    .ok()
+                    .filter(|key| cipher.is_valid_key(key))
+                    .ok_or_else(|| {
+                        CipherError::InvalidKey(format!("must be a number from 0 to {}", cipher.alphabet.len() - 1))
+                    })?;
+                Ok(cipher_step(cipher, key, decrypt))
+            },
+            "vigenere" => {
+                settings.check(&[&["key", "variant", "alphabet", "key-on-letters"][..], &TEXT_OPTIONS].concat())?;
+                let cipher = Vigenere {
+                    alphabet: settings.alphabet()?,
+                    options: settings.text_options()?,
+                    variant: Variant::parse(settings.value("variant").unwrap_or("vigenere"))
+                        .ok_or_else(|| StepError::InvalidSetting(String::from("invalid variant")))?,
+                };
+                Ok(cipher_step(cipher, String::from(settings.required("key")?), decrypt))
+            },
+            "affine" => {
+                settings.check(&["key"])?;
+                let key = affine::parse_key(settings.required("key")?)?;
+                Ok(cipher_step(Affine, key, decrypt))
+            },
+            "substitution" => {
+                settings.check(&["key", "keyword"])?;
+                let key = match settings.value("keyword") {
+                    Some(keyword) => substitution::keyword_alphabet(keyword),
+                    None => String::from(settings.required("key")?),
+                };
+                Ok(cipher_step(Substitution, key, decrypt))
+            },
+            "playfair" => {
+                settings.check(&["key", "filler", "omit-q"])?;
+                let key = PlayfairKey {
+                    keyword: String::from(settings.value("key").unwrap_or_default()),
+                    merge: if settings.flag("omit-q") { Merge::OmitQ } else { Merge::JIntoI },
+                    filler: settings.char("filler")?.unwrap_or('X'),
+                };
+                Ok(cipher_step(Playfair, key, decrypt))
+            },
+            "hill" => {
+                settings.check(&["key", "keyword", "size"])?;
+                let key = match settings.value("keyword") {
+                    Some(keyword) => {
+                        let size = settings
+                            .value("size")
+                            .unwrap_or("2")
+                            .parse()
+                            .map_err(|_| StepError::InvalidSetting(String::from("invalid size: must be a number")))?;
+                        Matrix::from_keyword(keyword, size).ok_or_else(|| {
+                            CipherError::InvalidKey(String::from("the keyword is too short to fill the matrix"))
+                        })?
+                    },
+                    None => Matrix::parse(settings.required("key")?)
+                        .ok_or_else(|| CipherError::InvalidKey(String::from("must be rows of numbers, like 3,3;2,5")))?,
+                };
+                Ok(cipher_step(Hill, key, decrypt))
+            },
+            "enigma" => {
+                settings.check(&["model", "reflector", "rotors", "rings", "positions", "plugboard"])?;
+                Ok(cipher_step(Enigma, enigma_key(&settings)?, decrypt))
+            },
+            "rail-fence" => {
+                settings.check(&["key"])?;
+                let key = rail_fence::parse_key(settings.required("key")?)?;
+                Ok(cipher_step(RailFence, key, decrypt))
+            },
+            "columnar" => {
+                settings.check(&["key", "padding"])?;
+                let key = columnar::parse_key(settings.required("key")?, settings.char("padding")?)
+                    ?;
+                Ok(cipher_step(Columnar, key, decrypt))
+            },
+            "base64" => {
+                settings.check(&["url-safe", "no-padding", "wrap"])?;
+                let options = Base64Options {
+                    url_safe: settings.flag("url-safe"),
+                    no_padding: settings.flag("no-padding"),
+                    wrap: settings.flag("wrap"),
+                };
+                Ok(cipher_step(Base64 { options }, (), decrypt))
+            },
+            "hex" => {
+                settings.check(&["separator", "upper"])?;
+                let options = HexOptions {
+                    separator: match settings.value("separator").unwrap_or("none") {
+                        "none" => Separator::None,
+                        "space" => Separator::Space,
+                        "colon" => Separator::Colon,
+                        "dash" => Separator::Dash,
+                        _ => {
+                            return Err(StepError::InvalidSetting(String::from(
+                                "invalid separator: must be none, space, colon or dash",
+                            )))
+                        },
+                    },
+                    upper: settings.flag("upper"),
+                };
+                Ok(cipher_step(Hex { options }, (), decrypt))
+            },
+            "base32" => {
+                settings.check(&["crockford", "no-padding"])?;
+                let options = Base32Options {
+                    alphabet: if settings.flag("crockford") {
+                        Base32Alphabet::Crockford
+                    } else {
+                        Base32Alphabet::Rfc4648
+                    },
+                    no_padding: settings.flag("no-padding"),
+                };
+                Ok(cipher_step(Base32 { options }, (), decrypt))
+            },
+            "base58" => {
+                settings.check(&["check"])?;
+                Ok(cipher_step(Base58 { check: settings.flag("check") }, (), decrypt))
+            },
+            "ascii85" => {
+                settings.check(&["z85", "delimiters"])?;
+                let options = Ascii85Options {
+                    variant: if settings.flag("z85") {
+                        Ascii85Variant::Z85
+                    } else {
+                        Ascii85Variant::Adobe
+                    },
+                    delimiters: settings.flag("delimiters"),
+                };
+                Ok(cipher_step(Ascii85 { options }, (), decrypt))
+            },
+            "morse" => {
+                settings.check(&["dot", "dash", "letter-separator", "word-separator"])?;
+                let defaults = MorseOptions::default();
+                let options = MorseOptions {
+                    dot: settings.char("dot")?.unwrap_or(defaults.dot),
+                    dash: settings.char("dash")?.unwrap_or(defaults.dash),
+                    letter_separator: settings.value("letter-separator").map_or(defaults.letter_separator, String::from),
+                    word_separator: settings.value("word-separator").map_or(defaults.word_separator, String::from),
+                };
+                if let Some(error) = morse::options_error(&options) {
+                    return Err(StepError::InvalidSetting(format!("invalid options: {}", error)));
+                }
+                Ok(cipher_step(Morse { options }, (), decrypt))
+            },
+            _ => Err(StepError::UnknownOperation(self.operation.clone())),
+        }
+    }
+}
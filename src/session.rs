@@ -0,0 +1,218 @@
+//! Saving everything in the window to a file, so that it can be picked up
+//! again later. The last session is kept in the config directory and
+//! restored at start.
+
+use crate::{
+    tools::identify::HAND_OFF,
+    widget::file_buttons::{OPEN_FOR, SAVE_FOR},
+    State,
+};
+use ciphertools::identify::FAMILIES;
+use druid::{
+    commands, AppDelegate, Command, DelegateCtx, Env, FileDialogOptions, FileSpec, LocalizedString,
+    MenuDesc, MenuItem, Selector, SysMods, Target, WidgetId, WindowId,
+};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+const OPEN_SESSION: Selector = Selector::new("ciphertools.open-session");
+const SAVE_SESSION_AS: Selector = Selector::new("ciphertools.save-session-as");
+
+const SESSION_FILE: FileSpec = FileSpec::new("CipherTools session", &["json"]);
+
+/// The directory for this platform's per-user settings.
+fn config_dir() -> Option<PathBuf> {
+    if cfg!(target_os = "windows") {
+        return std::env::var_os("APPDATA").map(PathBuf::from);
+    }
+    let home = std::env::var_os("HOME").map(PathBuf::from);
+    if cfg!(target_os = "macos") {
+        return home.map(|home| home.join("Library").join("Application Support"));
+    }
+    match std::env::var_os("XDG_CONFIG_HOME") {
+        Some(dir) if !dir.is_empty() => Some(PathBuf::from(dir)),
+        _ => home.map(|home| home.join(".config")),
+    }
+}
+
+/// Where the last session is kept between runs.
+pub fn default_path() -> Option<PathBuf> {
+    Some(config_dir()?.join("ciphertools").join("session.json"))
+}
+
+pub fn load(path: &Path) -> Result<State, String> {
+    let json =
+        fs::read_to_string(path).map_err(|e| format!("couldn't read {}: {}", path.display(), e))?;
+    serde_json::from_str(&json)
+        .map_err(|e| format!("{} isn't a session file: {}", path.display(), e))
+}
+
+pub fn save(state: &State, path: &Path) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("couldn't create {}: {}", dir.display(), e))?;
+    }
+    let json = serde_json::to_string_pretty(state).map_err(|e| e.to_string())?;
+    fs::write(path, json).map_err(|e| format!("couldn't write {}: {}", path.display(), e))
+}
+
+/// The last session, or a fresh one if there isn't one or it can't be read.
+pub fn restore() -> State {
+    let path = match default_path() {
+        Some(path) if path.exists() => path,
+        _ => return State::default(),
+    };
+    load(&path).unwrap_or_else(|e| State {
+        session_status: format!("Starting a new session: {}", e),
+        ..State::default()
+    })
+}
+
+fn save_last(state: &State) {
+    if let Some(path) = default_path() {
+        if let Err(e) = save(state, &path) {
+            // The window has closed by now, so there's nowhere else to say.
+            eprintln!("Couldn't save the session: {}", e);
+        }
+    }
+}
+
+/// Handles the File menu, the open and save buttons on fields and handing text
+/// from the Identify tab to another, and saves the session when the window
+/// closes.
+#[derive(Default)]
+pub struct Delegate {
+    /// The session file that was last opened or saved, which Save writes to.
+    path: Option<PathBuf>,
+    /// The widget that asked for the file dialog being shown, or `None` if
+    /// it's for a session.
+    field: Option<WidgetId>,
+}
+
+impl AppDelegate<State> for Delegate {
+    fn command(
+        &mut self,
+        ctx: &mut DelegateCtx,
+        target: Target,
+        cmd: &Command,
+        data: &mut State,
+        _env: &Env,
+    ) -> bool {
+        if let Target::Widget(_) = target {
+            // Files that have already been sent on to a field.
+            return true;
+        }
+        if let Some(&id) = cmd.get(OPEN_FOR) {
+            self.field = Some(id);
+            ctx.submit_command(commands::SHOW_OPEN_PANEL.with(FileDialogOptions::new()), target);
+        } else if let Some(&id) = cmd.get(SAVE_FOR) {
+            self.field = Some(id);
+            ctx.submit_command(commands::SHOW_SAVE_PANEL.with(FileDialogOptions::new()), target);
+        } else if cmd.is(OPEN_SESSION) {
+            self.field = None;
+            ctx.submit_command(commands::SHOW_OPEN_PANEL.with(dialog_options()), target);
+        } else if cmd.is(SAVE_SESSION_AS) {
+            self.field = None;
+            ctx.submit_command(commands::SHOW_SAVE_PANEL.with(dialog_options()), target);
+        } else if let Some((family, text)) = cmd.get(HAND_OFF) {
+            data.hand_off(FAMILIES[*family], text.clone());
+        } else if cmd.is(commands::NEW_FILE) {
+            *data = State::default();
+            self.path = None;
+        } else if let Some(info) = cmd.get(commands::OPEN_FILE) {
+            if let Some(id) = self.field.take() {
+                ctx.submit_command(commands::OPEN_FILE.with(info.clone()), Target::Widget(id));
+                return false;
+            }
+            match load(info.path()) {
+                Ok(state) => {
+                    *data = state;
+                    self.path = Some(info.path().to_path_buf());
+                },
+                Err(e) => data.session_status = format!("Couldn't open the session: {}", e),
+            }
+        } else if let Some(info) = cmd.get(commands::SAVE_FILE) {
+            if let (Some(info), Some(id)) = (info, self.field.take()) {
+                ctx.submit_command(commands::SAVE_FILE.with(Some(info.clone())), Target::Widget(id));
+                return false;
+            }
+            let path = match (info, &self.path) {
+                (Some(info), _) => info.path().to_path_buf(),
+                (None, Some(path)) => path.clone(),
+                (None, None) => {
+                    ctx.submit_command(commands::SHOW_SAVE_PANEL.with(dialog_options()), target);
+                    return false;
+                },
+            };
+            match save(data, &path) {
+                Ok(()) => {
+                    data.session_status = String::new();
+                    self.path = Some(path);
+                },
+                Err(e) => data.session_status = format!("Couldn't save the session: {}", e),
+            }
+        } else {
+            return true;
+        }
+        false
+    }
+
+    fn window_removed(
+        &mut self,
+        _id: WindowId,
+        data: &mut State,
+        _env: &Env,
+        _ctx: &mut DelegateCtx,
+    ) {
+        save_last(data);
+    }
+}
+
+fn dialog_options() -> FileDialogOptions {
+    FileDialogOptions::new()
+        .allowed_types(vec![SESSION_FILE])
+        .default_type(SESSION_FILE)
+}
+
+fn item(id: &'static str, name: &'static str, command: impl Into<Command>) -> MenuItem<State> {
+    MenuItem::new(LocalizedString::new(id).with_placeholder(name), command)
+}
+
+pub fn menu() -> MenuDesc<State> {
+    let file =
+        MenuDesc::new(LocalizedString::new("ciphertools-menu-file").with_placeholder("File"))
+            .append(
+                item("ciphertools-menu-new", "New Session", commands::NEW_FILE)
+                    .hotkey(SysMods::Cmd, "n"),
+            )
+            .append(
+                item(
+                    "ciphertools-menu-open",
+                    "Open Session...",
+                    OPEN_SESSION,
+                )
+                .hotkey(SysMods::Cmd, "o"),
+            )
+            .append(
+                item(
+                    "ciphertools-menu-save",
+                    "Save Session",
+                    commands::SAVE_FILE.with(None),
+                )
+                .hotkey(SysMods::Cmd, "s"),
+            )
+            .append(
+                item(
+                    "ciphertools-menu-save-as",
+                    "Save Session As...",
+                    SAVE_SESSION_AS,
+                )
+                .hotkey(SysMods::CmdShift, "s"),
+            )
+            .append_separator()
+            .append(item("ciphertools-menu-quit", "Quit", commands::CLOSE_ALL_WINDOWS));
+    MenuDesc::platform_default()
+        .unwrap_or_else(MenuDesc::empty)
+        .append(file)
+}
@@ -0,0 +1,158 @@
+use crate::{
+    alphabet::Alphabet,
+    analysis::chi_squared,
+    text_options::{most_common, Column, Mark, Pair, TextOptions},
+    Cipher,
+    CipherError,
+};
+
+/// The shift (Caesar) cipher over an alphabet. The key is the number of
+/// places to shift by.
+#[derive(Clone, Debug, Default)]
+pub struct Shift {
+    pub alphabet: Alphabet,
+    pub options: TextOptions,
+}
+
+/// Returns the position of an ASCII letter in the alphabet, ignoring case.
+pub(crate) fn letter_index(c: char) -> Option<u32> {
+    match c {
+        'a'..='z' => Some(c as u32 - 97),
+        'A'..='Z' => Some(c as u32 - 65),
+        _ => None,
+    }
+}
+
+/// Lines up the characters of `plaintext` and `ciphertext` for ciphers that
+/// only change ASCII letters and keep their case, as each letter's index in
+/// the plaintext along with it and the letter it became.
+pub(crate) fn letter_pairs(plaintext: &str, ciphertext: &str) -> Result<Vec<(usize, char, char)>, CipherError> {
+    if plaintext.chars().count() != ciphertext.chars().count() {
+        return Err(CipherError::MismatchedLengths);
+    }
+    let mut pairs = Vec::new();
+    for (index, (plain, cipher)) in plaintext.chars().zip(ciphertext.chars()).enumerate() {
+        match (letter_index(plain), letter_index(cipher)) {
+            (Some(_), Some(_)) => {
+                if plain.is_ascii_uppercase() != cipher.is_ascii_uppercase() {
+                    return Err(CipherError::MismatchedCase(index));
+                }
+                pairs.push((index, plain, cipher));
+            },
+            (None, None) if plain == cipher => {},
+            _ => return Err(CipherError::NonAlphabeticMismatch(index)),
+        }
+    }
+    Ok(pairs)
+}
+
+pub fn shift(plaintext: &str, count: u32, alphabet: &Alphabet) -> String {
+    plaintext.chars().map(|c| alphabet.shift(c, count)).collect()
+}
+
+pub fn find_key(
+    plaintext: &str,
+    ciphertext: &str,
+    alphabet: &Alphabet,
+    options: &TextOptions,
+) -> Result<u32, CipherError> {
+    let mut current = None;
+    for pair in options.align(plaintext, ciphertext, alphabet)? {
+        let key = (pair.cipher + alphabet.len() - pair.plain) % alphabet.len();
+        if let Some(current) = current {
+            if current != key {
+                return Err(CipherError::InconsistentKey(pair.index));
+            }
+        } else {
+            current = Some(key);
+        }
+    }
+    current.ok_or_else(no_letters)
+}
+
+/// The error for texts with no letters to find a key from.
+pub(crate) fn no_letters() -> CipherError {
+    CipherError::InvalidText(String::from("there are no letters to find the key from"))
+}
+
+/// Lines up `plaintext` with `ciphertext`, with the letter for the count that
+/// takes each plaintext letter to its ciphertext letter. Letters that don't
+/// agree with the most common count are marked as inconsistent.
+pub fn compare(plaintext: &str, ciphertext: &str, alphabet: &Alphabet, options: &TextOptions) -> Vec<Column> {
+    let n = alphabet.len();
+    let columns = options.compare(plaintext, ciphertext, alphabet);
+    let count = |pair: &Pair| (pair.cipher + n - pair.plain) % n;
+    let common = most_common(columns.iter().filter_map(|(_, pair)| pair.as_ref().map(count)));
+    columns
+        .into_iter()
+        .map(|(mut column, pair)| {
+            if let Some(pair) = pair {
+                column.key = Some(alphabet.letter(count(&pair), false));
+                if column.mark == Mark::Fine && Some(count(&pair)) != common {
+                    column.mark = Mark::Inconsistent;
+                }
+            }
+            column
+        })
+        .collect()
+}
+
+/// A possible decryption of a ciphertext, as found by [`crack`].
+#[derive(Clone, Debug)]
+pub struct Candidate {
+    pub count: u32,
+    pub plaintext: String,
+    /// The chi-squared score of the plaintext against English; lower is better.
+    pub score: f64,
+}
+
+/// Decrypts `ciphertext` with every possible count, most English-looking first.
+/// Only the English letters in the alphabet count towards the score.
+pub fn crack(ciphertext: &str, alphabet: &Alphabet) -> Vec<Candidate> {
+    let mut candidates = (0..alphabet.len())
+        .map(|count| {
+            let plaintext = shift(ciphertext, alphabet.len() - count, alphabet);
+            Candidate {
+                count,
+                score: chi_squared(&plaintext),
+                plaintext,
+            }
+        })
+        .collect::<Vec<_>>();
+    candidates.sort_by(|a, b| a.score.partial_cmp(&b.score).unwrap());
+    candidates
+}
+
+impl Shift {
+    fn check_key(&self, key: u32) -> Result<(), CipherError> {
+        if self.is_valid_key(&key) {
+            Ok(())
+        } else {
+            Err(CipherError::InvalidKey(format!("the count must be less than {}", self.alphabet.len())))
+        }
+    }
+}
+
+impl Cipher for Shift {
+    type Key = u32;
+
+    fn is_valid_key(&self, key: &u32) -> bool {
+        *key < self.alphabet.len()
+    }
+
+    fn encrypt(&self, plaintext: &str, key: &u32) -> Result<String, CipherError> {
+        self.check_key(*key)?;
+        let ciphertext = shift(plaintext, *key, &self.alphabet);
+        Ok(self.options.format(&ciphertext, &self.alphabet, true))
+    }
+
+    fn decrypt(&self, ciphertext: &str, key: &u32) -> Result<String, CipherError> {
+        self.check_key(*key)?;
+        let plaintext = shift(ciphertext, self.alphabet.len() - key, &self.alphabet);
+        Ok(self.options.format(&plaintext, &self.alphabet, false))
+    }
+
+    fn find_key(&self, plaintext: &str, ciphertext: &str) -> Result<u32, CipherError> {
+        find_key(plaintext, ciphertext, &self.alphabet, &self.options)
+    }
+}
@@ -0,0 +1,271 @@
+use crate::{
+    analysis::Quadgrams,
+    shift::{letter_index, letter_pairs},
+    Cipher,
+    CipherError,
+};
+
+/// The general monoalphabetic substitution cipher. The key is the cipher
+/// alphabet: the letters that A to Z are replaced with, in order.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Substitution;
+
+const ENGLISH_BY_FREQUENCY: &[u8; 26] = b"etaoinshrdlcumwfgypbvkjxqz";
+
+/// Which plaintext letter each ciphertext letter stands for, as far as is known.
+pub type Mapping = [Option<u32>; 26];
+
+/// Builds a cipher alphabet from a keyword: the letters of the keyword, without
+/// repeats, followed by the rest of the alphabet in order.
+pub fn keyword_alphabet(keyword: &str) -> String {
+    let mut alphabet = String::with_capacity(26);
+    let letters = keyword
+        .chars()
+        .filter(|c| c.is_ascii_alphabetic())
+        .map(|c| c.to_ascii_uppercase())
+        .chain('A'..='Z');
+    for c in letters {
+        if !alphabet.contains(c) {
+            alphabet.push(c);
+        }
+    }
+    alphabet
+}
+
+/// Parses a cipher alphabet into the position of each letter's replacement.
+/// Fails unless it is a permutation of the 26 letters.
+fn parse_key(key: &str) -> Result<[u32; 26], CipherError> {
+    let mut parsed = [0; 26];
+    let mut seen = [false; 26];
+    let mut len = 0;
+    for (i, c) in key.chars().enumerate() {
+        let x = letter_index(c).ok_or(CipherError::InvalidKeyChar(i))?;
+        if len == 26 || seen[x as usize] {
+            return Err(CipherError::InvalidKeyChar(i));
+        }
+        seen[x as usize] = true;
+        parsed[len] = x;
+        len += 1;
+    }
+    if len == 26 {
+        Ok(parsed)
+    } else {
+        Err(CipherError::InvalidKey(String::from("must use each letter exactly once")))
+    }
+}
+
+/// Replaces each letter `x` with `table[x]`, preserving case.
+fn substitute(text: &str, table: &[u32; 26]) -> String {
+    text.chars()
+        .map(|c| match letter_index(c) {
+            Some(x) => {
+                let base = if c.is_ascii_uppercase() { 65 } else { 97 };
+                std::char::from_u32(table[x as usize] + base).unwrap()
+            },
+            None => c,
+        })
+        .collect()
+}
+
+fn invert(table: &[u32; 26]) -> [u32; 26] {
+    let mut inverse = [0; 26];
+    for (x, &y) in table.iter().enumerate() {
+        inverse[y as usize] = x as u32;
+    }
+    inverse
+}
+
+pub fn encrypt(plaintext: &str, key: &str) -> Result<String, CipherError> {
+    Ok(substitute(plaintext, &parse_key(key)?))
+}
+
+pub fn decrypt(ciphertext: &str, key: &str) -> Result<String, CipherError> {
+    Ok(substitute(ciphertext, &invert(&parse_key(key)?)))
+}
+
+/// Finds a key that encrypts `plaintext` to `ciphertext`. Letters that don't
+/// appear in the plaintext are given the unused ciphertext letters in order.
+pub fn find_key(plaintext: &str, ciphertext: &str) -> Result<String, CipherError> {
+    let mut table = [None; 26];
+    let mut used = [None; 26];
+    for (index, plain, cipher) in letter_pairs(plaintext, ciphertext)? {
+        let (p, c) = (letter_index(plain).unwrap(), letter_index(cipher).unwrap());
+        // Each letter has to have one replacement, and no two letters the same
+        // one.
+        if table[p as usize].is_some_and(|existing| existing != c) || used[c as usize].is_some_and(|x| x != p) {
+            return Err(CipherError::InconsistentKey(index));
+        }
+        table[p as usize] = Some(c);
+        used[c as usize] = Some(p);
+    }
+
+    let mut unused = (0..26).filter(|x| used[*x as usize].is_none());
+    let key = table
+        .iter()
+        .map(|x| std::char::from_u32(x.or_else(|| unused.next()).unwrap() + 65).unwrap())
+        .collect::<String>();
+    Ok(key)
+}
+
+/// Decrypts as much of `ciphertext` as `mapping` covers. Letters with no
+/// known plaintext become underscores.
+pub fn decrypt_partial(ciphertext: &str, mapping: &Mapping) -> String {
+    ciphertext
+        .chars()
+        .map(|c| match letter_index(c) {
+            Some(x) => match mapping[x as usize] {
+                Some(p) => {
+                    let base = if c.is_ascii_uppercase() { 65 } else { 97 };
+                    std::char::from_u32(p + base).unwrap()
+                },
+                None => '_',
+            },
+            None => c,
+        })
+        .collect()
+}
+
+/// Finds the ciphertext letters that are mapped to the same plaintext letter
+/// as another ciphertext letter.
+pub fn conflicts(mapping: &Mapping) -> [bool; 26] {
+    let mut conflicts = [false; 26];
+    for (i, x) in mapping.iter().enumerate() {
+        if x.is_some() {
+            conflicts[i] = mapping.iter().enumerate().any(|(j, y)| i != j && x == y);
+        }
+    }
+    conflicts
+}
+
+/// The cipher alphabet for a complete mapping with no conflicts.
+pub fn mapping_to_key(mapping: &Mapping) -> Option<String> {
+    let mut key = ['?'; 26];
+    for (cipher, plain) in mapping.iter().enumerate() {
+        key[(*plain)? as usize] = std::char::from_u32(cipher as u32 + 65).unwrap();
+    }
+    let key = key.iter().collect::<String>();
+    parse_key(&key).ok()?;
+    Some(key)
+}
+
+/// The mapping that decrypts with a valid cipher alphabet.
+pub fn key_to_mapping(key: &str) -> Option<Mapping> {
+    let mut mapping = [None; 26];
+    for (plain, &cipher) in parse_key(key).ok()?.iter().enumerate() {
+        mapping[cipher as usize] = Some(plain as u32);
+    }
+    Some(mapping)
+}
+
+/// The key found by [`crack`], and what it decrypts the ciphertext to.
+#[derive(Clone, Debug)]
+pub struct Candidate {
+    pub key: String,
+    pub plaintext: String,
+    /// The quadgram score of the plaintext; lower is better.
+    pub score: f64,
+}
+
+/// A small xorshift generator, so that cracking is repeatable without pulling
+/// in a dependency for randomness.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self, bound: usize) -> usize {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 % bound as u64) as usize
+    }
+}
+
+/// Guesses the key from `ciphertext` alone by hill climbing: starting from a
+/// guess, swap pairs of letters in the key for as long as that makes the
+/// decryption look more like English. Since the climb can get stuck, it is
+/// repeated `restarts` times from a slightly shuffled copy of the best key.
+pub fn crack(ciphertext: &str, restarts: usize) -> Candidate {
+    let quadgrams = Quadgrams::english();
+    let letters = ciphertext.chars().filter_map(letter_index).collect::<Vec<_>>();
+
+    // Start by matching up the most common letters with the most common in English.
+    let counts = crate::analysis::letter_counts(ciphertext);
+    let mut by_frequency = (0..26).collect::<Vec<usize>>();
+    by_frequency.sort_by_key(|&x| std::cmp::Reverse(counts[x]));
+    let mut initial = [0; 26];
+    for (rank, &cipher) in by_frequency.iter().enumerate() {
+        initial[cipher] = (ENGLISH_BY_FREQUENCY[rank] - b'a') as u32;
+    }
+
+    let score = |table: &[u32; 26], buffer: &mut Vec<u32>| {
+        buffer.clear();
+        buffer.extend(letters.iter().map(|&x| table[x as usize]));
+        quadgrams.score_letters(buffer)
+    };
+
+    let mut rng = Rng(0x2545_F491_4F6C_DD1D);
+    let mut buffer = Vec::with_capacity(letters.len());
+    let mut best = initial;
+    let mut best_score = score(&best, &mut buffer);
+    for restart in 0..restarts.max(1) {
+        // Each climb after the first starts from the best key so far, shaken up
+        // a little to get it out of whatever local maximum it is stuck in.
+        let mut table = best;
+        if restart > 0 {
+            for _ in 0..4 {
+                table.swap(rng.next(26), rng.next(26));
+            }
+        }
+        let mut current = score(&table, &mut buffer);
+        let mut improved = true;
+        while improved {
+            improved = false;
+            for i in 0..26 {
+                for j in i + 1..26 {
+                    table.swap(i, j);
+                    let new = score(&table, &mut buffer);
+                    if new < current {
+                        current = new;
+                        improved = true;
+                    } else {
+                        table.swap(i, j);
+                    }
+                }
+            }
+        }
+        if current < best_score {
+            best = table;
+            best_score = current;
+        }
+    }
+
+    // `best` decrypts, so the key is its inverse.
+    let key = invert(&best)
+        .iter()
+        .map(|&x| std::char::from_u32(x + 65).unwrap())
+        .collect::<String>();
+    Candidate {
+        plaintext: substitute(ciphertext, &best),
+        key,
+        score: best_score,
+    }
+}
+
+impl Cipher for Substitution {
+    type Key = String;
+
+    fn is_valid_key(&self, key: &String) -> bool {
+        parse_key(key).is_ok()
+    }
+
+    fn encrypt(&self, plaintext: &str, key: &String) -> Result<String, CipherError> {
+        encrypt(plaintext, key)
+    }
+
+    fn decrypt(&self, ciphertext: &str, key: &String) -> Result<String, CipherError> {
+        decrypt(ciphertext, key)
+    }
+
+    fn find_key(&self, plaintext: &str, ciphertext: &str) -> Result<String, CipherError> {
+        find_key(plaintext, ciphertext)
+    }
+}
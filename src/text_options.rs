@@ -0,0 +1,243 @@
+use crate::{
+    alphabet::{single, Alphabet},
+    CipherError,
+};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Case {
+    /// Keep the case of the input.
+    #[default]
+    Keep,
+    Upper,
+    Lower,
+}
+
+/// How a cipher treats the characters that aren't in its alphabet, and how it
+/// lays out its output.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TextOptions {
+    /// Only move on to the next letter of the key after a letter, rather than
+    /// after every character. Stripping and grouping do this too, since the
+    /// ciphertext no longer shows where anything else was.
+    pub key_on_letters: bool,
+    /// Leave out anything that isn't in the alphabet.
+    pub strip: bool,
+    pub case: Case,
+    /// Split the ciphertext into blocks of five letters, leaving out anything
+    /// that isn't in the alphabet. The plaintext is stripped but not split.
+    pub groups: bool,
+}
+
+/// One letter of a plaintext lined up with the ciphertext letter it became.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Pair {
+    /// How far through the key the cipher was.
+    pub position: usize,
+    /// The index of the letter in the plaintext.
+    pub index: usize,
+    pub plain: u32,
+    pub cipher: u32,
+}
+
+/// How a character of the plaintext compares with the ciphertext character
+/// lined up with it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mark {
+    Fine,
+    /// A letter lined up with something that isn't one, or two different
+    /// characters that aren't letters.
+    NonAlphabetic,
+    /// Two letters in different cases.
+    Case,
+    /// Two letters that don't fit the key found from the others.
+    Inconsistent,
+    /// A character with nothing lined up with it, because the other text ran out.
+    Unmatched,
+}
+
+/// A character of the plaintext lined up with one of the ciphertext, either of
+/// which can be missing.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Column {
+    pub plain: Option<char>,
+    pub cipher: Option<char>,
+    /// The key letter that takes the plaintext letter to the ciphertext one.
+    pub key: Option<char>,
+    pub mark: Mark,
+}
+
+/// The value that appears most often, or the first of those that do.
+pub(crate) fn most_common(values: impl IntoIterator<Item = u32>) -> Option<u32> {
+    let mut counts: Vec<(u32, usize)> = Vec::new();
+    for value in values {
+        match counts.iter_mut().find(|(x, _)| *x == value) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((value, 1)),
+        }
+    }
+    let max = counts.iter().map(|&(_, count)| count).max()?;
+    counts.into_iter().find(|&(_, count)| count == max).map(|(value, _)| value)
+}
+
+impl TextOptions {
+    /// Whether the key only moves on after letters.
+    pub(crate) fn key_on_letters_only(&self) -> bool {
+        self.key_on_letters || self.strip || self.groups
+    }
+
+    /// Lays out the output of a cipher. Groups are only used for ciphertext.
+    pub fn format(&self, text: &str, alphabet: &Alphabet, ciphertext: bool) -> String {
+        let groups = self.groups && ciphertext;
+        let mut output = String::new();
+        let mut letters = 0;
+        for c in text.chars() {
+            let is_letter = alphabet.index(c).is_some();
+            if !is_letter && (self.strip || self.groups) {
+                continue;
+            }
+            if groups && letters > 0 && letters % 5 == 0 {
+                output.push(' ');
+            }
+            output.push(match self.case {
+                Case::Keep => c,
+                Case::Upper => single(c.to_uppercase()).unwrap_or(c),
+                Case::Lower => single(c.to_lowercase()).unwrap_or(c),
+            });
+            if is_letter {
+                letters += 1;
+            }
+        }
+        output
+    }
+
+    /// Lines up the letters of `plaintext` with those of `ciphertext`, as
+    /// formatted with these options, failing where they can't be lined up.
+    pub(crate) fn align(
+        &self,
+        plaintext: &str,
+        ciphertext: &str,
+        alphabet: &Alphabet,
+    ) -> Result<Vec<Pair>, CipherError> {
+        let mut cipher_chars = ciphertext.chars().filter(|&c| {
+            // Anything that isn't a letter was either kept where it was, or
+            // left out entirely.
+            !(self.strip || self.groups) || alphabet.index(c).is_some()
+        });
+        let mut pairs = Vec::new();
+        let mut position = 0;
+        for (index, plain) in plaintext.chars().enumerate() {
+            match alphabet.find(plain) {
+                Some((p, plain_upper)) => {
+                    let cipher = cipher_chars.next().ok_or(CipherError::MismatchedLengths)?;
+                    let (c, cipher_upper) = alphabet.find(cipher).ok_or(CipherError::NonAlphabeticMismatch(index))?;
+                    if self.case == Case::Keep && plain_upper != cipher_upper {
+                        return Err(CipherError::MismatchedCase(index));
+                    }
+                    pairs.push(Pair {
+                        position,
+                        index,
+                        plain: p,
+                        cipher: c,
+                    });
+                    position += 1;
+                },
+                None => {
+                    if !(self.strip || self.groups) {
+                        let cipher = cipher_chars.next().ok_or(CipherError::MismatchedLengths)?;
+                        if !self.kept_as(plain, cipher) {
+                            return Err(CipherError::NonAlphabeticMismatch(index));
+                        }
+                    }
+                    if !self.key_on_letters_only() {
+                        position += 1;
+                    }
+                },
+            }
+        }
+        match cipher_chars.next() {
+            Some(_) => Err(CipherError::MismatchedLengths),
+            None => Ok(pairs),
+        }
+    }
+
+    /// Lines up `plaintext` with `ciphertext` like [`align`](Self::align), but
+    /// carries on past anything that doesn't line up, marking it instead. Pairs
+    /// of letters come with their [`Pair`], even in different cases.
+    pub(crate) fn compare(
+        &self,
+        plaintext: &str,
+        ciphertext: &str,
+        alphabet: &Alphabet,
+    ) -> Vec<(Column, Option<Pair>)> {
+        let skip = self.strip || self.groups;
+        let mut cipher_chars = ciphertext.chars().filter(|&c| !skip || alphabet.index(c).is_some());
+        let mut columns = Vec::new();
+        let mut position = 0;
+        for (index, plain) in plaintext.chars().enumerate() {
+            let column = |cipher, mark| Column {
+                plain: Some(plain),
+                cipher,
+                key: None,
+                mark,
+            };
+            match alphabet.find(plain) {
+                Some((p, plain_upper)) => {
+                    let cipher = cipher_chars.next();
+                    match cipher.and_then(|c| alphabet.find(c)) {
+                        Some((c, cipher_upper)) => {
+                            let mark = if self.case == Case::Keep && plain_upper != cipher_upper {
+                                Mark::Case
+                            } else {
+                                Mark::Fine
+                            };
+                            let pair = Pair {
+                                position,
+                                index,
+                                plain: p,
+                                cipher: c,
+                            };
+                            columns.push((column(cipher, mark), Some(pair)));
+                        },
+                        None if cipher.is_some() => columns.push((column(cipher, Mark::NonAlphabetic), None)),
+                        None => columns.push((column(None, Mark::Unmatched), None)),
+                    }
+                    position += 1;
+                },
+                None => {
+                    if skip {
+                        columns.push((column(None, Mark::Fine), None));
+                    } else {
+                        let cipher = cipher_chars.next();
+                        let mark = match cipher {
+                            Some(c) if self.kept_as(plain, c) => Mark::Fine,
+                            Some(_) => Mark::NonAlphabetic,
+                            None => Mark::Unmatched,
+                        };
+                        columns.push((column(cipher, mark), None));
+                    }
+                    if !self.key_on_letters_only() {
+                        position += 1;
+                    }
+                },
+            }
+        }
+        for cipher in cipher_chars {
+            let column = Column {
+                plain: None,
+                cipher: Some(cipher),
+                key: None,
+                mark: Mark::Unmatched,
+            };
+            columns.push((column, None));
+        }
+        columns
+    }
+
+    /// Whether `plain`, which isn't a letter, could have come out as `cipher`.
+    fn kept_as(&self, plain: char, cipher: char) -> bool {
+        match self.case {
+            Case::Keep => cipher == plain,
+            _ => cipher.to_lowercase().eq(plain.to_lowercase()),
+        }
+    }
+}
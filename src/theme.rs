@@ -11,6 +11,8 @@ pub const GREEN: Key<Color> = Key::new("thomhuds.green");
 pub const PALE_GREEN: Key<Color> = Key::new("thomhuds.pale_green");
 pub const BLUE: Key<Color> = Key::new("thomhuds.blue");
 pub const PALE_BLUE: Key<Color> = Key::new("thomhuds.pale_blue");
+pub const PURPLE: Key<Color> = Key::new("thomhuds.purple");
+pub const PALE_PURPLE: Key<Color> = Key::new("thomhuds.pale_purple");
 
 pub fn theme(env: &mut Env, _: &crate::State) {
     env.set(theme::BUTTON_BORDER_RADIUS, 4.);
@@ -24,6 +26,8 @@ pub fn theme(env: &mut Env, _: &crate::State) {
     env.set(PALE_GREEN, Color::from_rgba32_u32(0xA5D6A7FF));
     env.set(BLUE, Color::from_rgba32_u32(0x2196F3FF));
     env.set(PALE_BLUE, Color::from_rgba32_u32(0x90CAF9FF));
+    env.set(PURPLE, Color::from_rgba32_u32(0x9C27B0FF));
+    env.set(PALE_PURPLE, Color::from_rgba32_u32(0xCE93D8FF));
 
     #[cfg(target_os = "windows")]
     env.set(theme::FONT_NAME, "Segoe UI");
@@ -0,0 +1,256 @@
+use ciphertools::{
+    affine::{self, Affine, AffineKey},
+    Cipher,
+};
+use crate::widget::{
+    candidate_list::{candidate_list, Candidate},
+    error_status,
+    file_buttons::text_file_buttons,
+    input_label,
+    mode_selector::{mode_selector, ModeColour},
+    recompute::Recompute,
+    soft_label,
+    text_area::TextArea,
+    titled_panel,
+};
+use druid::{
+    lens,
+    widget::{Controller, CrossAxisAlignment, Either, Flex, MainAxisAlignment, SizedBox, Stepper, TextBox},
+    Data, Env, Event, EventCtx, Lens, LensExt, Widget, WidgetExt,
+};
+use serde::{Deserialize, Serialize};
+use std::{fmt, sync::Arc};
+
+#[derive(Clone, Copy, Data, PartialEq, Serialize, Deserialize)]
+struct Key {
+    a: u8,
+    b: u8,
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "A = {}, B = {}", self.a, self.b)
+    }
+}
+
+#[derive(Clone, Data, Lens, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AffineState {
+    plaintext: String,
+    ciphertext: String,
+    a: Option<u8>,
+    b: Option<u8>,
+    candidates: Arc<Vec<Candidate<Key>>>,
+    status: String,
+    mode: usize,
+}
+
+impl AffineState {
+    /// Cracks `ciphertext`, handed over from the Identify tab.
+    pub fn hand_off(&mut self, ciphertext: String) {
+        let old = self.clone();
+        self.ciphertext = ciphertext;
+        self.mode = 3;
+        recompute(self, &old);
+    }
+
+    fn key(&self) -> Option<AffineKey> {
+        Some(AffineKey {
+            a: self.a?,
+            b: self.b?,
+        })
+    }
+}
+
+/// Brings the outputs up to date after something changes.
+fn recompute(data: &mut AffineState, old: &AffineState) {
+    let result = match data.mode {
+        0 => {
+            // Encrypt
+            match data.key() {
+                Some(key) => Affine.encrypt(&data.plaintext, &key).map(|ciphertext| data.ciphertext = ciphertext),
+                None => Ok(()),
+            }
+        },
+        1 => {
+            // Decrypt
+            match data.key() {
+                Some(key) => Affine.decrypt(&data.ciphertext, &key).map(|plaintext| data.plaintext = plaintext),
+                None => Ok(()),
+            }
+        },
+        2 => {
+            // Find Key
+            Affine.find_key(&data.plaintext, &data.ciphertext).map(|key| {
+                data.a = Some(key.a);
+                data.b = Some(key.b);
+            })
+        },
+        3 => {
+            // Crack
+            if data.ciphertext != old.ciphertext || data.mode != old.mode {
+                let candidates = affine::crack(&data.ciphertext)
+                    .into_iter()
+                    .map(|candidate| Candidate {
+                        key: Key {
+                            a: candidate.key.a,
+                            b: candidate.key.b,
+                        },
+                        plaintext: candidate.plaintext,
+                        score: candidate.score,
+                    })
+                    .collect::<Vec<_>>();
+                data.a = candidates.first().map(|candidate| candidate.key.a);
+                data.b = candidates.first().map(|candidate| candidate.key.b);
+                data.candidates = Arc::new(candidates);
+            }
+            match data.key() {
+                Some(key) => Affine.decrypt(&data.ciphertext, &key).map(|plaintext| data.plaintext = plaintext),
+                None => Ok(()),
+            }
+        },
+        _ => panic!("affine: wrong mode"),
+    };
+    data.status = result.err().map(|e| error_status(&e)).unwrap_or_default();
+}
+
+struct DisableWithMode(&'static [usize]);
+
+impl<W: Widget<AffineState>> Controller<AffineState, W> for DisableWithMode {
+    fn event(
+        &mut self,
+        child: &mut W,
+        ctx: &mut EventCtx,
+        event: &Event,
+        data: &mut AffineState,
+        env: &Env,
+    ) {
+        if !self.0.contains(&data.mode) {
+            child.event(ctx, event, data, env);
+            return;
+        }
+        match event {
+            Event::MouseDown(_) | Event::MouseUp(_) | Event::KeyDown(_) | Event::KeyUp(_) => {},
+            _ => child.event(ctx, event, data, env),
+        }
+    }
+}
+
+/// A text box and stepper for one half of the key.
+fn key_input(name: &'static str, min: f64, step: f64) -> impl Widget<Option<u8>> {
+    Flex::row()
+        .with_child(soft_label(name))
+        .with_child(TextBox::new().lens(lens::Id.map(
+            |x: &Option<u8>| {
+                x.as_ref()
+                    .map(ToString::to_string)
+                    .unwrap_or_default()
+            },
+            |x: &mut Option<u8>, y: String| *x = y.parse().ok(),
+        )))
+        .with_child(
+            Stepper::new()
+                .with_range(min, 25.)
+                .with_step(step)
+                .with_wraparound(true)
+                .lens(lens::Id.map(
+                    move |x: &Option<u8>| x.map(f64::from).unwrap_or(min),
+                    |x: &mut Option<u8>, y: f64| *x = Some(y as u8),
+                )),
+        )
+}
+
+pub fn build_affine_widget() -> impl Widget<AffineState> {
+    let mode_selector = mode_selector(&[
+        ("Encrypt", ModeColour::Green),
+        ("Decrypt", ModeColour::Red),
+        ("Find Key", ModeColour::Blue),
+        ("Crack", ModeColour::Purple),
+    ])
+    .lens(AffineState::mode);
+
+    let plaintext = Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(
+            Flex::row()
+                .with_child(input_label("PLAINTEXT").lens(AffineState::mode.map(|x| *x == 1 || *x == 3, |_, _| {})))
+                .with_flex_spacer(1.0)
+                .with_child(text_file_buttons(AffineState::plaintext, AffineState::status)),
+        )
+        .with_child(
+            TextArea::new(3)
+                .lens(AffineState::plaintext)
+                .expand_width()
+                .controller(DisableWithMode(&[1, 3])),
+        );
+
+    let key = Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(input_label("KEY").lens(AffineState::mode.map(|x| *x == 2 || *x == 3, |_, _| {})))
+        .with_child(
+            Flex::row()
+                .with_child(key_input("A", 1., 2.).lens(AffineState::a))
+                .with_spacer(8.0)
+                .with_child(key_input("B", 0., 1.).lens(AffineState::b)),
+        )
+        .controller(DisableWithMode(&[2, 3]));
+
+    let ciphertext = Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(
+            Flex::row()
+                .with_child(input_label("CIPHERTEXT").lens(AffineState::mode.map(|x| *x == 0, |_, _| {})))
+                .with_flex_spacer(1.0)
+                .with_child(text_file_buttons(AffineState::ciphertext, AffineState::status)),
+        )
+        .with_child(
+            TextArea::new(3)
+                .lens(AffineState::ciphertext)
+                .expand_width()
+                .controller(DisableWithMode(&[0])),
+        );
+
+    let column = Flex::column()
+        .main_axis_alignment(MainAxisAlignment::Center)
+        .cross_axis_alignment(CrossAxisAlignment::Center)
+        .with_child(mode_selector)
+        .with_spacer(2.0)
+        .with_child(plaintext)
+        .with_spacer(2.0)
+        .with_child(key)
+        .with_spacer(2.0)
+        .with_child(ciphertext)
+        .with_spacer(2.0)
+        .with_flex_child(
+            Either::new(
+                |data: &AffineState, _| data.mode == 3,
+                candidate_list().lens(lens::Id.map(
+                    |data: &AffineState| {
+                        let key = data.key().map(|key| Key { a: key.a, b: key.b });
+                        (key, data.candidates.clone())
+                    },
+                    |data: &mut AffineState, (key, _): (Option<Key>, _)| {
+                        if let Some(key) = key {
+                            data.a = Some(key.a);
+                            data.b = Some(key.b);
+                        }
+                    },
+                )),
+                SizedBox::empty(),
+            ),
+            1.0,
+        )
+        .expand_height();
+
+    Recompute::new(
+        titled_panel(
+            "Affine Cipher",
+            " - Maps each letter x to ax + b.",
+            AffineState::status,
+            column,
+        ),
+        recompute,
+        |data| data.plaintext.len() + data.ciphertext.len(),
+        AffineState::status,
+    )
+}
@@ -0,0 +1,166 @@
+use ciphertools::{
+    ascii85::{self, Ascii85Options, Ascii85Variant},
+    hex::{self, HexOptions},
+};
+use crate::widget::{
+    file_buttons::{byte_file_buttons, text_file_buttons},
+    recompute::Recompute,
+    soft_label,
+    text_area::TextArea,
+    titled_panel,
+};
+use druid::{
+    widget::{Checkbox, CrossAxisAlignment, Flex, MainAxisAlignment, Radio},
+    Data, Lens, Widget, WidgetExt,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+const NOT_UTF8: &str = "The bytes aren't UTF-8 text, so the plaintext only shows what it can";
+const Z85_LENGTH: &str = "Z85 can only encode a multiple of four bytes";
+
+#[derive(Clone, Data, Lens, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Ascii85State {
+    plaintext: String,
+    encoded: String,
+    /// The bytes being encoded, as hex.
+    hex: String,
+    bytes: Arc<Vec<u8>>,
+    z85: bool,
+    delimiters: bool,
+    status: String,
+}
+
+impl Ascii85State {
+    /// Decodes `encoded`, handed over from the Identify tab.
+    pub fn hand_off(&mut self, encoded: String) {
+        let old = self.clone();
+        self.delimiters = encoded.trim_start().starts_with("<~");
+        self.encoded = encoded;
+        recompute(self, &old);
+    }
+
+    fn options(&self) -> Ascii85Options {
+        Ascii85Options {
+            variant: if self.z85 {
+                Ascii85Variant::Z85
+            } else {
+                Ascii85Variant::Adobe
+            },
+            delimiters: self.delimiters,
+        }
+    }
+
+    /// Encodes `bytes`, or explains why Z85 can't.
+    fn set_encoded(&mut self) {
+        match ascii85::encode_bytes(&self.bytes, &self.options()) {
+            Some(encoded) => self.encoded = encoded,
+            None => {
+                self.encoded = String::new();
+                self.status = String::from(Z85_LENGTH);
+            },
+        }
+    }
+
+    /// Shows `bytes` as plaintext, warning if they aren't UTF-8.
+    fn set_plaintext(&mut self) {
+        match std::str::from_utf8(&self.bytes) {
+            Ok(text) => {
+                self.plaintext = String::from(text);
+                self.status = String::new();
+            },
+            Err(_) => {
+                self.plaintext = String::from_utf8_lossy(&self.bytes).into_owned();
+                self.status = String::from(NOT_UTF8);
+            },
+        }
+    }
+}
+
+/// Brings the outputs up to date after something changes.
+fn recompute(data: &mut Ascii85State, old: &Ascii85State) {
+    let options = data.options();
+    if data.plaintext != old.plaintext {
+        data.bytes = Arc::new(data.plaintext.as_bytes().to_vec());
+        data.hex = hex::encode_bytes(&data.bytes, &HexOptions::SPACED);
+        data.status = String::new();
+        data.set_encoded();
+    } else if data.hex != old.hex {
+        match hex::decode_bytes(&data.hex) {
+            Ok(bytes) => {
+                data.bytes = Arc::new(bytes);
+                data.set_plaintext();
+                data.set_encoded();
+            },
+            Err(e) => data.status = format!("Invalid hex {}", e),
+        }
+    } else if data.encoded != old.encoded {
+        match ascii85::decode_bytes(&data.encoded, &options) {
+            Ok(bytes) => {
+                data.bytes = Arc::new(bytes);
+                data.hex = hex::encode_bytes(&data.bytes, &HexOptions::SPACED);
+                data.set_plaintext();
+            },
+            Err(e) => data.status = format!("Invalid {} {}", options.variant.name(), e),
+        }
+    } else if options != old.options() {
+        data.set_plaintext();
+        data.set_encoded();
+    }
+}
+
+pub fn build_ascii85_widget() -> impl Widget<Ascii85State> {
+    let plaintext = Flex::column()
+        .with_child(
+            Flex::row()
+                .with_child(soft_label("PLAINTEXT"))
+                .with_flex_spacer(1.0)
+                .with_child(byte_file_buttons(Ascii85State::bytes, Ascii85State::hex, Ascii85State::status)),
+        )
+        .with_child(TextArea::new(3).lens(Ascii85State::plaintext).expand_width());
+
+    let hex = Flex::column()
+        .with_child(soft_label("BYTES (hex)"))
+        .with_child(TextArea::new(3).lens(Ascii85State::hex).expand_width());
+
+    let options = Flex::row()
+        .with_child(soft_label("VARIANT"))
+        .with_child(Radio::new("Adobe", false).lens(Ascii85State::z85))
+        .with_child(Radio::new("Z85", true).lens(Ascii85State::z85))
+        .with_spacer(8.0)
+        .with_child(Checkbox::new("Add <~ ~> delimiters").lens(Ascii85State::delimiters));
+
+    let encoded = Flex::column()
+        .with_child(
+            Flex::row()
+                .with_child(soft_label("ASCII85"))
+                .with_flex_spacer(1.0)
+                .with_child(text_file_buttons(Ascii85State::encoded, Ascii85State::status)),
+        )
+        .with_child(TextArea::new(3).lens(Ascii85State::encoded).expand_width());
+
+    let column = Flex::column()
+        .main_axis_alignment(MainAxisAlignment::Center)
+        .cross_axis_alignment(CrossAxisAlignment::Center)
+        .with_child(plaintext)
+        .with_spacer(2.0)
+        .with_child(hex)
+        .with_spacer(2.0)
+        .with_child(options)
+        .with_spacer(2.0)
+        .with_child(encoded)
+        .expand_height();
+
+    Recompute::new(
+        titled_panel(
+            "Ascii85",
+            " - Writes every four bytes as five printable characters.",
+            Ascii85State::status,
+            column,
+        ),
+        recompute,
+        |data| data.plaintext.len() + data.encoded.len(),
+        Ascii85State::status,
+    )
+}
@@ -0,0 +1,152 @@
+use ciphertools::{
+    base32::{self, Base32Alphabet, Base32Options},
+    hex::{self, HexOptions},
+};
+use crate::widget::{
+    file_buttons::{byte_file_buttons, text_file_buttons},
+    recompute::Recompute,
+    soft_label,
+    text_area::TextArea,
+    titled_panel,
+};
+use druid::{
+    widget::{Checkbox, CrossAxisAlignment, Flex, MainAxisAlignment, Radio},
+    Data, Lens, Widget, WidgetExt,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+const NOT_UTF8: &str = "The bytes aren't UTF-8 text, so the plaintext only shows what it can";
+
+#[derive(Clone, Data, Lens, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Base32State {
+    plaintext: String,
+    base32: String,
+    /// The bytes being encoded, as hex.
+    hex: String,
+    bytes: Arc<Vec<u8>>,
+    crockford: bool,
+    no_padding: bool,
+    status: String,
+}
+
+impl Base32State {
+    /// Decodes `base32`, handed over from the Identify tab.
+    pub fn hand_off(&mut self, base32: String) {
+        let old = self.clone();
+        self.base32 = base32;
+        recompute(self, &old);
+    }
+
+    fn options(&self) -> Base32Options {
+        Base32Options {
+            alphabet: if self.crockford {
+                Base32Alphabet::Crockford
+            } else {
+                Base32Alphabet::Rfc4648
+            },
+            no_padding: self.no_padding,
+        }
+    }
+
+    /// Shows `bytes` as plaintext, warning if they aren't UTF-8.
+    fn set_plaintext(&mut self) {
+        match std::str::from_utf8(&self.bytes) {
+            Ok(text) => {
+                self.plaintext = String::from(text);
+                self.status = String::new();
+            },
+            Err(_) => {
+                self.plaintext = String::from_utf8_lossy(&self.bytes).into_owned();
+                self.status = String::from(NOT_UTF8);
+            },
+        }
+    }
+}
+
+/// Brings the outputs up to date after something changes.
+fn recompute(data: &mut Base32State, old: &Base32State) {
+    let options = data.options();
+    if data.plaintext != old.plaintext {
+        data.bytes = Arc::new(data.plaintext.as_bytes().to_vec());
+        data.base32 = base32::encode_bytes(&data.bytes, &options);
+        data.hex = hex::encode_bytes(&data.bytes, &HexOptions::SPACED);
+        data.status = String::new();
+    } else if data.hex != old.hex {
+        match hex::decode_bytes(&data.hex) {
+            Ok(bytes) => {
+                data.bytes = Arc::new(bytes);
+                data.base32 = base32::encode_bytes(&data.bytes, &options);
+                data.set_plaintext();
+            },
+            Err(e) => data.status = format!("Invalid hex {}", e),
+        }
+    } else if data.base32 != old.base32 {
+        match base32::decode_bytes(&data.base32, &options) {
+            Ok(bytes) => {
+                data.bytes = Arc::new(bytes);
+                data.hex = hex::encode_bytes(&data.bytes, &HexOptions::SPACED);
+                data.set_plaintext();
+            },
+            Err(e) => data.status = format!("Invalid Base32 {}", e),
+        }
+    } else if options != old.options() {
+        data.base32 = base32::encode_bytes(&data.bytes, &options);
+    }
+}
+
+pub fn build_base32_widget() -> impl Widget<Base32State> {
+    let plaintext = Flex::column()
+        .with_child(
+            Flex::row()
+                .with_child(soft_label("PLAINTEXT"))
+                .with_flex_spacer(1.0)
+                .with_child(byte_file_buttons(Base32State::bytes, Base32State::hex, Base32State::status)),
+        )
+        .with_child(TextArea::new(3).lens(Base32State::plaintext).expand_width());
+
+    let hex = Flex::column()
+        .with_child(soft_label("BYTES (hex)"))
+        .with_child(TextArea::new(3).lens(Base32State::hex).expand_width());
+
+    let options = Flex::row()
+        .with_child(soft_label("ALPHABET"))
+        .with_child(Radio::new("RFC 4648", false).lens(Base32State::crockford))
+        .with_child(Radio::new("Crockford", true).lens(Base32State::crockford))
+        .with_spacer(8.0)
+        .with_child(Checkbox::new("No padding").lens(Base32State::no_padding));
+
+    let base32 = Flex::column()
+        .with_child(
+            Flex::row()
+                .with_child(soft_label("BASE32"))
+                .with_flex_spacer(1.0)
+                .with_child(text_file_buttons(Base32State::base32, Base32State::status)),
+        )
+        .with_child(TextArea::new(3).lens(Base32State::base32).expand_width());
+
+    let column = Flex::column()
+        .main_axis_alignment(MainAxisAlignment::Center)
+        .cross_axis_alignment(CrossAxisAlignment::Center)
+        .with_child(plaintext)
+        .with_spacer(2.0)
+        .with_child(hex)
+        .with_spacer(2.0)
+        .with_child(options)
+        .with_spacer(2.0)
+        .with_child(base32)
+        .expand_height();
+
+    Recompute::new(
+        titled_panel(
+            "Base32",
+            " - Transforms text into its Base32 representation.",
+            Base32State::status,
+            column,
+        ),
+        recompute,
+        |data| data.plaintext.len() + data.base32.len(),
+        Base32State::status,
+    )
+}
@@ -0,0 +1,135 @@
+use ciphertools::{
+    base58,
+    hex::{self, HexOptions},
+};
+use crate::widget::{
+    file_buttons::{byte_file_buttons, text_file_buttons},
+    recompute::Recompute,
+    soft_label,
+    text_area::TextArea,
+    titled_panel,
+};
+use druid::{
+    widget::{Checkbox, CrossAxisAlignment, Flex, MainAxisAlignment},
+    Data, Lens, Widget, WidgetExt,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+const NOT_UTF8: &str = "The bytes aren't UTF-8 text, so the plaintext only shows what it can";
+
+#[derive(Clone, Data, Lens, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Base58State {
+    plaintext: String,
+    base58: String,
+    /// The bytes being encoded, as hex.
+    hex: String,
+    bytes: Arc<Vec<u8>>,
+    check: bool,
+    status: String,
+}
+
+impl Base58State {
+    /// Decodes `base58`, handed over from the Identify tab.
+    pub fn hand_off(&mut self, base58: String) {
+        let old = self.clone();
+        self.base58 = base58;
+        recompute(self, &old);
+    }
+
+    /// Shows `bytes` as plaintext, warning if they aren't UTF-8.
+    fn set_plaintext(&mut self) {
+        match std::str::from_utf8(&self.bytes) {
+            Ok(text) => {
+                self.plaintext = String::from(text);
+                self.status = String::new();
+            },
+            Err(_) => {
+                self.plaintext = String::from_utf8_lossy(&self.bytes).into_owned();
+                self.status = String::from(NOT_UTF8);
+            },
+        }
+    }
+}
+
+/// Brings the outputs up to date after something changes.
+fn recompute(data: &mut Base58State, old: &Base58State) {
+    if data.plaintext != old.plaintext {
+        data.bytes = Arc::new(data.plaintext.as_bytes().to_vec());
+        data.base58 = base58::encode_bytes(&data.bytes, data.check);
+        data.hex = hex::encode_bytes(&data.bytes, &HexOptions::SPACED);
+        data.status = String::new();
+    } else if data.hex != old.hex {
+        match hex::decode_bytes(&data.hex) {
+            Ok(bytes) => {
+                data.bytes = Arc::new(bytes);
+                data.base58 = base58::encode_bytes(&data.bytes, data.check);
+                data.set_plaintext();
+            },
+            Err(e) => data.status = format!("Invalid hex {}", e),
+        }
+    } else if data.base58 != old.base58 {
+        match base58::decode_bytes(&data.base58, data.check) {
+            Ok(bytes) => {
+                data.bytes = Arc::new(bytes);
+                data.hex = hex::encode_bytes(&data.bytes, &HexOptions::SPACED);
+                data.set_plaintext();
+            },
+            Err(e) => data.status = format!("Invalid Base58 {}", e),
+        }
+    } else if data.check != old.check {
+        data.base58 = base58::encode_bytes(&data.bytes, data.check);
+    }
+}
+
+pub fn build_base58_widget() -> impl Widget<Base58State> {
+    let plaintext = Flex::column()
+        .with_child(
+            Flex::row()
+                .with_child(soft_label("PLAINTEXT"))
+                .with_flex_spacer(1.0)
+                .with_child(byte_file_buttons(Base58State::bytes, Base58State::hex, Base58State::status)),
+        )
+        .with_child(TextArea::new(3).lens(Base58State::plaintext).expand_width());
+
+    let hex = Flex::column()
+        .with_child(soft_label("BYTES (hex)"))
+        .with_child(TextArea::new(3).lens(Base58State::hex).expand_width());
+
+    let options = Flex::row()
+        .with_child(Checkbox::new("Base58Check (add a checksum)").lens(Base58State::check));
+
+    let base58 = Flex::column()
+        .with_child(
+            Flex::row()
+                .with_child(soft_label("BASE58"))
+                .with_flex_spacer(1.0)
+                .with_child(text_file_buttons(Base58State::base58, Base58State::status)),
+        )
+        .with_child(TextArea::new(3).lens(Base58State::base58).expand_width());
+
+    let column = Flex::column()
+        .main_axis_alignment(MainAxisAlignment::Center)
+        .cross_axis_alignment(CrossAxisAlignment::Center)
+        .with_child(plaintext)
+        .with_spacer(2.0)
+        .with_child(hex)
+        .with_spacer(2.0)
+        .with_child(options)
+        .with_spacer(2.0)
+        .with_child(base58)
+        .expand_height();
+
+    Recompute::new(
+        titled_panel(
+            "Base58",
+            " - Transforms text into Base58 with Bitcoin's alphabet.",
+            Base58State::status,
+            column,
+        ),
+        recompute,
+        |data| data.plaintext.len() + data.base58.len(),
+        Base58State::status,
+    )
+}
@@ -1,52 +1,150 @@
-use crate::widget::{soft_label, titled_panel};
+use ciphertools::{
+    base64::{self, Base64Options},
+    hex::{self, HexOptions},
+};
+use crate::widget::{
+    file_buttons::{byte_file_buttons, text_file_buttons},
+    recompute::Recompute,
+    soft_label,
+    text_area::TextArea,
+    titled_panel,
+};
 use druid::{
-    widget::{CrossAxisAlignment, Flex, MainAxisAlignment, TextBox, Controller},
-    Data, Lens, Widget, WidgetExt, Env, EventCtx, Event,
+    widget::{Checkbox, CrossAxisAlignment, Flex, MainAxisAlignment},
+    Data, Lens, Widget, WidgetExt,
 };
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+const NOT_UTF8: &str = "The bytes aren't UTF-8 text, so the plaintext only shows what it can";
 
-#[derive(Clone, Data, Lens, Default)]
+#[derive(Clone, Data, Lens, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Base64State {
     plaintext: String,
     base64: String,
-    mode: usize,
+    /// The bytes being encoded, as hex.
+    hex: String,
+    bytes: Arc<Vec<u8>>,
+    url_safe: bool,
+    no_padding: bool,
+    wrap: bool,
+    status: String,
 }
 
-struct Base64Controller;
-
-impl<W: Widget<Base64State>> Controller<Base64State, W> for Base64Controller {
-    fn event(&mut self, child: &mut W, ctx: &mut EventCtx, event: &Event, data: &mut Base64State, env: &Env) {
-        let old_plain = data.plaintext.clone();
-        let old_base64 = data.base64.clone();
-        child.event(ctx, event, data, env);
-        if data.plaintext != old_plain {
-            data.base64 = base64::encode(&data.plaintext);
-            return;
-        } else if data.base64 != old_base64 {
-            data.plaintext = base64::decode(&data.base64).ok().map(|vec| String::from_utf8(vec).ok()).flatten().unwrap_or_else(|| String::from("Invalid"));
+impl Base64State {
+    /// Decodes `base64`, handed over from the Identify tab.
+    pub fn hand_off(&mut self, base64: String) {
+        let old = self.clone();
+        self.base64 = base64;
+        recompute(self, &old);
+    }
+
+    fn options(&self) -> Base64Options {
+        Base64Options {
+            url_safe: self.url_safe,
+            no_padding: self.no_padding,
+            wrap: self.wrap,
+        }
+    }
+
+    /// Shows `bytes` as plaintext, warning if they aren't UTF-8.
+    fn set_plaintext(&mut self) {
+        match std::str::from_utf8(&self.bytes) {
+            Ok(text) => {
+                self.plaintext = String::from(text);
+                self.status = String::new();
+            },
+            Err(_) => {
+                self.plaintext = String::from_utf8_lossy(&self.bytes).into_owned();
+                self.status = String::from(NOT_UTF8);
+            },
         }
     }
 }
 
+/// Brings the outputs up to date after something changes.
+fn recompute(data: &mut Base64State, old: &Base64State) {
+    let options = data.options();
+    if data.plaintext != old.plaintext {
+        data.bytes = Arc::new(data.plaintext.as_bytes().to_vec());
+        data.base64 = base64::encode_bytes(&data.bytes, &options);
+        data.hex = hex::encode_bytes(&data.bytes, &HexOptions::SPACED);
+        data.status = String::new();
+    } else if data.hex != old.hex {
+        match hex::decode_bytes(&data.hex) {
+            Ok(bytes) => {
+                data.bytes = Arc::new(bytes);
+                data.base64 = base64::encode_bytes(&data.bytes, &options);
+                data.set_plaintext();
+            },
+            Err(e) => data.status = format!("Invalid hex {}", e),
+        }
+    } else if data.base64 != old.base64 {
+        match base64::decode_bytes(&data.base64, &options) {
+            Ok(bytes) => {
+                data.bytes = Arc::new(bytes);
+                data.hex = hex::encode_bytes(&data.bytes, &HexOptions::SPACED);
+                data.set_plaintext();
+            },
+            Err(e) => data.status = format!("Invalid Base64 {}", e),
+        }
+    } else if options != old.options() {
+        data.base64 = base64::encode_bytes(&data.bytes, &options);
+    }
+}
+
 pub fn build_base64_widget() -> impl Widget<Base64State> {
     let plaintext = Flex::column()
-        .with_child(soft_label("PLAINTEXT"))
-        .with_child(TextBox::new().lens(Base64State::plaintext).expand_width());
+        .with_child(
+            Flex::row()
+                .with_child(soft_label("PLAINTEXT"))
+                .with_flex_spacer(1.0)
+                .with_child(byte_file_buttons(Base64State::bytes, Base64State::hex, Base64State::status)),
+        )
+        .with_child(TextArea::new(3).lens(Base64State::plaintext).expand_width());
+
+    let hex = Flex::column()
+        .with_child(soft_label("BYTES (hex)"))
+        .with_child(TextArea::new(3).lens(Base64State::hex).expand_width());
+
+    let options = Flex::row()
+        .with_child(Checkbox::new("URL-safe").lens(Base64State::url_safe))
+        .with_spacer(8.0)
+        .with_child(Checkbox::new("No padding").lens(Base64State::no_padding))
+        .with_spacer(8.0)
+        .with_child(Checkbox::new("Wrap lines for MIME").lens(Base64State::wrap));
 
     let base64 = Flex::column()
-        .with_child(soft_label("BASE64"))
-        .with_child(TextBox::new().lens(Base64State::base64).expand_width());
+        .with_child(
+            Flex::row()
+                .with_child(soft_label("BASE64"))
+                .with_flex_spacer(1.0)
+                .with_child(text_file_buttons(Base64State::base64, Base64State::status)),
+        )
+        .with_child(TextArea::new(3).lens(Base64State::base64).expand_width());
 
     let column = Flex::column()
         .main_axis_alignment(MainAxisAlignment::Center)
         .cross_axis_alignment(CrossAxisAlignment::Center)
         .with_child(plaintext)
         .with_spacer(2.0)
+        .with_child(hex)
+        .with_spacer(2.0)
+        .with_child(options)
+        .with_spacer(2.0)
         .with_child(base64)
         .expand_height();
 
-    titled_panel(
-        "Base64",
-        " - Transforms text into its Base64 representation.",
-        column,
-    ).controller(Base64Controller)
+    Recompute::new(
+        titled_panel(
+            "Base64",
+            " - Transforms text into its Base64 representation.",
+            Base64State::status,
+            column,
+        ),
+        recompute,
+        |data| data.plaintext.len() + data.base64.len(),
+        Base64State::status,
+    )
 }
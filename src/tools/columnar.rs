@@ -0,0 +1,255 @@
+use ciphertools::{
+    columnar::{self, Columnar, ColumnarKey},
+    Cipher,
+};
+use crate::widget::{
+    error_status,
+    file_buttons::text_file_buttons,
+    input_label,
+    letter_grid::{Cell, Grid, LetterGrid},
+    mode_selector::{mode_selector, ModeColour},
+    recompute::Recompute,
+    soft_label,
+    text_area::TextArea,
+    titled_panel,
+};
+use druid::{
+    widget::{Checkbox, Controller, CrossAxisAlignment, Either, Flex, MainAxisAlignment, Scroll, SizedBox, TextBox},
+    Data, Env, Event, EventCtx, Lens, LensExt, Widget, WidgetExt,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+#[derive(Clone, Data, Lens, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ColumnarState {
+    plaintext: String,
+    ciphertext: String,
+    keyword: String,
+    double: bool,
+    second_keyword: String,
+    regular: bool,
+    padding: String,
+    grid: Grid,
+    second_grid: Grid,
+    status: String,
+    mode: usize,
+}
+
+impl ColumnarState {
+    /// Decrypts `ciphertext` with the current keyword, handed over from the Identify tab.
+    pub fn hand_off(&mut self, ciphertext: String) {
+        let old = self.clone();
+        self.ciphertext = ciphertext;
+        self.mode = 1;
+        recompute(self, &old);
+    }
+
+    fn key(&self) -> ColumnarKey {
+        ColumnarKey {
+            keyword: self.keyword.clone(),
+            second_keyword: if self.double {
+                Some(self.second_keyword.clone())
+            } else {
+                None
+            },
+            padding: if self.regular {
+                Some(self.padding.chars().next().unwrap_or('X'))
+            } else {
+                None
+            },
+        }
+    }
+
+    fn update_grids(&mut self) {
+        let key = self.key();
+        self.grid = grid(&self.plaintext, &key.keyword, key.padding);
+        self.second_grid = match &key.second_keyword {
+            Some(second) => {
+                let first = ColumnarKey {
+                    second_keyword: None,
+                    ..key.clone()
+                };
+                let once = columnar::encrypt(&self.plaintext, &first).unwrap_or_default();
+                grid(&once, second, None)
+            },
+            None => Grid::default(),
+        };
+    }
+}
+
+/// Lays `text` out under `keyword`, numbering the keyword with the order the
+/// columns are read in, and each character with where it ends up.
+fn grid(text: &str, keyword: &str, padding: Option<char>) -> Grid {
+    let ranks = match columnar::column_ranks(keyword) {
+        Some(ranks) => ranks,
+        None => return Grid::default(),
+    };
+    let chars = columnar::pad(text, ranks.len(), padding).chars().collect::<Vec<_>>();
+    let mut positions = vec![0; chars.len()];
+    for (position, i) in columnar::read_order(chars.len(), &ranks).into_iter().enumerate() {
+        positions[i] = position;
+    }
+
+    let header = keyword
+        .chars()
+        .filter(char::is_ascii_alphabetic)
+        .zip(ranks.iter())
+        .map(|(letter, rank)| Cell {
+            text: letter.to_ascii_uppercase().to_string(),
+            note: (rank + 1).to_string(),
+            header: true,
+            ..Cell::default()
+        });
+    let body = chars
+        .iter()
+        .zip(positions.iter())
+        .map(|(&c, position)| Cell::character(c, (position + 1).to_string()));
+    Grid {
+        columns: ranks.len(),
+        cells: Arc::new(header.chain(body).collect()),
+    }
+}
+
+/// Brings the outputs up to date after something changes.
+fn recompute(data: &mut ColumnarState, _old: &ColumnarState) {
+    let key = data.key();
+    let result = match data.mode {
+        0 => {
+            // Encrypt
+            Columnar.encrypt(&data.plaintext, &key).map(|ciphertext| data.ciphertext = ciphertext)
+        },
+        1 => {
+            // Decrypt
+            Columnar.decrypt(&data.ciphertext, &key).map(|plaintext| data.plaintext = plaintext)
+        },
+        _ => panic!("columnar: wrong mode"),
+    };
+    data.status = result.err().map(|e| error_status(&e)).unwrap_or_default();
+    data.update_grids();
+}
+
+struct DisableWithMode(&'static [usize]);
+
+impl<W: Widget<ColumnarState>> Controller<ColumnarState, W> for DisableWithMode {
+    fn event(
+        &mut self,
+        child: &mut W,
+        ctx: &mut EventCtx,
+        event: &Event,
+        data: &mut ColumnarState,
+        env: &Env,
+    ) {
+        if !self.0.contains(&data.mode) {
+            child.event(ctx, event, data, env);
+            return;
+        }
+        match event {
+            Event::MouseDown(_) | Event::MouseUp(_) | Event::KeyDown(_) | Event::KeyUp(_) => {},
+            _ => child.event(ctx, event, data, env),
+        }
+    }
+}
+
+pub fn build_columnar_widget() -> impl Widget<ColumnarState> {
+    let mode_selector = mode_selector(&[("Encrypt", ModeColour::Green), ("Decrypt", ModeColour::Red)])
+        .lens(ColumnarState::mode);
+
+    let plaintext = Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(
+            Flex::row()
+                .with_child(input_label("PLAINTEXT").lens(ColumnarState::mode.map(|x| *x == 1, |_, _| {})))
+                .with_flex_spacer(1.0)
+                .with_child(text_file_buttons(ColumnarState::plaintext, ColumnarState::status)),
+        )
+        .with_child(
+            TextArea::new(3)
+                .lens(ColumnarState::plaintext)
+                .expand_width()
+                .controller(DisableWithMode(&[1])),
+        );
+
+    let key = Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(soft_label("KEYWORD"))
+        .with_child(TextBox::new().lens(ColumnarState::keyword).expand_width())
+        .with_child(
+            Flex::row()
+                .with_child(Checkbox::new("Double transposition").lens(ColumnarState::double))
+                .with_spacer(8.0)
+                .with_flex_child(
+                    Either::new(
+                        |data: &ColumnarState, _| data.double,
+                        TextBox::new().lens(ColumnarState::second_keyword).expand_width(),
+                        SizedBox::empty(),
+                    ),
+                    1.0,
+                ),
+        )
+        .with_child(
+            Flex::row()
+                .with_child(Checkbox::new("Regular grid, padded with").lens(ColumnarState::regular))
+                .with_spacer(4.0)
+                .with_child(TextBox::new().lens(ColumnarState::padding).fix_width(32.0)),
+        );
+
+    let ciphertext = Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(
+            Flex::row()
+                .with_child(input_label("CIPHERTEXT").lens(ColumnarState::mode.map(|x| *x == 0, |_, _| {})))
+                .with_flex_spacer(1.0)
+                .with_child(text_file_buttons(ColumnarState::ciphertext, ColumnarState::status)),
+        )
+        .with_child(
+            TextArea::new(3)
+                .lens(ColumnarState::ciphertext)
+                .expand_width()
+                .controller(DisableWithMode(&[0])),
+        );
+
+    let grids = Flex::row()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(
+            Flex::column()
+                .cross_axis_alignment(CrossAxisAlignment::Start)
+                .with_child(soft_label("GRID"))
+                .with_child(LetterGrid.lens(ColumnarState::grid)),
+        )
+        .with_spacer(16.0)
+        .with_child(Either::new(
+            |data: &ColumnarState, _| data.double,
+            Flex::column()
+                .cross_axis_alignment(CrossAxisAlignment::Start)
+                .with_child(soft_label("SECOND GRID"))
+                .with_child(LetterGrid.lens(ColumnarState::second_grid)),
+            SizedBox::empty(),
+        ));
+
+    let column = Flex::column()
+        .main_axis_alignment(MainAxisAlignment::Center)
+        .cross_axis_alignment(CrossAxisAlignment::Center)
+        .with_child(mode_selector)
+        .with_spacer(2.0)
+        .with_child(plaintext)
+        .with_spacer(2.0)
+        .with_child(key)
+        .with_spacer(2.0)
+        .with_child(ciphertext)
+        .with_spacer(2.0)
+        .with_flex_child(Scroll::new(grids), 1.0)
+        .expand_height();
+
+    Recompute::new(
+        titled_panel(
+            "Columnar Transposition",
+            " - Writes the text in rows and reads the columns off in the keyword's order.",
+            ColumnarState::status,
+            column,
+        ),
+        recompute,
+        |data| data.plaintext.len() + data.ciphertext.len(),
+        ColumnarState::status,
+    )
+}
@@ -0,0 +1,300 @@
+use ciphertools::enigma::{self, EnigmaKey, Machine, Model, RotorSetting, GREEK_ROTORS, REFLECTORS, ROTORS};
+use crate::widget::{
+    file_buttons::text_file_buttons,
+    input_label,
+    mode_selector::{mode_selector, ModeColour},
+    recompute::Recompute,
+    soft_label,
+    text_area::TextArea,
+    titled_panel,
+};
+use druid::{
+    lens,
+    widget::{Controller, CrossAxisAlignment, Either, Flex, Label, MainAxisAlignment, Radio, SizedBox, Stepper, TextBox},
+    Data, Env, Event, EventCtx, Lens, LensExt, Widget, WidgetExt,
+};
+use serde::{Deserialize, Serialize};
+
+const MODELS: [Model; 3] = [Model::EnigmaI, Model::M3, Model::M4];
+const NORMAL_ROTORS: [usize; 8] = [0, 1, 2, 3, 4, 5, 6, 7];
+
+#[derive(Clone, Data, Lens, Serialize, Deserialize)]
+struct Slot {
+    /// The rotor's index in `ROTORS`.
+    rotor: usize,
+    ring: u32,
+    position: u32,
+}
+
+impl Slot {
+    fn new(rotor: usize) -> Self {
+        Slot {
+            rotor,
+            ring: 0,
+            position: 0,
+        }
+    }
+
+    fn setting(&self) -> RotorSetting {
+        RotorSetting {
+            rotor: self.rotor,
+            ring: self.ring,
+            position: self.position,
+        }
+    }
+}
+
+#[derive(Clone, Data, Lens, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EnigmaState {
+    plaintext: String,
+    ciphertext: String,
+    model: usize,
+    reflector: usize,
+    /// The M4's fourth rotor, which is ignored on the other models.
+    greek: Slot,
+    left: Slot,
+    middle: Slot,
+    right: Slot,
+    plugboard: String,
+    /// The letters in the rotor windows after typing in the text.
+    windows: String,
+    status: String,
+    mode: usize,
+}
+
+impl Default for EnigmaState {
+    fn default() -> Self {
+        EnigmaState {
+            plaintext: String::new(),
+            ciphertext: String::new(),
+            model: 0,
+            reflector: 1,
+            greek: Slot::new(GREEK_ROTORS[0]),
+            left: Slot::new(0),
+            middle: Slot::new(1),
+            right: Slot::new(2),
+            plugboard: String::new(),
+            windows: String::new(),
+            status: String::new(),
+            mode: 0,
+        }
+    }
+}
+
+impl EnigmaState {
+    fn key(&self) -> EnigmaKey {
+        let model = MODELS[self.model];
+        let mut rotors = vec![self.left.setting(), self.middle.setting(), self.right.setting()];
+        if model == Model::M4 {
+            rotors.insert(0, self.greek.setting());
+        }
+        EnigmaKey {
+            model,
+            reflector: self.reflector,
+            rotors,
+            plugboard: self.plugboard.clone(),
+        }
+    }
+}
+
+fn letter(x: u32) -> char {
+    std::char::from_u32(x + 65).unwrap()
+}
+
+/// Brings the outputs up to date after something changes.
+fn recompute(data: &mut EnigmaState, old: &EnigmaState) {
+    if data.model != old.model {
+        // Start each model off with the reflector it was usually used with.
+        data.reflector = if MODELS[data.model] == Model::M4 { 3 } else { 1 };
+    }
+
+    let key = data.key();
+    let mut machine = match Machine::new(&key) {
+        Some(machine) => machine,
+        None => {
+            data.status = format!("Invalid key: {}", enigma::key_error(&key).unwrap());
+            data.windows = String::new();
+            return;
+        },
+    };
+    data.status = String::new();
+    // Encrypting and decrypting are the same, so the modes only decide
+    // which way the text goes.
+    match data.mode {
+        0 => data.ciphertext = machine.type_text(&data.plaintext),
+        1 => data.plaintext = machine.type_text(&data.ciphertext),
+        _ => panic!("enigma: wrong mode"),
+    }
+    data.windows = machine.positions().into_iter().map(letter).collect();
+}
+
+struct DisableWithMode(&'static [usize]);
+
+impl<W: Widget<EnigmaState>> Controller<EnigmaState, W> for DisableWithMode {
+    fn event(
+        &mut self,
+        child: &mut W,
+        ctx: &mut EventCtx,
+        event: &Event,
+        data: &mut EnigmaState,
+        env: &Env,
+    ) {
+        if !self.0.contains(&data.mode) {
+            child.event(ctx, event, data, env);
+            return;
+        }
+        match event {
+            Event::MouseDown(_) | Event::MouseUp(_) | Event::KeyDown(_) | Event::KeyUp(_) => {},
+            _ => child.event(ctx, event, data, env),
+        }
+    }
+}
+
+/// A label and a stepper that goes through the letters A to Z.
+fn letter_input(name: &'static str) -> impl Widget<u32> {
+    Flex::row()
+        .with_child(soft_label(name))
+        .with_child(Label::dynamic(|x: &u32, _| format!("{} ({:02})", letter(*x), x + 1)))
+        .with_child(
+            Stepper::new()
+                .with_range(0.0, 25.0)
+                .with_step(1.0)
+                .with_wraparound(true)
+                .lens(lens::Id.map(|x: &u32| *x as f64, |x: &mut u32, y: f64| *x = y as u32)),
+        )
+}
+
+/// The settings for one rotor, which can be any of `choices`.
+fn rotor_slot(title: &'static str, choices: &'static [usize]) -> impl Widget<Slot> {
+    let rotor = Flex::row()
+        .with_child(soft_label("ROTOR"))
+        .with_child(Label::dynamic(|rotor: &usize, _| String::from(ROTORS[*rotor].name)))
+        .with_child(
+            Stepper::new()
+                .with_range(0.0, (choices.len() - 1) as f64)
+                .with_step(1.0)
+                .with_wraparound(true)
+                .lens(lens::Id.map(
+                    move |rotor: &usize| choices.iter().position(|x| x == rotor).unwrap_or(0) as f64,
+                    move |rotor: &mut usize, y: f64| *rotor = choices[y as usize],
+                )),
+        )
+        .lens(Slot::rotor);
+
+    Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(soft_label(title))
+        .with_child(rotor)
+        .with_child(letter_input("RING").lens(Slot::ring))
+        .with_child(letter_input("START").lens(Slot::position))
+        .padding(4.0)
+}
+
+pub fn build_enigma_widget() -> impl Widget<EnigmaState> {
+    let mode_selector = mode_selector(&[("Encrypt", ModeColour::Green), ("Decrypt", ModeColour::Red)])
+        .lens(EnigmaState::mode);
+
+    let plaintext = Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(
+            Flex::row()
+                .with_child(input_label("PLAINTEXT").lens(EnigmaState::mode.map(|x| *x == 1, |_, _| {})))
+                .with_flex_spacer(1.0)
+                .with_child(text_file_buttons(EnigmaState::plaintext, EnigmaState::status)),
+        )
+        .with_child(
+            TextArea::new(3)
+                .lens(EnigmaState::plaintext)
+                .expand_width()
+                .controller(DisableWithMode(&[1])),
+        );
+
+    let model = Flex::row()
+        .with_child(soft_label("MODEL"))
+        .with_child(Radio::new("Enigma I", 0).lens(EnigmaState::model))
+        .with_child(Radio::new("M3", 1).lens(EnigmaState::model))
+        .with_child(Radio::new("M4", 2).lens(EnigmaState::model))
+        .with_spacer(8.0)
+        .with_child(soft_label("REFLECTOR"))
+        .with_child(
+            Label::dynamic(|reflector: &usize, _| String::from(REFLECTORS[*reflector].name))
+                .lens(EnigmaState::reflector),
+        )
+        .with_child(
+            Stepper::new()
+                .with_range(0.0, (REFLECTORS.len() - 1) as f64)
+                .with_step(1.0)
+                .with_wraparound(true)
+                .lens(EnigmaState::reflector.map(|x| *x as f64, |x, y| *x = y as usize)),
+        );
+
+    let rotors = Flex::row()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(Either::new(
+            |data: &EnigmaState, _| MODELS[data.model] == Model::M4,
+            rotor_slot("FOURTH", &GREEK_ROTORS).lens(EnigmaState::greek),
+            SizedBox::empty(),
+        ))
+        .with_child(rotor_slot("LEFT", &NORMAL_ROTORS).lens(EnigmaState::left))
+        .with_child(rotor_slot("MIDDLE", &NORMAL_ROTORS).lens(EnigmaState::middle))
+        .with_child(rotor_slot("RIGHT", &NORMAL_ROTORS).lens(EnigmaState::right));
+
+    let plugboard = Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(soft_label("PLUGBOARD"))
+        .with_child(TextBox::new().lens(EnigmaState::plugboard).expand_width());
+
+    let windows = Flex::row()
+        .with_child(soft_label("WINDOWS"))
+        .with_child(
+            Label::dynamic(|windows: &String, _| {
+                windows.chars().map(String::from).collect::<Vec<_>>().join(" ")
+            })
+            .with_text_size(28.0)
+            .lens(EnigmaState::windows),
+        );
+
+    let ciphertext = Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(
+            Flex::row()
+                .with_child(input_label("CIPHERTEXT").lens(EnigmaState::mode.map(|x| *x == 0, |_, _| {})))
+                .with_flex_spacer(1.0)
+                .with_child(text_file_buttons(EnigmaState::ciphertext, EnigmaState::status)),
+        )
+        .with_child(
+            TextArea::new(3)
+                .lens(EnigmaState::ciphertext)
+                .expand_width()
+                .controller(DisableWithMode(&[0])),
+        );
+
+    let column = Flex::column()
+        .main_axis_alignment(MainAxisAlignment::Center)
+        .cross_axis_alignment(CrossAxisAlignment::Center)
+        .with_child(mode_selector)
+        .with_spacer(2.0)
+        .with_child(plaintext)
+        .with_spacer(2.0)
+        .with_child(model)
+        .with_child(rotors)
+        .with_child(plugboard)
+        .with_spacer(2.0)
+        .with_child(windows)
+        .with_spacer(2.0)
+        .with_child(ciphertext)
+        .expand_height();
+
+    Recompute::new(
+        titled_panel(
+            "Enigma Machine",
+            " - Sends each letter through rotors that step as it is typed.",
+            EnigmaState::status,
+            column,
+        ),
+        recompute,
+        |data| data.plaintext.len() + data.ciphertext.len(),
+        EnigmaState::status,
+    )
+}
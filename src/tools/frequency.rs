@@ -0,0 +1,162 @@
+use ciphertools::analysis::{self, ENGLISH_FREQUENCIES};
+use crate::widget::{
+    bar_chart::{Bar, BarChart},
+    file_buttons::text_file_buttons,
+    recompute::Recompute,
+    soft_label,
+    text_area::TextArea,
+    titled_panel,
+};
+use druid::{
+    widget::{CrossAxisAlignment, Flex, Label, List, MainAxisAlignment, Scroll},
+    Data, Lens, Widget, WidgetExt,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// How many of the most common bigrams and trigrams to list.
+const TOP_NGRAMS: usize = 50;
+
+#[derive(Clone, Data, Lens, Serialize, Deserialize)]
+struct Row {
+    ngram: String,
+    count: usize,
+    percentage: f64,
+    english: Option<f64>,
+}
+
+#[derive(Clone, Data, Lens, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FrequencyState {
+    text: String,
+    status: String,
+    total: usize,
+    chart: Arc<Vec<Bar>>,
+    letters: Arc<Vec<Row>>,
+    bigrams: Arc<Vec<Row>>,
+    trigrams: Arc<Vec<Row>>,
+}
+
+impl FrequencyState {
+    fn analyse(&mut self) {
+        let counts = analysis::letter_counts(&self.text);
+        self.total = counts.iter().sum();
+        let total = self.total.max(1) as f64;
+
+        let letters = counts
+            .iter()
+            .zip(ENGLISH_FREQUENCIES.iter())
+            .enumerate()
+            .map(|(i, (&count, &english))| Row {
+                ngram: std::char::from_u32(i as u32 + 65).unwrap().to_string(),
+                count,
+                percentage: count as f64 / total * 100.0,
+                english: Some(english * 100.0),
+            })
+            .collect::<Vec<_>>();
+        self.chart = Arc::new(
+            letters
+                .iter()
+                .map(|row| Bar {
+                    label: row.ngram.clone(),
+                    value: row.percentage,
+                    reference: row.english,
+                })
+                .collect(),
+        );
+        self.letters = Arc::new(letters);
+        self.bigrams = Arc::new(ngram_rows(&self.text, 2));
+        self.trigrams = Arc::new(ngram_rows(&self.text, 3));
+    }
+}
+
+fn ngram_rows(text: &str, n: usize) -> Vec<Row> {
+    let counts = analysis::ngram_counts(text, n);
+    let total = counts.iter().map(|(_, count)| count).sum::<usize>().max(1) as f64;
+    counts
+        .into_iter()
+        .take(TOP_NGRAMS)
+        .map(|(ngram, count)| Row {
+            ngram,
+            count,
+            percentage: count as f64 / total * 100.0,
+            english: None,
+        })
+        .collect()
+}
+
+/// Brings the outputs up to date after something changes.
+fn recompute(data: &mut FrequencyState, old: &FrequencyState) {
+    if data.text != old.text || data.letters.is_empty() {
+        data.analyse();
+        data.status = String::new();
+    }
+}
+
+fn table(title: &'static str) -> impl Widget<Arc<Vec<Row>>> {
+    Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(soft_label(title))
+        .with_flex_child(
+            Scroll::new(List::new(|| {
+                Label::dynamic(|row: &Row, _| match row.english {
+                    Some(english) => format!(
+                        "{}  {}  {:.2}%  (English {:.2}%)",
+                        row.ngram, row.count, row.percentage, english
+                    ),
+                    None => format!("{}  {}  {:.2}%", row.ngram, row.count, row.percentage),
+                })
+            }))
+            .vertical(),
+            1.0,
+        )
+}
+
+pub fn build_frequency_widget() -> impl Widget<FrequencyState> {
+    let text = Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(
+            Flex::row()
+                .with_child(soft_label("TEXT"))
+                .with_flex_spacer(1.0)
+                .with_child(text_file_buttons(FrequencyState::text, FrequencyState::status)),
+        )
+        .with_child(TextArea::new(3).lens(FrequencyState::text).expand_width());
+
+    let chart = Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(
+            Label::dynamic(|total: &usize, _| format!("LETTERS: {}", total))
+                .with_text_size(14.0)
+                .lens(FrequencyState::total),
+        )
+        .with_child(BarChart::new().lens(FrequencyState::chart));
+
+    let tables = Flex::row()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_flex_child(table("LETTERS").lens(FrequencyState::letters), 1.0)
+        .with_flex_child(table("BIGRAMS").lens(FrequencyState::bigrams), 1.0)
+        .with_flex_child(table("TRIGRAMS").lens(FrequencyState::trigrams), 1.0);
+
+    let column = Flex::column()
+        .main_axis_alignment(MainAxisAlignment::Center)
+        .cross_axis_alignment(CrossAxisAlignment::Center)
+        .with_child(text)
+        .with_spacer(2.0)
+        .with_child(chart)
+        .with_spacer(2.0)
+        .with_flex_child(tables, 1.0)
+        .expand_height();
+
+    Recompute::new(
+        titled_panel(
+            "Frequency Analysis",
+            " - Counts how often letters and runs of letters appear.",
+            FrequencyState::status,
+            column,
+        ),
+        recompute,
+        |data| data.text.len(),
+        FrequencyState::status,
+    )
+}
@@ -0,0 +1,134 @@
+use ciphertools::hex::{self, HexOptions, Separator};
+use crate::widget::{
+    file_buttons::{file_buttons, text_file_buttons},
+    recompute::Recompute,
+    soft_label,
+    text_area::TextArea,
+    titled_panel,
+};
+use druid::{
+    widget::{Checkbox, CrossAxisAlignment, Flex, MainAxisAlignment, Radio},
+    Data, Lens, Widget, WidgetExt,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+const NOT_UTF8: &str = "The bytes aren't UTF-8 text, so the plaintext only shows what it can";
+const SEPARATORS: [Separator; 4] = [Separator::None, Separator::Space, Separator::Colon, Separator::Dash];
+
+#[derive(Clone, Data, Lens, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HexState {
+    plaintext: String,
+    hex: String,
+    bytes: Arc<Vec<u8>>,
+    /// The separator's index in `SEPARATORS`.
+    separator: usize,
+    upper: bool,
+    status: String,
+}
+
+impl HexState {
+    /// Decodes `hex`, handed over from the Identify tab.
+    pub fn hand_off(&mut self, hex: String) {
+        let old = self.clone();
+        self.hex = hex;
+        recompute(self, &old);
+    }
+
+    fn options(&self) -> HexOptions {
+        HexOptions {
+            separator: SEPARATORS[self.separator],
+            upper: self.upper,
+        }
+    }
+
+    /// Shows `bytes` as plaintext, warning if they aren't UTF-8.
+    fn set_plaintext(&mut self) {
+        match std::str::from_utf8(&self.bytes) {
+            Ok(text) => {
+                self.plaintext = String::from(text);
+                self.status = String::new();
+            },
+            Err(_) => {
+                self.plaintext = String::from_utf8_lossy(&self.bytes).into_owned();
+                self.status = String::from(NOT_UTF8);
+            },
+        }
+    }
+}
+
+/// Brings the outputs up to date after something changes.
+fn recompute(data: &mut HexState, old: &HexState) {
+    let options = data.options();
+    if data.plaintext != old.plaintext {
+        data.bytes = Arc::new(data.plaintext.as_bytes().to_vec());
+        data.hex = hex::encode_bytes(&data.bytes, &options);
+        data.status = String::new();
+    } else if data.hex != old.hex {
+        match hex::decode_bytes(&data.hex) {
+            Ok(bytes) => {
+                data.bytes = Arc::new(bytes);
+                data.set_plaintext();
+            },
+            Err(e) => data.status = format!("Invalid hex {}", e),
+        }
+    } else if options != old.options() {
+        data.hex = hex::encode_bytes(&data.bytes, &options);
+    }
+}
+
+pub fn build_hex_widget() -> impl Widget<HexState> {
+    let plaintext = Flex::column()
+        .with_child(
+            Flex::row()
+                .with_child(soft_label("PLAINTEXT"))
+                .with_flex_spacer(1.0)
+                .with_child(file_buttons(
+                    HexState::status,
+                    |data: &mut HexState, file| data.hex = hex::encode_bytes(&file, &data.options()),
+                    |data| data.bytes.to_vec(),
+                )),
+        )
+        .with_child(TextArea::new(3).lens(HexState::plaintext).expand_width());
+
+    let options = Flex::row()
+        .with_child(soft_label("SEPARATOR"))
+        .with_child(Radio::new("None", 0).lens(HexState::separator))
+        .with_child(Radio::new("Space", 1).lens(HexState::separator))
+        .with_child(Radio::new("Colon", 2).lens(HexState::separator))
+        .with_child(Radio::new("Dash", 3).lens(HexState::separator))
+        .with_spacer(8.0)
+        .with_child(Checkbox::new("Uppercase").lens(HexState::upper));
+
+    let hex = Flex::column()
+        .with_child(
+            Flex::row()
+                .with_child(soft_label("HEX"))
+                .with_flex_spacer(1.0)
+                .with_child(text_file_buttons(HexState::hex, HexState::status)),
+        )
+        .with_child(TextArea::new(3).lens(HexState::hex).expand_width());
+
+    let column = Flex::column()
+        .main_axis_alignment(MainAxisAlignment::Center)
+        .cross_axis_alignment(CrossAxisAlignment::Center)
+        .with_child(plaintext)
+        .with_spacer(2.0)
+        .with_child(options)
+        .with_spacer(2.0)
+        .with_child(hex)
+        .expand_height();
+
+    Recompute::new(
+        titled_panel(
+            "Hex",
+            " - Writes each byte as two hexadecimal digits.",
+            HexState::status,
+            column,
+        ),
+        recompute,
+        |data| data.plaintext.len() + data.hex.len(),
+        HexState::status,
+    )
+}
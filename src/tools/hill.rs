@@ -0,0 +1,266 @@
+use ciphertools::{
+    hill::{self, Hill, Matrix},
+    Cipher,
+};
+use crate::widget::{
+    error_status,
+    file_buttons::text_file_buttons,
+    input_label,
+    mode_selector::{mode_selector, ModeColour},
+    recompute::Recompute,
+    soft_label,
+    text_area::TextArea,
+    titled_panel,
+};
+use druid::{
+    lens,
+    widget::{Controller, CrossAxisAlignment, Flex, Label, MainAxisAlignment, Stepper, TextBox, ViewSwitcher},
+    Data, Env, Event, EventCtx, Lens, LensExt, Widget, WidgetExt,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+const MIN_SIZE: usize = 2;
+const MAX_SIZE: usize = 5;
+
+#[derive(Clone, Data, Lens, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HillState {
+    plaintext: String,
+    ciphertext: String,
+    keyword: String,
+    size: usize,
+    /// The key matrix, row by row. Entries that haven't been filled in are `None`.
+    entries: Arc<Vec<Option<u32>>>,
+    /// The determinant and inverse of the key, or what's missing from it.
+    details: String,
+    status: String,
+    mode: usize,
+}
+
+impl Default for HillState {
+    fn default() -> Self {
+        HillState {
+            plaintext: String::new(),
+            ciphertext: String::new(),
+            keyword: String::new(),
+            size: MIN_SIZE,
+            entries: Arc::new(Matrix::identity(MIN_SIZE).unwrap().entries.into_iter().map(Some).collect()),
+            details: String::new(),
+            status: String::new(),
+            mode: 0,
+        }
+    }
+}
+
+impl HillState {
+    fn key(&self) -> Option<Matrix> {
+        Some(Matrix {
+            size: self.size,
+            entries: self.entries.iter().copied().collect::<Option<Vec<_>>>()?,
+        })
+    }
+
+    fn set_key(&mut self, key: Matrix) {
+        self.entries = Arc::new(key.entries.into_iter().map(Some).collect());
+    }
+
+    /// Changes the size of the key, keeping the entries that still fit and
+    /// filling in the rest from the identity matrix.
+    fn resize(&mut self, old_size: usize) {
+        let size = self.size;
+        let entries = (0..size * size)
+            .map(|i| {
+                let (row, column) = (i / size, i % size);
+                if row < old_size && column < old_size {
+                    self.entries[row * old_size + column]
+                } else {
+                    Some((row == column) as u32)
+                }
+            })
+            .collect();
+        self.entries = Arc::new(entries);
+    }
+}
+
+/// Brings the outputs up to date after something changes.
+fn recompute(data: &mut HillState, old: &HillState) {
+    if data.size != old.size {
+        data.resize(old.size);
+    }
+    if data.keyword != old.keyword || data.size != old.size {
+        if let Some(key) = Matrix::from_keyword(&data.keyword, data.size) {
+            data.set_key(key);
+        }
+    }
+
+    let result = match (data.mode, data.key()) {
+        (0, Some(key)) => {
+            // Encrypt
+            Hill.encrypt(&data.plaintext, &key).map(|ciphertext| data.ciphertext = ciphertext)
+        },
+        (1, Some(key)) => {
+            // Decrypt
+            Hill.decrypt(&data.ciphertext, &key).map(|plaintext| data.plaintext = plaintext)
+        },
+        (0, None) | (1, None) => Ok(()),
+        (2, _) => {
+            // Find Key
+            hill::find_key(&data.plaintext, &data.ciphertext, data.size).map(|key| data.set_key(key))
+        },
+        _ => panic!("hill: wrong mode"),
+    };
+    data.status = result.err().map(|e| error_status(&e)).unwrap_or_default();
+
+    data.details = match data.key() {
+        None => String::from("Fill in every entry of the key"),
+        Some(key) => match key.inverse() {
+            Some(inverse) => format!("Determinant {}, inverse {}", key.determinant(), inverse),
+            None => format!("Determinant {}", key.determinant()),
+        },
+    };
+}
+
+struct DisableWithMode(&'static [usize]);
+
+impl<W: Widget<HillState>> Controller<HillState, W> for DisableWithMode {
+    fn event(
+        &mut self,
+        child: &mut W,
+        ctx: &mut EventCtx,
+        event: &Event,
+        data: &mut HillState,
+        env: &Env,
+    ) {
+        if !self.0.contains(&data.mode) {
+            child.event(ctx, event, data, env);
+            return;
+        }
+        match event {
+            Event::MouseDown(_) | Event::MouseUp(_) | Event::KeyDown(_) | Event::KeyUp(_) => {},
+            _ => child.event(ctx, event, data, env),
+        }
+    }
+}
+
+/// A grid of text boxes, one for each entry of a `size` by `size` matrix.
+fn matrix_grid(size: usize) -> impl Widget<Arc<Vec<Option<u32>>>> {
+    let mut rows = Flex::column();
+    for row in 0..size {
+        let mut cells = Flex::row();
+        for column in 0..size {
+            let i = row * size + column;
+            cells.add_child(
+                TextBox::new()
+                    .lens(lens::Id.map(
+                        move |entries: &Arc<Vec<Option<u32>>>| {
+                            entries
+                                .get(i)
+                                .copied()
+                                .flatten()
+                                .map(|x| x.to_string())
+                                .unwrap_or_default()
+                        },
+                        move |entries: &mut Arc<Vec<Option<u32>>>, text: String| {
+                            if let Some(entry) = Arc::make_mut(entries).get_mut(i) {
+                                *entry = text.trim().parse::<u32>().ok().map(|x| x % 26);
+                            }
+                        },
+                    ))
+                    .fix_width(40.0)
+                    .padding(2.0),
+            );
+        }
+        rows.add_child(cells);
+    }
+    rows
+}
+
+pub fn build_hill_widget() -> impl Widget<HillState> {
+    let mode_selector = mode_selector(&[
+        ("Encrypt", ModeColour::Green),
+        ("Decrypt", ModeColour::Red),
+        ("Find Key", ModeColour::Blue),
+    ])
+    .lens(HillState::mode);
+
+    let plaintext = Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(
+            Flex::row()
+                .with_child(input_label("PLAINTEXT").lens(HillState::mode.map(|x| *x == 1, |_, _| {})))
+                .with_flex_spacer(1.0)
+                .with_child(text_file_buttons(HillState::plaintext, HillState::status)),
+        )
+        .with_child(
+            TextArea::new(3)
+                .lens(HillState::plaintext)
+                .expand_width()
+                .controller(DisableWithMode(&[1])),
+        );
+
+    let size = Flex::row()
+        .with_child(soft_label("SIZE"))
+        .with_child(Label::dynamic(|size: &usize, _| format!("{0} x {0}", size)).lens(HillState::size))
+        .with_child(
+            Stepper::new()
+                .with_range(MIN_SIZE as f64, MAX_SIZE as f64)
+                .with_step(1.0)
+                .lens(HillState::size.map(|x| *x as f64, |x, y| *x = y as usize)),
+        );
+
+    let key = Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(input_label("KEY").lens(HillState::mode.map(|x| *x == 2, |_, _| {})))
+        .with_child(
+            Flex::row()
+                .with_child(soft_label("KEYWORD"))
+                .with_flex_child(TextBox::new().lens(HillState::keyword).expand_width(), 1.0),
+        )
+        .with_child(ViewSwitcher::new(
+            |data: &HillState, _| data.size,
+            |size, _, _| matrix_grid(*size).lens(HillState::entries).boxed(),
+        ))
+        .with_child(Label::dynamic(|details: &String, _| details.clone()).lens(HillState::details))
+        .controller(DisableWithMode(&[2]));
+
+    let ciphertext = Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(
+            Flex::row()
+                .with_child(input_label("CIPHERTEXT").lens(HillState::mode.map(|x| *x == 0, |_, _| {})))
+                .with_flex_spacer(1.0)
+                .with_child(text_file_buttons(HillState::ciphertext, HillState::status)),
+        )
+        .with_child(
+            TextArea::new(3)
+                .lens(HillState::ciphertext)
+                .expand_width()
+                .controller(DisableWithMode(&[0])),
+        );
+
+    let column = Flex::column()
+        .main_axis_alignment(MainAxisAlignment::Center)
+        .cross_axis_alignment(CrossAxisAlignment::Center)
+        .with_child(mode_selector)
+        .with_spacer(2.0)
+        .with_child(plaintext)
+        .with_spacer(2.0)
+        .with_child(size)
+        .with_child(key)
+        .with_spacer(2.0)
+        .with_child(ciphertext)
+        .expand_height();
+
+    Recompute::new(
+        titled_panel(
+            "Hill Cipher",
+            " - Multiplies blocks of letters by the key matrix.",
+            HillState::status,
+            column,
+        ),
+        recompute,
+        |data| data.plaintext.len() + data.ciphertext.len(),
+        HillState::status,
+    )
+}
@@ -0,0 +1,187 @@
+use ciphertools::{
+    analysis::{ENGLISH_IOC, RANDOM_IOC},
+    identify::{self, FAMILIES},
+};
+use crate::{
+    theme::HOT_COLOUR,
+    widget::{
+        bar_chart::{Bar, BarChart},
+        file_buttons::text_file_buttons,
+        recompute::Recompute,
+        soft_label,
+        text_area::TextArea,
+        titled_panel,
+    },
+};
+use druid::{
+    lens,
+    theme::BUTTON_BORDER_RADIUS,
+    widget::{CrossAxisAlignment, Flex, Label, List, MainAxisAlignment, Painter, Scroll},
+    Data, Lens, LensExt, RenderContext, Selector, Widget, WidgetExt,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Asks for the text to be opened in the tab for a family, given as its index
+/// in `FAMILIES`.
+pub const HAND_OFF: Selector<(usize, String)> = Selector::new("ciphertools.hand-off");
+
+#[derive(Clone, Data, Serialize, Deserialize)]
+struct Row {
+    family: usize,
+    score: f64,
+    reason: String,
+}
+
+#[derive(Clone, Data, Lens, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IdentifyState {
+    text: String,
+    status: String,
+    statistics: Arc<Vec<String>>,
+    periodic_ioc: Arc<Vec<Bar>>,
+    guesses: Arc<Vec<Row>>,
+}
+
+impl IdentifyState {
+    fn identify(&mut self) {
+        let statistics = identify::statistics(&self.text);
+        let yes_no = |x: bool| if x { "yes" } else { "no" };
+        let divisors = statistics.divisors.iter().map(ToString::to_string).collect::<Vec<_>>();
+        self.statistics = Arc::new(vec![
+            format!("Length: {} characters, {} of them letters", statistics.length, statistics.letters),
+            format!("Characters: {} ({} different)", statistics.charset(), statistics.distinct),
+            format!(
+                "Index of coincidence: {:.3}  (English {:.3}, random {:.3})",
+                statistics.ioc, ENGLISH_IOC, RANDOM_IOC
+            ),
+            format!("Entropy: {:.2} bits per character", statistics.entropy),
+            format!(
+                "Letters divisible by: {}    Even: {}",
+                if divisors.is_empty() { String::from("none") } else { divisors.join(", ") },
+                yes_no(statistics.even_length()),
+            ),
+            format!(
+                "Doubled letters: {}  ({} within pairs)",
+                statistics.doubled_letters, statistics.doubled_pairs
+            ),
+            format!(
+                "Has J: {}    Has Z: {}    Digits only: {}",
+                yes_no(statistics.has_j),
+                yes_no(statistics.has_z),
+                yes_no(statistics.digits_only),
+            ),
+        ]);
+        self.periodic_ioc = Arc::new(
+            statistics
+                .periodic_ioc
+                .iter()
+                .enumerate()
+                .map(|(i, &ioc)| Bar {
+                    label: (i + 1).to_string(),
+                    value: ioc,
+                    reference: None,
+                })
+                .collect(),
+        );
+        self.guesses = Arc::new(
+            identify::guess(&self.text, &statistics)
+                .into_iter()
+                .map(|guess| Row {
+                    family: FAMILIES.iter().position(|&family| family == guess.family).unwrap(),
+                    score: guess.score,
+                    reason: guess.reason,
+                })
+                .collect(),
+        );
+    }
+}
+
+/// Brings the outputs up to date after something changes.
+fn recompute(data: &mut IdentifyState, old: &IdentifyState) {
+    if data.text != old.text || data.statistics.is_empty() {
+        data.identify();
+        data.status = String::new();
+    }
+}
+
+/// The likely families, best first. Clicking on one opens the text in its tab.
+fn guess_list() -> impl Widget<(String, Arc<Vec<Row>>)> {
+    Scroll::new(List::new(|| {
+        Label::dynamic(|(_, row): &(String, Row), _| {
+            format!("{}  {:.0}%  - {}", FAMILIES[row.family].name(), row.score * 100.0, row.reason)
+        })
+        .padding(2.0)
+        .expand_width()
+        .background(Painter::new(|ctx, _: &(String, Row), env| {
+            if ctx.is_hot() {
+                let background = ctx
+                    .size()
+                    .to_rect()
+                    .to_rounded_rect(env.get(BUTTON_BORDER_RADIUS));
+                ctx.fill(background, &env.get(HOT_COLOUR));
+            }
+        }))
+        .on_click(|ctx, (text, row): &mut (String, Row), _| {
+            ctx.submit_command(HAND_OFF.with((row.family, text.clone())), None)
+        })
+    }))
+    .vertical()
+}
+
+pub fn build_identify_widget() -> impl Widget<IdentifyState> {
+    let text = Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(
+            Flex::row()
+                .with_child(soft_label("CIPHERTEXT"))
+                .with_flex_spacer(1.0)
+                .with_child(text_file_buttons(IdentifyState::text, IdentifyState::status)),
+        )
+        .with_child(TextArea::new(3).lens(IdentifyState::text).expand_width());
+
+    let statistics = Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(soft_label("STATISTICS"))
+        .with_child(List::new(|| Label::dynamic(|line: &String, _| line.clone())).lens(IdentifyState::statistics))
+        .with_child(soft_label("INDEX OF COINCIDENCE FOR EACH KEY LENGTH"))
+        .with_child(
+            BarChart::new()
+                .with_reference_line(ENGLISH_IOC)
+                .with_reference_line(RANDOM_IOC)
+                .lens(IdentifyState::periodic_ioc),
+        );
+
+    let guesses = Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(soft_label("LIKELY CIPHERS (click to open in their tab)"))
+        .with_flex_child(
+            guess_list().lens(lens::Id.map(
+                |data: &IdentifyState| (data.text.clone(), data.guesses.clone()),
+                |_: &mut IdentifyState, _| {},
+            )),
+            1.0,
+        );
+
+    let column = Flex::column()
+        .main_axis_alignment(MainAxisAlignment::Center)
+        .cross_axis_alignment(CrossAxisAlignment::Center)
+        .with_child(text)
+        .with_spacer(2.0)
+        .with_child(statistics)
+        .with_spacer(2.0)
+        .with_flex_child(guesses, 1.0)
+        .expand_height();
+
+    Recompute::new(
+        titled_panel(
+            "Identify",
+            " - Guesses which cipher or encoding a text came from.",
+            IdentifyState::status,
+            column,
+        ),
+        recompute,
+        |data| data.text.len(),
+        IdentifyState::status,
+    )
+}
@@ -1,3 +1,18 @@
+pub mod affine;
+pub mod ascii85;
+pub mod base32;
+pub mod base58;
 pub mod base64;
+pub mod columnar;
+pub mod enigma;
+pub mod frequency;
+pub mod hex;
+pub mod hill;
+pub mod identify;
+pub mod morse;
+pub mod playfair;
+pub mod rail_fence;
+pub mod recipe;
 pub mod shift;
+pub mod substitution;
 pub mod vigenere;
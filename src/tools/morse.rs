@@ -0,0 +1,270 @@
+use ciphertools::{
+    morse::{self, Keying, MorseOptions},
+    wav::Wav,
+};
+use crate::widget::{
+    file_buttons::text_file_buttons,
+    recompute::Recompute,
+    soft_label,
+    text_area::TextArea,
+    titled_panel,
+};
+use druid::{
+    lens,
+    widget::{Button, CrossAxisAlignment, Flex, Label, MainAxisAlignment, Stepper, TextBox},
+    Data, Lens, LensExt, Widget, WidgetExt,
+};
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+#[derive(Clone, Data, Lens, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MorseState {
+    plaintext: String,
+    morse: String,
+    dot: String,
+    dash: String,
+    letter_separator: String,
+    word_separator: String,
+    wpm: u32,
+    /// The overall speed. Farnsworth timing is off when it is the same as `wpm`.
+    farnsworth: u32,
+    frequency: u32,
+    /// The WAV file to export to or import from.
+    path: String,
+    status: String,
+}
+
+impl Default for MorseState {
+    fn default() -> Self {
+        let options = MorseOptions::default();
+        let keying = Keying::default();
+        MorseState {
+            plaintext: String::new(),
+            morse: String::new(),
+            dot: options.dot.to_string(),
+            dash: options.dash.to_string(),
+            letter_separator: options.letter_separator,
+            word_separator: options.word_separator,
+            wpm: keying.wpm,
+            farnsworth: keying.farnsworth_wpm,
+            frequency: keying.frequency,
+            path: String::from("morse.wav"),
+            status: String::new(),
+        }
+    }
+}
+
+impl MorseState {
+    /// Decodes `morse`, handed over from the Identify tab.
+    pub fn hand_off(&mut self, morse: String) {
+        let old = self.clone();
+        self.morse = morse;
+        recompute(self, &old);
+    }
+
+    fn options(&self) -> Result<MorseOptions, String> {
+        let symbol = |name: &str, value: &str| {
+            let mut chars = value.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => Ok(c),
+                _ => Err(format!("Invalid options: the {} must be one character", name)),
+            }
+        };
+        let options = MorseOptions {
+            dot: symbol("dot", &self.dot)?,
+            dash: symbol("dash", &self.dash)?,
+            letter_separator: self.letter_separator.clone(),
+            word_separator: self.word_separator.clone(),
+        };
+        match morse::options_error(&options) {
+            Some(e) => Err(format!("Invalid options: {}", e)),
+            None => Ok(options),
+        }
+    }
+
+    fn keying(&self) -> Keying {
+        Keying {
+            wpm: self.wpm,
+            farnsworth_wpm: self.farnsworth.min(self.wpm),
+            frequency: self.frequency,
+            ..Keying::default()
+        }
+    }
+
+    fn encode(&mut self, options: &MorseOptions) {
+        match morse::encode(&self.plaintext, options) {
+            Ok(morse) => {
+                self.morse = morse;
+                self.status = String::new();
+            },
+            Err(e) => self.status = format!("Invalid text {}", e),
+        }
+    }
+
+    fn decode(&mut self, options: &MorseOptions) {
+        match morse::decode(&self.morse, options) {
+            Ok(plaintext) => {
+                self.plaintext = plaintext;
+                self.status = String::new();
+            },
+            Err(e) => self.status = format!("Invalid Morse {}", e),
+        }
+    }
+
+    fn export_wav(&mut self) {
+        let options = match self.options() {
+            Ok(options) => options,
+            Err(e) => {
+                self.status = e;
+                return;
+            },
+        };
+        let wav = match morse::to_audio(&self.morse, &options, &self.keying()) {
+            Ok(wav) => wav,
+            Err(e) => {
+                self.status = format!("Could not make the audio: {}", e);
+                return;
+            },
+        };
+        self.status = match fs::write(&self.path, wav.to_bytes()) {
+            Ok(()) => format!("Saved {:.1} seconds of audio to {}", wav.duration(), self.path),
+            Err(e) => format!("Could not write {}: {}", self.path, e),
+        };
+    }
+
+    fn import_wav(&mut self) {
+        let options = match self.options() {
+            Ok(options) => options,
+            Err(e) => {
+                self.status = e;
+                return;
+            },
+        };
+        let wav = match fs::read(&self.path) {
+            Ok(bytes) => match Wav::from_bytes(&bytes) {
+                Ok(wav) => wav,
+                Err(e) => {
+                    self.status = format!("Invalid WAV file: {}", e);
+                    return;
+                },
+            },
+            Err(e) => {
+                self.status = format!("Could not read {}: {}", self.path, e);
+                return;
+            },
+        };
+        match morse::from_audio(&wav, &options) {
+            Ok(morse) => {
+                self.morse = morse;
+                self.decode(&options);
+            },
+            Err(e) => self.status = format!("Could not hear any Morse: {}", e),
+        }
+    }
+}
+
+/// Brings the outputs up to date after something changes.
+fn recompute(data: &mut MorseState, old: &MorseState) {
+    let options = match data.options() {
+        Ok(options) => options,
+        Err(e) => {
+            data.status = e;
+            return;
+        },
+    };
+    if data.plaintext != old.plaintext {
+        data.encode(&options);
+    } else if data.morse != old.morse {
+        data.decode(&options);
+    } else if old.options() != Ok(options.clone()) {
+        data.encode(&options);
+    }
+}
+
+/// A label and a stepper for a whole number.
+fn number_input(name: &'static str, min: f64, max: f64, step: f64) -> impl Widget<u32> {
+    Flex::row()
+        .with_child(soft_label(name))
+        .with_child(Label::dynamic(|x: &u32, _| x.to_string()))
+        .with_child(
+            Stepper::new()
+                .with_range(min, max)
+                .with_step(step)
+                .lens(lens::Id.map(|x: &u32| *x as f64, |x: &mut u32, y: f64| *x = y as u32)),
+        )
+}
+
+pub fn build_morse_widget() -> impl Widget<MorseState> {
+    let plaintext = Flex::column()
+        .with_child(
+            Flex::row()
+                .with_child(soft_label("PLAINTEXT"))
+                .with_flex_spacer(1.0)
+                .with_child(text_file_buttons(MorseState::plaintext, MorseState::status)),
+        )
+        .with_child(TextArea::new(3).lens(MorseState::plaintext).expand_width());
+
+    let symbols = Flex::row()
+        .with_child(soft_label("DOT"))
+        .with_child(TextBox::new().lens(MorseState::dot).fix_width(32.0))
+        .with_spacer(8.0)
+        .with_child(soft_label("DASH"))
+        .with_child(TextBox::new().lens(MorseState::dash).fix_width(32.0))
+        .with_spacer(8.0)
+        .with_child(soft_label("LETTER GAP"))
+        .with_child(TextBox::new().lens(MorseState::letter_separator).fix_width(48.0))
+        .with_spacer(8.0)
+        .with_child(soft_label("WORD GAP"))
+        .with_child(TextBox::new().lens(MorseState::word_separator).fix_width(48.0));
+
+    let morse = Flex::column()
+        .with_child(
+            Flex::row()
+                .with_child(soft_label("MORSE"))
+                .with_flex_spacer(1.0)
+                .with_child(text_file_buttons(MorseState::morse, MorseState::status)),
+        )
+        .with_child(TextArea::new(3).lens(MorseState::morse).expand_width());
+
+    let keying = Flex::row()
+        .with_child(number_input("WPM", 5.0, 60.0, 1.0).lens(MorseState::wpm))
+        .with_spacer(8.0)
+        .with_child(number_input("FARNSWORTH WPM", 5.0, 60.0, 1.0).lens(MorseState::farnsworth))
+        .with_spacer(8.0)
+        .with_child(number_input("TONE (Hz)", 300.0, 1200.0, 50.0).lens(MorseState::frequency));
+
+    let audio = Flex::row()
+        .with_child(soft_label("WAV FILE"))
+        .with_flex_child(TextBox::new().lens(MorseState::path).expand_width(), 1.0)
+        .with_spacer(4.0)
+        .with_child(Button::new("Export").on_click(|_, data: &mut MorseState, _| data.export_wav()))
+        .with_spacer(4.0)
+        .with_child(Button::new("Import").on_click(|_, data: &mut MorseState, _| data.import_wav()));
+
+    let column = Flex::column()
+        .main_axis_alignment(MainAxisAlignment::Center)
+        .cross_axis_alignment(CrossAxisAlignment::Center)
+        .with_child(plaintext)
+        .with_spacer(2.0)
+        .with_child(symbols)
+        .with_spacer(2.0)
+        .with_child(morse)
+        .with_spacer(2.0)
+        .with_child(keying)
+        .with_spacer(2.0)
+        .with_child(audio)
+        .expand_height();
+
+    Recompute::new(
+        titled_panel(
+            "Morse Code",
+            " - Translates text to International Morse and back, and keys it as audio.",
+            MorseState::status,
+            column,
+        ),
+        recompute,
+        |data| data.plaintext.len() + data.morse.len(),
+        MorseState::status,
+    )
+}
@@ -0,0 +1,285 @@
+use ciphertools::playfair::{self, Merge, PlayfairKey, Square, Step};
+use crate::{
+    theme::HOT_COLOUR,
+    widget::{
+        error_status,
+        file_buttons::text_file_buttons,
+        input_label,
+        key_square::{Highlight, KeySquare, KeySquareData},
+        mode_selector::{mode_selector, ModeColour},
+        recompute::Recompute,
+        soft_label,
+        text_area::TextArea,
+        titled_panel,
+    },
+};
+use druid::{
+    lens,
+    theme::{BUTTON_BORDER_RADIUS, PRIMARY_DARK},
+    widget::{Checkbox, Controller, CrossAxisAlignment, Flex, Label, List, MainAxisAlignment, Painter, Scroll, TextBox},
+    Data, Env, Event, EventCtx, Lens, LensExt, RenderContext, Widget, WidgetExt,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+#[derive(Clone, Data, Lens, Serialize, Deserialize)]
+struct Digraph {
+    index: usize,
+    input: String,
+    output: String,
+    highlight: Highlight,
+}
+
+#[derive(Clone, Data, Lens, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PlayfairState {
+    plaintext: String,
+    ciphertext: String,
+    keyword: String,
+    omit_q: bool,
+    filler: String,
+    square: KeySquareData,
+    digraphs: Arc<Vec<Digraph>>,
+    /// The digraph under the mouse, which is highlighted in the square.
+    selected: Option<usize>,
+    status: String,
+    mode: usize,
+}
+
+impl PlayfairState {
+    /// Decrypts `ciphertext` with the current key, handed over from the Identify tab.
+    pub fn hand_off(&mut self, ciphertext: String) {
+        let old = self.clone();
+        self.ciphertext = ciphertext;
+        self.mode = 1;
+        recompute(self, &old);
+    }
+
+    fn key(&self) -> PlayfairKey {
+        PlayfairKey {
+            keyword: self.keyword.clone(),
+            merge: if self.omit_q { Merge::OmitQ } else { Merge::JIntoI },
+            filler: self.filler.chars().next().unwrap_or('X'),
+        }
+    }
+
+    fn show_steps(&mut self, steps: Vec<Step>) {
+        let digraphs = steps
+            .into_iter()
+            .enumerate()
+            .map(|(index, step)| Digraph {
+                index,
+                input: step.input.iter().collect(),
+                output: step.output.iter().collect(),
+                highlight: Highlight {
+                    from: (step.from[0], step.from[1]),
+                    to: (step.to[0], step.to[1]),
+                },
+            })
+            .collect::<Vec<_>>();
+        self.digraphs = Arc::new(digraphs);
+    }
+}
+
+/// Brings the outputs up to date after something changes.
+fn recompute(data: &mut PlayfairState, _old: &PlayfairState) {
+    let key = data.key();
+    let steps = match data.mode {
+        0 => {
+            // Encrypt
+            let steps = playfair::encrypt_steps(&data.plaintext, &key);
+            if let Ok(steps) = &steps {
+                data.ciphertext = steps.iter().flat_map(|step| step.output.iter()).collect();
+            }
+            steps
+        },
+        1 => {
+            // Decrypt
+            let steps = playfair::decrypt_steps(&data.ciphertext, &key);
+            if let Ok(steps) = &steps {
+                data.plaintext = steps.iter().flat_map(|step| step.output.iter()).collect();
+            }
+            steps
+        },
+        _ => panic!("playfair: wrong mode"),
+    };
+    match steps {
+        Ok(steps) => {
+            data.show_steps(steps);
+            data.status = String::new();
+        },
+        Err(e) => data.status = error_status(&e),
+    }
+
+    data.square.letters = Square::new(&key.keyword, key.merge).letters().iter().collect();
+    data.square.highlight = data
+        .selected
+        .and_then(|i| data.digraphs.get(i))
+        .map(|digraph| digraph.highlight);
+}
+
+struct DisableWithMode(&'static [usize]);
+
+impl<W: Widget<PlayfairState>> Controller<PlayfairState, W> for DisableWithMode {
+    fn event(
+        &mut self,
+        child: &mut W,
+        ctx: &mut EventCtx,
+        event: &Event,
+        data: &mut PlayfairState,
+        env: &Env,
+    ) {
+        if !self.0.contains(&data.mode) {
+            child.event(ctx, event, data, env);
+            return;
+        }
+        match event {
+            Event::MouseDown(_) | Event::MouseUp(_) | Event::KeyDown(_) | Event::KeyUp(_) => {},
+            _ => child.event(ctx, event, data, env),
+        }
+    }
+}
+
+/// Selects a digraph when the mouse moves over it.
+struct SelectOnHover;
+
+impl<W: Widget<(Option<usize>, Digraph)>> Controller<(Option<usize>, Digraph), W> for SelectOnHover {
+    fn event(
+        &mut self,
+        child: &mut W,
+        ctx: &mut EventCtx,
+        event: &Event,
+        data: &mut (Option<usize>, Digraph),
+        env: &Env,
+    ) {
+        if let Event::MouseMove(_) = event {
+            if ctx.is_hot() {
+                data.0 = Some(data.1.index);
+            }
+        }
+        child.event(ctx, event, data, env);
+    }
+}
+
+fn digraph_list() -> impl Widget<(Option<usize>, Arc<Vec<Digraph>>)> {
+    Scroll::new(
+        List::new(|| {
+            Label::dynamic(|(_, digraph): &(Option<usize>, Digraph), _| {
+                format!("{} → {}", digraph.input, digraph.output)
+            })
+            .padding(4.0)
+            .background(Painter::new(
+                |ctx, (selected, digraph): &(Option<usize>, Digraph), env| {
+                    let colour = if *selected == Some(digraph.index) {
+                        env.get(PRIMARY_DARK)
+                    } else if ctx.is_hot() {
+                        env.get(HOT_COLOUR)
+                    } else {
+                        return;
+                    };
+                    let background = ctx
+                        .size()
+                        .to_rect()
+                        .to_rounded_rect(env.get(BUTTON_BORDER_RADIUS));
+                    ctx.fill(background, &colour);
+                },
+            ))
+            .expand_width()
+            .controller(SelectOnHover)
+        }),
+    )
+    .vertical()
+}
+
+pub fn build_playfair_widget() -> impl Widget<PlayfairState> {
+    let mode_selector = mode_selector(&[("Encrypt", ModeColour::Green), ("Decrypt", ModeColour::Red)])
+        .lens(PlayfairState::mode);
+
+    let plaintext = Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(
+            Flex::row()
+                .with_child(input_label("PLAINTEXT").lens(PlayfairState::mode.map(|x| *x == 1, |_, _| {})))
+                .with_flex_spacer(1.0)
+                .with_child(text_file_buttons(PlayfairState::plaintext, PlayfairState::status)),
+        )
+        .with_child(
+            TextArea::new(3)
+                .lens(PlayfairState::plaintext)
+                .expand_width()
+                .controller(DisableWithMode(&[1])),
+        );
+
+    let key = Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(soft_label("KEYWORD"))
+        .with_child(TextBox::new().lens(PlayfairState::keyword).expand_width())
+        .with_child(
+            Flex::row()
+                .with_child(soft_label("FILLER"))
+                .with_child(TextBox::new().lens(PlayfairState::filler).fix_width(32.0))
+                .with_spacer(8.0)
+                .with_child(Checkbox::new("Omit Q instead of merging J into I").lens(PlayfairState::omit_q)),
+        );
+
+    let ciphertext = Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(
+            Flex::row()
+                .with_child(input_label("CIPHERTEXT").lens(PlayfairState::mode.map(|x| *x == 0, |_, _| {})))
+                .with_flex_spacer(1.0)
+                .with_child(text_file_buttons(PlayfairState::ciphertext, PlayfairState::status)),
+        )
+        .with_child(
+            TextArea::new(3)
+                .lens(PlayfairState::ciphertext)
+                .expand_width()
+                .controller(DisableWithMode(&[0])),
+        );
+
+    let inputs = Flex::column()
+        .with_child(plaintext)
+        .with_spacer(2.0)
+        .with_child(key)
+        .with_spacer(2.0)
+        .with_child(ciphertext);
+
+    let digraphs = Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(soft_label("DIGRAPHS (hover to show in the square)"))
+        .with_flex_child(
+            digraph_list().lens(lens::Id.map(
+                |data: &PlayfairState| (data.selected, data.digraphs.clone()),
+                |data: &mut PlayfairState, (selected, _)| data.selected = selected,
+            )),
+            1.0,
+        );
+
+    let column = Flex::column()
+        .main_axis_alignment(MainAxisAlignment::Center)
+        .cross_axis_alignment(CrossAxisAlignment::Center)
+        .with_child(mode_selector)
+        .with_spacer(2.0)
+        .with_child(
+            Flex::row()
+                .cross_axis_alignment(CrossAxisAlignment::Start)
+                .with_flex_child(inputs, 1.0)
+                .with_spacer(8.0)
+                .with_child(KeySquare.lens(PlayfairState::square)),
+        )
+        .with_spacer(2.0)
+        .with_flex_child(digraphs, 1.0)
+        .expand_height();
+
+    Recompute::new(
+        titled_panel(
+            "Playfair Cipher",
+            " - Swaps pairs of letters using a 5x5 square.",
+            PlayfairState::status,
+            column,
+        ),
+        recompute,
+        |data| data.plaintext.len() + data.ciphertext.len(),
+        PlayfairState::status,
+    )
+}
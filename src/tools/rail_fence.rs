@@ -0,0 +1,205 @@
+use ciphertools::{
+    rail_fence::{self, RailFence, RailFenceKey},
+    Cipher,
+};
+use crate::widget::{
+    file_buttons::text_file_buttons,
+    input_label,
+    letter_grid::{Cell, Grid, LetterGrid},
+    mode_selector::{mode_selector, ModeColour},
+    recompute::Recompute,
+    soft_label,
+    text_area::TextArea,
+    titled_panel,
+};
+use druid::{
+    widget::{Controller, CrossAxisAlignment, Flex, Label, MainAxisAlignment, Scroll, Stepper},
+    Data, Env, Event, EventCtx, Lens, LensExt, Widget, WidgetExt,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+const MAX_RAILS: usize = 20;
+
+#[derive(Clone, Data, Lens, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RailFenceState {
+    plaintext: String,
+    ciphertext: String,
+    rails: usize,
+    offset: usize,
+    grid: Grid,
+    status: String,
+    mode: usize,
+}
+
+impl Default for RailFenceState {
+    fn default() -> Self {
+        RailFenceState {
+            plaintext: String::new(),
+            ciphertext: String::new(),
+            rails: 3,
+            offset: 0,
+            grid: Grid::default(),
+            status: String::new(),
+            mode: 0,
+        }
+    }
+}
+
+impl RailFenceState {
+    /// Decrypts `ciphertext` with the current key, handed over from the Identify tab.
+    pub fn hand_off(&mut self, ciphertext: String) {
+        let old = self.clone();
+        self.ciphertext = ciphertext;
+        self.mode = 1;
+        recompute(self, &old);
+    }
+
+    fn key(&self) -> RailFenceKey {
+        RailFenceKey {
+            rails: self.rails,
+            offset: self.offset,
+        }
+    }
+
+    /// Lays the plaintext out in its zigzag, numbering each character with
+    /// where it ends up in the ciphertext.
+    fn update_grid(&mut self) {
+        let key = self.key();
+        let chars = self.plaintext.chars().collect::<Vec<_>>();
+        let mut cells = vec![Cell::default(); chars.len() * key.rails];
+        for (position, i) in rail_fence::read_order(chars.len(), key).into_iter().enumerate() {
+            let row = rail_fence::rail(i, key);
+            cells[row * chars.len() + i] = Cell::character(chars[i], (position + 1).to_string());
+        }
+        self.grid = Grid {
+            columns: chars.len(),
+            cells: Arc::new(cells),
+        };
+    }
+}
+
+/// Brings the outputs up to date after something changes.
+fn recompute(data: &mut RailFenceState, _old: &RailFenceState) {
+    data.offset %= 2 * (data.rails - 1);
+    match data.mode {
+        0 => {
+            // Encrypt
+            data.ciphertext = RailFence.encrypt(&data.plaintext, &data.key()).unwrap();
+        },
+        1 => {
+            // Decrypt
+            data.plaintext = RailFence.decrypt(&data.ciphertext, &data.key()).unwrap();
+        },
+        _ => panic!("rail fence: wrong mode"),
+    }
+    data.update_grid();
+}
+
+struct DisableWithMode(&'static [usize]);
+
+impl<W: Widget<RailFenceState>> Controller<RailFenceState, W> for DisableWithMode {
+    fn event(
+        &mut self,
+        child: &mut W,
+        ctx: &mut EventCtx,
+        event: &Event,
+        data: &mut RailFenceState,
+        env: &Env,
+    ) {
+        if !self.0.contains(&data.mode) {
+            child.event(ctx, event, data, env);
+            return;
+        }
+        match event {
+            Event::MouseDown(_) | Event::MouseUp(_) | Event::KeyDown(_) | Event::KeyUp(_) => {},
+            _ => child.event(ctx, event, data, env),
+        }
+    }
+}
+
+pub fn build_rail_fence_widget() -> impl Widget<RailFenceState> {
+    let mode_selector = mode_selector(&[("Encrypt", ModeColour::Green), ("Decrypt", ModeColour::Red)])
+        .lens(RailFenceState::mode);
+
+    let plaintext = Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(
+            Flex::row()
+                .with_child(input_label("PLAINTEXT").lens(RailFenceState::mode.map(|x| *x == 1, |_, _| {})))
+                .with_flex_spacer(1.0)
+                .with_child(text_file_buttons(RailFenceState::plaintext, RailFenceState::status)),
+        )
+        .with_child(
+            TextArea::new(3)
+                .lens(RailFenceState::plaintext)
+                .expand_width()
+                .controller(DisableWithMode(&[1])),
+        );
+
+    let key = Flex::row()
+        .with_child(soft_label("RAILS"))
+        .with_child(Label::dynamic(|rails: &usize, _| rails.to_string()).lens(RailFenceState::rails))
+        .with_child(
+            Stepper::new()
+                .with_range(2.0, MAX_RAILS as f64)
+                .with_step(1.0)
+                .lens(RailFenceState::rails.map(|x| *x as f64, |x, y| *x = y as usize)),
+        )
+        .with_spacer(8.0)
+        .with_child(soft_label("OFFSET"))
+        .with_child(Label::dynamic(|offset: &usize, _| offset.to_string()).lens(RailFenceState::offset))
+        .with_child(
+            Stepper::new()
+                .with_range(0.0, (2 * MAX_RAILS - 3) as f64)
+                .with_step(1.0)
+                .lens(RailFenceState::offset.map(|x| *x as f64, |x, y| *x = y as usize)),
+        );
+
+    let ciphertext = Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(
+            Flex::row()
+                .with_child(input_label("CIPHERTEXT").lens(RailFenceState::mode.map(|x| *x == 0, |_, _| {})))
+                .with_flex_spacer(1.0)
+                .with_child(text_file_buttons(RailFenceState::ciphertext, RailFenceState::status)),
+        )
+        .with_child(
+            TextArea::new(3)
+                .lens(RailFenceState::ciphertext)
+                .expand_width()
+                .controller(DisableWithMode(&[0])),
+        );
+
+    let grid = Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(soft_label("ZIGZAG (numbered by position in the ciphertext)"))
+        .with_flex_child(Scroll::new(LetterGrid.lens(RailFenceState::grid)), 1.0);
+
+    let column = Flex::column()
+        .main_axis_alignment(MainAxisAlignment::Center)
+        .cross_axis_alignment(CrossAxisAlignment::Center)
+        .with_child(mode_selector)
+        .with_spacer(2.0)
+        .with_child(plaintext)
+        .with_spacer(2.0)
+        .with_child(key)
+        .with_spacer(2.0)
+        .with_child(ciphertext)
+        .with_spacer(2.0)
+        .with_flex_child(grid, 1.0)
+        .expand_height();
+
+    Recompute::new(
+        titled_panel(
+            "Rail Fence Cipher",
+            " - Writes the text in a zigzag and reads it off rail by rail.",
+            RailFenceState::status,
+            column,
+        ),
+        recompute,
+        |data| data.plaintext.len() + data.ciphertext.len(),
+        RailFenceState::status,
+    )
+}
@@ -0,0 +1,201 @@
+use ciphertools::recipe::{Recipe, Step, OPERATIONS};
+use crate::widget::{
+    file_buttons::text_file_buttons,
+    recompute::Recompute,
+    soft_label,
+    text_area::TextArea,
+    titled_panel,
+};
+use druid::{
+    theme::FOREGROUND_DARK,
+    widget::{Button, CrossAxisAlignment, Flex, Label, List, MainAxisAlignment, Scroll, Stepper, TextBox},
+    Data, Lens, LensExt, Widget, WidgetExt,
+};
+use serde::{Deserialize, Serialize};
+use std::{fs, sync::Arc};
+
+#[derive(Clone, Data, Lens, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct StepState {
+    /// The operation's index in `OPERATIONS`.
+    operation: usize,
+    settings: String,
+    output: String,
+    /// Set by the step's Remove button, so that the controller can take it out.
+    remove: bool,
+}
+
+#[derive(Clone, Data, Lens, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RecipeState {
+    input: String,
+    steps: Arc<Vec<StepState>>,
+    /// The recipe file to save to or load from.
+    path: String,
+    status: String,
+}
+
+impl Default for RecipeState {
+    fn default() -> Self {
+        RecipeState {
+            input: String::new(),
+            steps: Arc::new(vec![StepState::default()]),
+            path: String::from("recipe.txt"),
+            status: String::new(),
+        }
+    }
+}
+
+impl RecipeState {
+    fn recipe(&self) -> Recipe {
+        Recipe {
+            steps: self
+                .steps
+                .iter()
+                .map(|step| Step {
+                    operation: String::from(OPERATIONS[step.operation].name),
+                    settings: step.settings.clone(),
+                })
+                .collect(),
+        }
+    }
+
+    /// What the output depends on, to tell when it needs working out again.
+    fn inputs(&self) -> (String, Vec<(usize, String)>) {
+        let steps = self.steps.iter().map(|step| (step.operation, step.settings.clone())).collect();
+        (self.input.clone(), steps)
+    }
+
+    /// Runs the recipe, showing each step's output under it.
+    fn run(&mut self) {
+        let mut outputs = self.recipe().run(&self.input).into_iter();
+        for step in Arc::make_mut(&mut self.steps) {
+            step.output = match outputs.next() {
+                Some(Ok(output)) => output,
+                Some(Err(e)) => format!("Error: {}", e),
+                None => String::new(),
+            };
+        }
+    }
+
+    fn save(&mut self) {
+        self.status = match fs::write(&self.path, self.recipe().to_string()) {
+            Ok(()) => format!("Saved the recipe to {}", self.path),
+            Err(e) => format!("Could not write {}: {}", self.path, e),
+        };
+    }
+
+    fn load(&mut self) {
+        let recipe = match fs::read_to_string(&self.path) {
+            Ok(text) => Recipe::parse(&text),
+            Err(e) => {
+                self.status = format!("Could not read {}: {}", self.path, e);
+                return;
+            },
+        };
+        match recipe {
+            Ok(recipe) => {
+                let steps = recipe
+                    .steps
+                    .into_iter()
+                    .map(|step| StepState {
+                        operation: OPERATIONS.iter().position(|x| x.name == step.operation).unwrap(),
+                        settings: step.settings,
+                        ..StepState::default()
+                    })
+                    .collect();
+                self.steps = Arc::new(steps);
+                self.status = format!("Loaded the recipe from {}", self.path);
+            },
+            Err(e) => self.status = format!("Invalid recipe: {}", e),
+        }
+    }
+}
+
+/// Brings the outputs up to date after something changes.
+fn recompute(data: &mut RecipeState, old: &RecipeState) {
+    if data.steps.iter().any(|step| step.remove) {
+        Arc::make_mut(&mut data.steps).retain(|step| !step.remove);
+    }
+    if data.inputs() != old.inputs() {
+        data.run();
+    }
+}
+
+fn step_row() -> impl Widget<StepState> {
+    let operation = Flex::row()
+        .with_child(
+            Label::dynamic(|operation: &usize, _| String::from(OPERATIONS[*operation].name))
+                .fix_width(160.0)
+                .lens(StepState::operation),
+        )
+        .with_child(
+            Stepper::new()
+                .with_range(0.0, (OPERATIONS.len() - 1) as f64)
+                .with_step(1.0)
+                .with_wraparound(true)
+                .lens(StepState::operation.map(|x| *x as f64, |x, y| *x = y as usize)),
+        )
+        .with_flex_child(TextBox::new().lens(StepState::settings).expand_width(), 1.0)
+        .with_spacer(4.0)
+        .with_child(Button::new("Remove").on_click(|_, data: &mut StepState, _| data.remove = true));
+
+    Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(operation)
+        .with_child(
+            Label::dynamic(|operation: &usize, _| format!("Settings: {}", OPERATIONS[*operation].settings))
+                .with_text_color(FOREGROUND_DARK)
+                .with_text_size(12.0)
+                .lens(StepState::operation),
+        )
+        .with_child(Label::dynamic(|output: &String, _| output.clone()).lens(StepState::output))
+        .padding(4.0)
+}
+
+pub fn build_recipe_widget() -> impl Widget<RecipeState> {
+    let input = Flex::column()
+        .with_child(
+            Flex::row()
+                .with_child(soft_label("INPUT"))
+                .with_flex_spacer(1.0)
+                .with_child(text_file_buttons(RecipeState::input, RecipeState::status)),
+        )
+        .with_child(TextArea::new(3).lens(RecipeState::input).expand_width());
+
+    let steps = Scroll::new(List::new(step_row).lens(RecipeState::steps)).vertical();
+
+    let buttons = Flex::row()
+        .with_child(Button::new("Add step").on_click(|_, data: &mut RecipeState, _| {
+            Arc::make_mut(&mut data.steps).push(StepState::default())
+        }))
+        .with_spacer(8.0)
+        .with_child(soft_label("RECIPE FILE"))
+        .with_flex_child(TextBox::new().lens(RecipeState::path).expand_width(), 1.0)
+        .with_spacer(4.0)
+        .with_child(Button::new("Save").on_click(|_, data: &mut RecipeState, _| data.save()))
+        .with_spacer(4.0)
+        .with_child(Button::new("Load").on_click(|_, data: &mut RecipeState, _| data.load()));
+
+    let column = Flex::column()
+        .main_axis_alignment(MainAxisAlignment::Start)
+        .cross_axis_alignment(CrossAxisAlignment::Center)
+        .with_child(input)
+        .with_spacer(2.0)
+        .with_flex_child(steps, 1.0)
+        .with_spacer(2.0)
+        .with_child(buttons)
+        .expand_height();
+
+    Recompute::new(
+        titled_panel(
+            "Recipe",
+            " - Runs the input through each step in turn.",
+            RecipeState::status,
+            column,
+        ),
+        recompute,
+        |data| data.input.len(),
+        RecipeState::status,
+    )
+}
@@ -1,105 +1,124 @@
+use ciphertools::{
+    alphabet::{self, Alphabet},
+    shift::{self, Shift},
+    Cipher,
+};
 use crate::widget::{
+    alphabet_editor::alphabet_editor,
+    candidate_list::{candidate_list, Candidate},
+    error_status,
+    file_buttons::text_file_buttons,
     input_label,
+    letter_grid::{comparison_grid, Grid, LetterGrid},
     mode_selector::{mode_selector, ModeColour},
+    recompute::Recompute,
+    soft_label,
+    text_area::TextArea,
+    text_options::{text_options, TextOptionsState},
     titled_panel,
 };
 use druid::{
-    widget::{Controller, CrossAxisAlignment, Flex, MainAxisAlignment, Stepper, TextBox},
+    lens,
+    widget::{Controller, CrossAxisAlignment, Either, Flex, MainAxisAlignment, Scroll, SizedBox, Stepper, TextBox, ViewSwitcher},
     Data, Env, Event, EventCtx, Lens, LensExt, Widget, WidgetExt,
 };
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 
-fn shift(plaintext: &str, count: u8) -> String {
-    let mut out = String::with_capacity(plaintext.len());
-    for mut c in plaintext.chars() {
-        let mut x = c as u32;
-        match c {
-            'a'..='z' => {
-                x %= 97;
-                x += count as u32;
-                x %= 26;
-                x += 97;
-            }
-            'A'..='Z' => {
-                x %= 65;
-                x += count as u32;
-                x %= 26;
-                x += 65;
-            }
-            _ => {}
-        }
-        c = std::char::from_u32(x).unwrap();
-        out.push(c);
-    }
-    out
+#[derive(Clone, Data, Lens, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ShiftState {
+    plaintext: String,
+    ciphertext: String,
+    count: Option<u32>,
+    alphabet: String,
+    options: TextOptionsState,
+    comparison: Grid,
+    candidates: Arc<Vec<Candidate<u32>>>,
+    status: String,
+    mode: usize,
 }
 
-fn find_key(plaintext: &str, ciphertext: &str) -> Option<u8> {
-    let mut current = None;
-    for (plain, cipher) in plaintext.chars().zip(ciphertext.chars()) {
-        match (plain.is_ascii_alphabetic(), cipher.is_ascii_alphabetic()) {
-            (true, true) => {
-                if plain.is_ascii_uppercase() != cipher.is_ascii_uppercase() {
-                    return None;
-                }
-                let key = ((cipher as i32 - plain as i32) % 26) as u8;
-                if let Some(current) = current {
-                    if current != key {
-                        return None;
-                    }
-                } else {
-                    current = Some(key as u8);
-                }
-            }
-            (false, false) => continue,
-            _ => return None,
+impl Default for ShiftState {
+    fn default() -> Self {
+        ShiftState {
+            plaintext: String::new(),
+            ciphertext: String::new(),
+            count: None,
+            alphabet: String::from(alphabet::PRESETS[0].letters),
+            options: TextOptionsState::default(),
+            comparison: Grid::default(),
+            candidates: Arc::new(Vec::new()),
+            status: String::new(),
+            mode: 0,
         }
     }
-    current
 }
 
-#[derive(Clone, Data, Lens, Default)]
-pub struct ShiftState {
-    plaintext: String,
-    ciphertext: String,
-    count: Option<u8>,
-    mode: usize,
+impl ShiftState {
+    /// Cracks `ciphertext`, handed over from the Identify tab.
+    pub fn hand_off(&mut self, ciphertext: String) {
+        let old = self.clone();
+        self.ciphertext = ciphertext;
+        self.mode = 3;
+        recompute(self, &old);
+    }
 }
 
-struct ShiftController;
-
-impl<W: Widget<ShiftState>> Controller<ShiftState, W> for ShiftController {
-    fn event(
-        &mut self,
-        child: &mut W,
-        ctx: &mut EventCtx,
-        event: &Event,
-        data: &mut ShiftState,
-        env: &Env,
-    ) {
-        child.event(ctx, event, data, env);
-        match data.mode {
-            0 => {
-                // Encrypt
-                if let Some(count) = data.count {
-                    data.ciphertext = shift(&data.plaintext, count);
-                }
-            }
-            1 => {
-                // Decrypt
-                if let Some(count) = data.count {
-                    data.plaintext = shift(&data.ciphertext, 26 - count);
-                }
+/// Brings the outputs up to date after something changes.
+fn recompute(data: &mut ShiftState, old: &ShiftState) {
+    let cipher = match Alphabet::new(&data.alphabet) {
+        Some(alphabet) => Shift {
+            alphabet,
+            options: data.options.options(),
+        },
+        None => {
+            data.status = format!("Invalid alphabet: {}", alphabet::error(&data.alphabet).unwrap());
+            return;
+        },
+    };
+    data.count = data.count.map(|count| count % cipher.alphabet.len());
+    let result = match (data.mode, data.count) {
+        (0, Some(count)) => {
+            // Encrypt
+            cipher.encrypt(&data.plaintext, &count).map(|ciphertext| data.ciphertext = ciphertext)
+        },
+        (1, Some(count)) => {
+            // Decrypt
+            cipher.decrypt(&data.ciphertext, &count).map(|plaintext| data.plaintext = plaintext)
+        },
+        (0, None) | (1, None) => Ok(()),
+        (2, _) => {
+            // Find Key
+            let columns = shift::compare(&data.plaintext, &data.ciphertext, &cipher.alphabet, &cipher.options);
+            data.comparison = comparison_grid(&columns);
+            cipher.find_key(&data.plaintext, &data.ciphertext).map(|count| data.count = Some(count))
+        },
+        (3, _) => {
+            // Crack
+            if data.ciphertext != old.ciphertext || data.alphabet != old.alphabet || data.mode != old.mode {
+                let candidates = shift::crack(&data.ciphertext, &cipher.alphabet)
+                    .into_iter()
+                    .map(|candidate| Candidate {
+                        key: candidate.count,
+                        plaintext: candidate.plaintext,
+                        score: candidate.score,
+                    })
+                    .collect::<Vec<_>>();
+                data.count = candidates.first().map(|candidate| candidate.key);
+                data.candidates = Arc::new(candidates);
             }
-            2 => {
-                // Find Key
-                data.count = find_key(&data.plaintext, &data.ciphertext);
+            match data.count {
+                Some(count) => cipher.decrypt(&data.ciphertext, &count).map(|plaintext| data.plaintext = plaintext),
+                None => Ok(()),
             }
-            _ => panic!("shift: wrong mode"),
-        }
-    }
+        },
+        _ => panic!("shift: wrong mode"),
+    };
+    data.status = result.err().map(|e| error_status(&e)).unwrap_or_default();
 }
 
-struct DisableWithMode(usize);
+struct DisableWithMode(&'static [usize]);
 
 impl<W: Widget<ShiftState>> Controller<ShiftState, W> for DisableWithMode {
     fn event(
@@ -110,12 +129,12 @@ impl<W: Widget<ShiftState>> Controller<ShiftState, W> for DisableWithMode {
         data: &mut ShiftState,
         env: &Env,
     ) {
-        if data.mode != self.0 {
+        if !self.0.contains(&data.mode) {
             child.event(ctx, event, data, env);
             return;
         }
         match event {
-            Event::MouseDown(_) | Event::MouseUp(_) | Event::KeyDown(_) | Event::KeyUp(_) => return,
+            Event::MouseDown(_) | Event::MouseUp(_) | Event::KeyDown(_) | Event::KeyUp(_) => {},
             _ => child.event(ctx, event, data, env),
         }
     }
@@ -126,45 +145,77 @@ pub fn build_shift_widget() -> impl Widget<ShiftState> {
         ("Encrypt", ModeColour::Green),
         ("Decrypt", ModeColour::Red),
         ("Find Key", ModeColour::Blue),
+        ("Crack", ModeColour::Purple),
     ])
     .lens(ShiftState::mode);
 
     let plaintext = Flex::column()
         .cross_axis_alignment(CrossAxisAlignment::Start)
-        .with_child(input_label("PLAINTEXT").lens(ShiftState::mode.map(|x| *x == 1, |_, _| {})))
-        .with_child(TextBox::new().lens(ShiftState::plaintext).expand_width())
-        .controller(DisableWithMode(1));
+        .with_child(
+            Flex::row()
+                .with_child(input_label("PLAINTEXT").lens(ShiftState::mode.map(|x| *x == 1 || *x == 3, |_, _| {})))
+                .with_flex_spacer(1.0)
+                .with_child(text_file_buttons(ShiftState::plaintext, ShiftState::status)),
+        )
+        .with_child(
+            TextArea::new(3)
+                .lens(ShiftState::plaintext)
+                .expand_width()
+                .controller(DisableWithMode(&[1, 3])),
+        );
 
     let count = Flex::column()
         .cross_axis_alignment(CrossAxisAlignment::Start)
-        .with_child(input_label("COUNT").lens(ShiftState::mode.map(|x| *x == 2, |_, _| {})))
+        .with_child(input_label("COUNT").lens(ShiftState::mode.map(|x| *x == 2 || *x == 3, |_, _| {})))
         .with_child(
             Flex::row()
                 .with_child(TextBox::new().lens(ShiftState::count.map(
                     |x| {
                         x.as_ref()
                             .map(ToString::to_string)
-                            .unwrap_or_else(|| String::from("N/A"))
+                            .unwrap_or_default()
                     },
                     |x, y| *x = y.parse().ok(),
                 )))
-                .with_child(
-                    Stepper::new()
-                        .with_range(0., 25.)
-                        .with_wraparound(true)
-                        .lens(
-                            ShiftState::count
-                                .map(|x| x.unwrap_or(0) as f64, |x, y| *x = Some(y as u8)),
-                        ),
-                ),
+                .with_child(ViewSwitcher::new(
+                    |data: &ShiftState, _| Alphabet::new(&data.alphabet).map_or(1, |alphabet| alphabet.len()),
+                    |len, _, _| {
+                        Stepper::new()
+                            .with_range(0., (*len - 1) as f64)
+                            .with_wraparound(true)
+                            .lens(
+                                ShiftState::count
+                                    .map(|x| x.unwrap_or(0) as f64, |x, y| *x = Some(y as u32)),
+                            )
+                            .boxed()
+                    },
+                )),
         )
-        .controller(DisableWithMode(2));
+        .controller(DisableWithMode(&[2, 3]));
+
+    let alphabet = alphabet_editor().lens(ShiftState::alphabet);
+
+    let options = text_options(false).lens(ShiftState::options);
 
     let ciphertext = Flex::column()
         .cross_axis_alignment(CrossAxisAlignment::Start)
-        .with_child(input_label("CIPHERTEXT").lens(ShiftState::mode.map(|x| *x == 0, |_, _| {})))
-        .with_child(TextBox::new().lens(ShiftState::ciphertext).expand_width())
-        .controller(DisableWithMode(0));
+        .with_child(
+            Flex::row()
+                .with_child(input_label("CIPHERTEXT").lens(ShiftState::mode.map(|x| *x == 0, |_, _| {})))
+                .with_flex_spacer(1.0)
+                .with_child(text_file_buttons(ShiftState::ciphertext, ShiftState::status)),
+        )
+        .with_child(
+            TextArea::new(3)
+                .lens(ShiftState::ciphertext)
+                .expand_width()
+                .controller(DisableWithMode(&[0])),
+        );
+
+    let comparison = Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(soft_label("COMPARISON (pale red: doesn't line up, purple: wrong case, red: doesn't fit the key)"))
+        .with_flex_child(Scroll::new(LetterGrid.lens(ShiftState::comparison)), 1.0);
 
     let column = Flex::column()
         .main_axis_alignment(MainAxisAlignment::Center)
@@ -175,13 +226,37 @@ pub fn build_shift_widget() -> impl Widget<ShiftState> {
         .with_spacer(2.0)
         .with_child(count)
         .with_spacer(2.0)
+        .with_child(alphabet)
+        .with_child(options)
+        .with_spacer(2.0)
         .with_child(ciphertext)
+        .with_spacer(2.0)
+        .with_flex_child(
+            Either::new(|data: &ShiftState, _| data.mode == 2, comparison, SizedBox::empty()),
+            1.0,
+        )
+        .with_flex_child(
+            Either::new(
+                |data: &ShiftState, _| data.mode == 3,
+                candidate_list().lens(lens::Id.map(
+                    |data: &ShiftState| (data.count, data.candidates.clone()),
+                    |data: &mut ShiftState, (count, _)| data.count = count,
+                )),
+                SizedBox::empty(),
+            ),
+            1.0,
+        )
         .expand_height();
 
-    titled_panel(
-        "Shift Cipher",
-        " - Shifts each character along the alphabet.",
-        column,
+    Recompute::new(
+        titled_panel(
+            "Shift Cipher",
+            " - Shifts each character along the alphabet.",
+            ShiftState::status,
+            column,
+        ),
+        recompute,
+        |data| data.plaintext.len() + data.ciphertext.len(),
+        ShiftState::status,
     )
-    .controller(ShiftController)
 }
@@ -0,0 +1,287 @@
+use ciphertools::{
+    substitution::{self, Mapping, Substitution},
+    Cipher,
+};
+use crate::{
+    theme::RED,
+    widget::{
+        error_status,
+        file_buttons::text_file_buttons,
+        input_label,
+        mode_selector::{mode_selector, ModeColour},
+        recompute::Recompute,
+        soft_label,
+        text_area::TextArea,
+        titled_panel,
+    },
+};
+use druid::{
+    lens,
+    theme::BUTTON_BORDER_RADIUS,
+    widget::{Button, Controller, CrossAxisAlignment, Either, Flex, Label, MainAxisAlignment, Painter, SizedBox, TextBox},
+    Data, Env, Event, EventCtx, Lens, LensExt, RenderContext, Widget, WidgetExt,
+};
+use serde::{Deserialize, Serialize};
+
+/// How many times the automatic solver restarts its climb.
+const RESTARTS: usize = 100;
+
+#[derive(Clone, Data, Lens, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SubstitutionState {
+    plaintext: String,
+    ciphertext: String,
+    keyword: String,
+    key: String,
+    /// The plaintext letter assigned to each ciphertext letter in Solve mode,
+    /// or an underscore if there isn't one yet.
+    mapping: String,
+    status: String,
+    mode: usize,
+}
+
+impl SubstitutionState {
+    /// Solves `ciphertext`, handed over from the Identify tab.
+    pub fn hand_off(&mut self, ciphertext: String) {
+        let old = self.clone();
+        self.ciphertext = ciphertext;
+        self.mode = 3;
+        recompute(self, &old);
+    }
+
+    fn parsed_mapping(&self) -> Mapping {
+        let mut mapping = [None; 26];
+        for (x, c) in self.mapping.chars().take(26).enumerate() {
+            mapping[x] = if c.is_ascii_alphabetic() {
+                Some(c.to_ascii_uppercase() as u32 - 65)
+            } else {
+                None
+            };
+        }
+        mapping
+    }
+
+    fn set_mapping(&mut self, mapping: &Mapping) {
+        self.mapping = mapping
+            .iter()
+            .map(|x| x.map_or('_', |x| std::char::from_u32(x + 65).unwrap()))
+            .collect();
+    }
+}
+
+/// Brings the outputs up to date after something changes.
+fn recompute(data: &mut SubstitutionState, old: &SubstitutionState) {
+    if data.keyword != old.keyword {
+        data.key = substitution::keyword_alphabet(&data.keyword);
+    }
+    let result = match data.mode {
+        0 => {
+            // Encrypt
+            if data.key.is_empty() {
+                Ok(())
+            } else {
+                Substitution
+                    .encrypt(&data.plaintext, &data.key)
+                    .map(|ciphertext| data.ciphertext = ciphertext)
+            }
+        },
+        1 => {
+            // Decrypt
+            if data.key.is_empty() {
+                Ok(())
+            } else {
+                Substitution
+                    .decrypt(&data.ciphertext, &data.key)
+                    .map(|plaintext| data.plaintext = plaintext)
+            }
+        },
+        2 => {
+            // Find Key
+            Substitution.find_key(&data.plaintext, &data.ciphertext).map(|key| data.key = key)
+        },
+        3 => {
+            // Solve
+            let mapping = data.parsed_mapping();
+            data.plaintext = substitution::decrypt_partial(&data.ciphertext, &mapping);
+            if let Some(key) = substitution::mapping_to_key(&mapping) {
+                data.key = key;
+            }
+            Ok(())
+        },
+        _ => panic!("substitution: wrong mode"),
+    };
+    data.status = result.err().map(|e| error_status(&e)).unwrap_or_default();
+}
+
+struct DisableWithMode(&'static [usize]);
+
+impl<W: Widget<SubstitutionState>> Controller<SubstitutionState, W> for DisableWithMode {
+    fn event(
+        &mut self,
+        child: &mut W,
+        ctx: &mut EventCtx,
+        event: &Event,
+        data: &mut SubstitutionState,
+        env: &Env,
+    ) {
+        if !self.0.contains(&data.mode) {
+            child.event(ctx, event, data, env);
+            return;
+        }
+        match event {
+            Event::MouseDown(_) | Event::MouseUp(_) | Event::KeyDown(_) | Event::KeyUp(_) => {},
+            _ => child.event(ctx, event, data, env),
+        }
+    }
+}
+
+/// The plaintext letter for one ciphertext letter, highlighted if another
+/// ciphertext letter has been given the same one.
+fn mapping_cell(x: usize) -> impl Widget<SubstitutionState> {
+    let input = TextBox::new()
+        .lens(lens::Id.map(
+            move |data: &SubstitutionState| match data.parsed_mapping()[x] {
+                Some(p) => std::char::from_u32(p + 97).unwrap().to_string(),
+                None => String::new(),
+            },
+            move |data: &mut SubstitutionState, text: String| {
+                let mut mapping = data.parsed_mapping();
+                // Take the last letter typed, so that typing over a letter replaces it.
+                mapping[x] = text
+                    .chars()
+                    .rev()
+                    .find(char::is_ascii_alphabetic)
+                    .map(|c| c.to_ascii_lowercase() as u32 - 97);
+                data.set_mapping(&mapping);
+            },
+        ))
+        .fix_width(32.0)
+        .padding(2.0)
+        .background(Painter::new(move |ctx, data: &SubstitutionState, env| {
+            if substitution::conflicts(&data.parsed_mapping())[x] {
+                let background = ctx
+                    .size()
+                    .to_rect()
+                    .to_rounded_rect(env.get(BUTTON_BORDER_RADIUS));
+                ctx.fill(background, &env.get(RED));
+            }
+        }));
+
+    Flex::column()
+        .with_child(Label::new(std::char::from_u32(x as u32 + 65).unwrap().to_string()))
+        .with_child(input)
+}
+
+fn mapping_grid() -> impl Widget<SubstitutionState> {
+    let mut rows = Flex::column().cross_axis_alignment(CrossAxisAlignment::Start);
+    for half in 0..2 {
+        let mut row = Flex::row();
+        for x in half * 13..(half + 1) * 13 {
+            row.add_child(mapping_cell(x));
+        }
+        rows.add_child(row);
+    }
+
+    Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(soft_label("MAPPING (ciphertext letter above its plaintext letter)"))
+        .with_child(rows)
+        .with_spacer(2.0)
+        .with_child(
+            Flex::row()
+                .with_child(Button::new("Auto Solve").on_click(|_, data: &mut SubstitutionState, _| {
+                    let candidate = substitution::crack(&data.ciphertext, RESTARTS);
+                    if let Some(mapping) = substitution::key_to_mapping(&candidate.key) {
+                        data.set_mapping(&mapping);
+                    }
+                }))
+                .with_spacer(4.0)
+                .with_child(Button::new("Clear").on_click(|_, data: &mut SubstitutionState, _| {
+                    data.mapping = String::new();
+                })),
+        )
+}
+
+pub fn build_substitution_widget() -> impl Widget<SubstitutionState> {
+    let mode_selector = mode_selector(&[
+        ("Encrypt", ModeColour::Green),
+        ("Decrypt", ModeColour::Red),
+        ("Find Key", ModeColour::Blue),
+        ("Solve", ModeColour::Purple),
+    ])
+    .lens(SubstitutionState::mode);
+
+    let plaintext = Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(
+            Flex::row()
+                .with_child(input_label("PLAINTEXT").lens(SubstitutionState::mode.map(|x| *x == 1 || *x == 3, |_, _| {})))
+                .with_flex_spacer(1.0)
+                .with_child(text_file_buttons(SubstitutionState::plaintext, SubstitutionState::status)),
+        )
+        .with_child(
+            TextArea::new(3)
+                .lens(SubstitutionState::plaintext)
+                .expand_width()
+                .controller(DisableWithMode(&[1, 3])),
+        );
+
+    let keyword = Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(input_label("KEYWORD").lens(SubstitutionState::mode.map(|x| *x == 2 || *x == 3, |_, _| {})))
+        .with_child(TextBox::new().lens(SubstitutionState::keyword).expand_width())
+        .controller(DisableWithMode(&[2, 3]));
+
+    let key = Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(input_label("KEY").lens(SubstitutionState::mode.map(|x| *x == 2 || *x == 3, |_, _| {})))
+        .with_child(TextBox::new().lens(SubstitutionState::key).expand_width())
+        .controller(DisableWithMode(&[2, 3]));
+
+    let ciphertext = Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(
+            Flex::row()
+                .with_child(input_label("CIPHERTEXT").lens(SubstitutionState::mode.map(|x| *x == 0, |_, _| {})))
+                .with_flex_spacer(1.0)
+                .with_child(text_file_buttons(SubstitutionState::ciphertext, SubstitutionState::status)),
+        )
+        .with_child(
+            TextArea::new(3)
+                .lens(SubstitutionState::ciphertext)
+                .expand_width()
+                .controller(DisableWithMode(&[0])),
+        );
+
+    let column = Flex::column()
+        .main_axis_alignment(MainAxisAlignment::Center)
+        .cross_axis_alignment(CrossAxisAlignment::Center)
+        .with_child(mode_selector)
+        .with_spacer(2.0)
+        .with_child(plaintext)
+        .with_spacer(2.0)
+        .with_child(keyword)
+        .with_spacer(2.0)
+        .with_child(key)
+        .with_spacer(2.0)
+        .with_child(ciphertext)
+        .with_spacer(2.0)
+        .with_child(Either::new(
+            |data: &SubstitutionState, _| data.mode == 3,
+            mapping_grid(),
+            SizedBox::empty(),
+        ))
+        .expand_height();
+
+    Recompute::new(
+        titled_panel(
+            "Substitution Cipher",
+            " - Replaces each letter with another, using the key as the new alphabet.",
+            SubstitutionState::status,
+            column,
+        ),
+        recompute,
+        |data| data.plaintext.len() + data.ciphertext.len(),
+        SubstitutionState::status,
+    )
+}
@@ -1,149 +1,275 @@
-use crate::widget::{mode_selector::{ModeColour, mode_selector}, titled_panel, input_label};
+use ciphertools::{
+    alphabet::{self, Alphabet},
+    analysis::{ENGLISH_IOC, RANDOM_IOC},
+    vigenere::{self, Vigenere, VARIANTS},
+    Cipher,
+    CipherError,
+};
+use crate::{
+    theme::{BLUE, GREEN, HOT_COLOUR},
+    widget::{
+        alphabet_editor::alphabet_editor,
+        bar_chart::{Bar, BarChart},
+        candidate_list::{candidate_list, Candidate},
+        error_status,
+        file_buttons::text_file_buttons,
+        input_label,
+        letter_grid::{comparison_grid, Cell, Grid, LetterGrid},
+        mode_selector::{mode_selector, ModeColour},
+        recompute::Recompute,
+        soft_label,
+        text_area::TextArea,
+        text_options::{text_options, TextOptionsState},
+        titled_panel,
+    },
+};
 use druid::{
-    widget::{CrossAxisAlignment, Flex, MainAxisAlignment, TextBox, Controller},
-    Data, Lens, LensExt, Widget, WidgetExt, EventCtx, Event, Env
+    lens,
+    theme::{BUTTON_BORDER_RADIUS, PRIMARY_DARK},
+    widget::{
+        Checkbox, Controller, CrossAxisAlignment, Either, Flex, Label, List, MainAxisAlignment, Painter, Scroll,
+        SizedBox, Stepper, TextBox,
+    },
+    Data, Env, Event, EventCtx, Lens, LensExt, Rect, RenderContext, Widget, WidgetExt,
 };
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 
-fn encrypt(plaintext: &str, key: &str) -> Option<String> {
-    let mut ciphertext = String::new();
-    for (plain, key) in plaintext.chars().zip(key.chars().cycle()) {
-        let key_val;
-        match key {
-            'a'..='z' => {
-                key_val = key as u32 - 97;
-            },
-            'A'..='Z' => {
-                key_val = key as u32 - 65;
-            },
-            _ => return None,
-        }
+/// The longest key the Crack mode looks for.
+const MAX_KEY_LENGTH: usize = 20;
 
-        let mut x = plain as u32;
-        match plain {
-            'a'..='z' => {
-                x %= 97;
-                x += key_val as u32;
-                x %= 26;
-                x += 97;
-            },
-            'A'..='Z' => {
-                x %= 65;
-                x += key_val as u32;
-                x %= 26;
-                x += 65;
-            },
-            _ => {},
-        }
-        ciphertext.push(std::char::from_u32(x).unwrap());
-    }
-    Some(ciphertext)
+/// The key a crib gives at one offset, as listed in Crib Drag mode.
+#[derive(Clone, Data, Serialize, Deserialize)]
+struct Fragment {
+    offset: usize,
+    fragment: String,
+    period: Option<usize>,
+    english: bool,
+    /// What clicking on the fragment puts in the key box.
+    key: String,
 }
 
-fn decrypt(ciphertext: &str, key: &str) -> Option<String> {
-    let mut plaintext = String::new();
-    for (cipher, key) in ciphertext.chars().zip(key.chars().cycle()) {
-        let key_val;
-        match key {
-            'a'..='z' => {
-                key_val = 26 - (key as u32 - 97);
-            },
-            'A'..='Z' => {
-                key_val = 26 - (key as u32 - 65);
-            },
-            _ => return None,
-        }
+#[derive(Clone, Data, Lens, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VigenereState {
+    plaintext: String,
+    ciphertext: String,
+    key: String,
+    alphabet: String,
+    options: TextOptionsState,
+    variant: usize,
+    show_table: bool,
+    table: Grid,
+    comparison: Grid,
+    crib: String,
+    /// Only list the offsets where the crib gives a likely key.
+    likely_only: bool,
+    fragments: Arc<Vec<Fragment>>,
+    candidates: Arc<Vec<Candidate<String>>>,
+    column_ioc: Arc<Vec<Bar>>,
+    estimate: String,
+    status: String,
+    mode: usize,
+}
 
-        let mut x = cipher as u32;
-        match cipher {
-            'a'..='z' => {
-                x %= 97;
-                x += key_val as u32;
-                x %= 26;
-                x += 97;
-            },
-            'A'..='Z' => {
-                x %= 65;
-                x += key_val as u32;
-                x %= 26;
-                x += 65;
-            },
-            _ => {},
+impl Default for VigenereState {
+    fn default() -> Self {
+        VigenereState {
+            plaintext: String::new(),
+            ciphertext: String::new(),
+            key: String::new(),
+            alphabet: String::from(alphabet::PRESETS[0].letters),
+            options: TextOptionsState::default(),
+            variant: 0,
+            show_table: false,
+            table: Grid::default(),
+            comparison: Grid::default(),
+            crib: String::new(),
+            likely_only: false,
+            fragments: Arc::new(Vec::new()),
+            candidates: Arc::new(Vec::new()),
+            column_ioc: Arc::new(Vec::new()),
+            estimate: String::new(),
+            status: String::new(),
+            mode: 0,
         }
-        plaintext.push(std::char::from_u32(x).unwrap());
     }
-    Some(plaintext)
 }
 
-fn find_key(plaintext: &str, ciphertext: &str) -> Option<String> {
-    let mut key = String::new();
-    for (plain, cipher) in plaintext.chars().zip(ciphertext.chars()) {
-        match (plain.is_ascii_alphabetic(), cipher.is_ascii_alphabetic()) {
-            (true, true) => {
-                let difference = (cipher as i32 - plain as i32) % 26;
-                let c = std::char::from_u32((difference + 97) as u32).unwrap();
-                key.push(c);
+impl VigenereState {
+    /// Cracks `ciphertext`, handed over from the Identify tab.
+    pub fn hand_off(&mut self, ciphertext: String) {
+        let old = self.clone();
+        self.ciphertext = ciphertext;
+        self.mode = 3;
+        recompute(self, &old);
+    }
+
+    fn crack(&mut self, cipher: &Vigenere) {
+        let crack = match vigenere::crack(&self.ciphertext, MAX_KEY_LENGTH, cipher) {
+            Some(crack) => crack,
+            None => {
+                self.estimate = format!("The {} variant can't be cracked", cipher.variant.name());
+                self.column_ioc = Arc::new(Vec::new());
+                self.candidates = Arc::new(Vec::new());
+                return;
             },
-            (false, false) => if plain == cipher { continue } else { return None },
-            _ => return None,
+        };
+
+        let mut kasiski = (2..=crack.kasiski.len())
+            .filter(|&len| crack.kasiski[len - 1] > 0)
+            .collect::<Vec<_>>();
+        kasiski.sort_by_key(|&len| std::cmp::Reverse(crack.kasiski[len - 1]));
+        let kasiski = kasiski
+            .iter()
+            .take(3)
+            .map(|&len| format!("{} ({})", len, crack.kasiski[len - 1]))
+            .collect::<Vec<_>>();
+        self.estimate = format!(
+            "Friedman: {}    Kasiski: {}",
+            crack.friedman.map(|x| format!("{:.1}", x)).unwrap_or_else(|| String::from("N/A")),
+            if kasiski.is_empty() { String::from("N/A") } else { kasiski.join(", ") },
+        );
+
+        self.column_ioc = Arc::new(
+            crack
+                .column_ioc
+                .iter()
+                .enumerate()
+                .map(|(i, &ioc)| Bar {
+                    label: (i + 1).to_string(),
+                    value: ioc,
+                    reference: None,
+                })
+                .collect(),
+        );
+        self.candidates = Arc::new(
+            crack
+                .candidates
+                .into_iter()
+                .map(|candidate| Candidate {
+                    key: candidate.key,
+                    plaintext: candidate.plaintext,
+                    score: candidate.score,
+                })
+                .collect(),
+        );
+        if let Some(best) = self.candidates.first() {
+            self.key = best.key.clone();
         }
     }
 
-    'outer: for len in 1..=key.len() / 2 {
-        // the key should be entirely ascii, it doesn't make sense for it to not be
-        assert!(key.is_char_boundary(len));
-        let first = &key[0..len];
-        let num_chunks = key.len() / len;
-        for i in 1..num_chunks {
-            let chunk = &key[i * len..(i + 1) * len];
-            if !first.starts_with(chunk) {
-                continue 'outer;
-            }
+    fn crib_drag(&mut self, cipher: &Vigenere) -> Result<(), CipherError> {
+        if self.crib.trim().is_empty() {
+            self.fragments = Arc::new(Vec::new());
+            return Ok(());
         }
-        key.truncate(len);
+        let fragments = vigenere::crib_drag(&self.ciphertext, &self.crib, cipher)?;
+        self.fragments = Arc::new(
+            fragments
+                .into_iter()
+                .filter(|fragment| !self.likely_only || fragment.period.is_some() || fragment.english)
+                .map(|fragment| Fragment {
+                    offset: fragment.offset,
+                    key: fragment.key.as_ref().unwrap_or(&fragment.fragment).clone(),
+                    fragment: fragment.fragment,
+                    period: fragment.period,
+                    english: fragment.english,
+                })
+                .collect(),
+        );
+        Ok(())
     }
 
-    Some(key)
-}
-
-#[derive(Clone, Data, Lens, Default)]
-pub struct VigenereState {
-    plaintext: String,
-    ciphertext: String,
-    key: String,
-    mode: usize,
+    /// Lays out the tabula recta with the plaintext letters along the top and
+    /// the key letters down the side.
+    fn update_table(&mut self, cipher: &Vigenere) {
+        let header = |text: String| Cell {
+            text,
+            note: String::new(),
+            header: true,
+            ..Cell::default()
+        };
+        let top = std::iter::once(header(String::new())).chain(
+            cipher
+                .alphabet
+                .letters()
+                .iter()
+                .map(|&c| header(c.to_uppercase().collect())),
+        );
+        let rows = vigenere::tabula_recta(cipher).into_iter().flat_map(|(label, row)| {
+            std::iter::once(header(label)).chain(row.into_iter().map(|c| Cell::character(c, String::new())))
+        });
+        self.table = Grid {
+            columns: cipher.alphabet.len() as usize + 1,
+            cells: Arc::new(top.chain(rows).collect()),
+        };
+    }
 }
 
-struct VigenereController;
-
-impl<W: Widget<VigenereState>> Controller<VigenereState, W> for VigenereController {
-    fn event(
-        &mut self,
-        child: &mut W,
-        ctx: &mut EventCtx,
-        event: &Event,
-        data: &mut VigenereState,
-        env: &Env,
-    ) {
-        child.event(ctx, event, data, env);
-        match data.mode {
-            0 => {
-                // Encrypt
-                data.ciphertext = encrypt(&data.plaintext, &data.key).unwrap_or_else(|| String::from("Invalid key"));
-            }
-            1 => {
-                // Decrypt
-                data.plaintext = decrypt(&data.ciphertext, &data.key).unwrap_or_else(|| String::from("Invalid key"));
-            }
-            2 => {
-                // Find Key
-                data.key = find_key(&data.plaintext, &data.ciphertext).unwrap_or_else(|| String::from("Invalid text"));
-            }
-            _ => panic!("vigenère: wrong mode"),
-        }
+/// Brings the outputs up to date after something changes.
+fn recompute(data: &mut VigenereState, old: &VigenereState) {
+    let cipher = match Alphabet::new(&data.alphabet) {
+        Some(alphabet) => Vigenere {
+            alphabet,
+            options: data.options.options(),
+            variant: VARIANTS[data.variant],
+        },
+        None => {
+            data.status = format!("Invalid alphabet: {}", alphabet::error(&data.alphabet).unwrap());
+            return;
+        },
+    };
+    let changed = data.alphabet != old.alphabet || data.options != old.options || data.variant != old.variant;
+    if changed || data.table.cells.is_empty() {
+        data.update_table(&cipher);
     }
+    let result = match data.mode {
+        0 => {
+            // Encrypt
+            cipher.encrypt(&data.plaintext, &data.key).map(|ciphertext| data.ciphertext = ciphertext)
+        },
+        1 => {
+            // Decrypt
+            cipher.decrypt(&data.ciphertext, &data.key).map(|plaintext| data.plaintext = plaintext)
+        },
+        2 => {
+            // Find Key
+            data.comparison = comparison_grid(&vigenere::compare(&data.plaintext, &data.ciphertext, &cipher));
+            cipher.find_key(&data.plaintext, &data.ciphertext).map(|key| data.key = key)
+        },
+        3 => {
+            // Crack
+            if data.ciphertext != old.ciphertext || changed || data.mode != old.mode {
+                data.crack(&cipher);
+            }
+            cipher.decrypt(&data.ciphertext, &data.key).map(|plaintext| data.plaintext = plaintext)
+        },
+        4 => {
+            // Crib Drag
+            let dragged = if data.ciphertext != old.ciphertext
+                || data.crib != old.crib
+                || data.likely_only != old.likely_only
+                || changed
+                || data.mode != old.mode
+            {
+                data.crib_drag(&cipher)
+            } else {
+                Ok(())
+            };
+            dragged.and_then(|_| {
+                if data.key.is_empty() {
+                    return Ok(());
+                }
+                cipher.decrypt(&data.ciphertext, &data.key).map(|plaintext| data.plaintext = plaintext)
+            })
+        },
+        _ => panic!("vigenère: wrong mode"),
+    };
+    data.status = result.err().map(|e| error_status(&e)).unwrap_or_default();
 }
 
-struct DisableWithMode(usize);
+struct DisableWithMode(&'static [usize]);
 
 impl<W: Widget<VigenereState>> Controller<VigenereState, W> for DisableWithMode {
     fn event(
@@ -154,42 +280,169 @@ impl<W: Widget<VigenereState>> Controller<VigenereState, W> for DisableWithMode
         data: &mut VigenereState,
         env: &Env,
     ) {
-        if data.mode != self.0 {
+        if !self.0.contains(&data.mode) {
             child.event(ctx, event, data, env);
             return;
         }
         match event {
-            Event::MouseDown(_) | Event::MouseUp(_) | Event::KeyDown(_) | Event::KeyUp(_) => return,
+            Event::MouseDown(_) | Event::MouseUp(_) | Event::KeyDown(_) | Event::KeyUp(_) => {},
             _ => child.event(ctx, event, data, env),
         }
     }
 }
 
+/// The offsets of a crib drag, marked where the key repeats or looks like
+/// English. Clicking on one puts its key in the key box.
+fn fragment_list() -> impl Widget<(String, Arc<Vec<Fragment>>)> {
+    Scroll::new(List::new(|| {
+        Label::dynamic(|(_, fragment): &(String, Fragment), _| {
+            let mut notes = Vec::new();
+            if let Some(period) = fragment.period {
+                notes.push(format!("repeats every {}", period));
+            }
+            if fragment.english {
+                notes.push(String::from("looks like English"));
+            }
+            format!("{}:  {}  {}", fragment.offset, fragment.fragment, notes.join(", "))
+        })
+        .padding((8.0, 2.0, 2.0, 2.0))
+        .expand_width()
+        .background(Painter::new(|ctx, (key, fragment): &(String, Fragment), env| {
+            let size = ctx.size();
+            let colour = if *key == fragment.key {
+                Some(env.get(PRIMARY_DARK))
+            } else if ctx.is_hot() {
+                Some(env.get(HOT_COLOUR))
+            } else {
+                None
+            };
+            if let Some(colour) = colour {
+                let background = size.to_rect().to_rounded_rect(env.get(BUTTON_BORDER_RADIUS));
+                ctx.fill(background, &colour);
+            }
+            let marker = if fragment.period.is_some() {
+                env.get(GREEN)
+            } else if fragment.english {
+                env.get(BLUE)
+            } else {
+                return;
+            };
+            ctx.fill(Rect::new(0.0, 0.0, 4.0, size.height), &marker);
+        }))
+        .on_click(|_, (key, fragment), _| *key = fragment.key.clone())
+    }))
+    .vertical()
+}
+
 pub fn build_vigenere_widget() -> impl Widget<VigenereState> {
     let mode_selector = mode_selector(&[
         ("Encrypt", ModeColour::Green),
         ("Decrypt", ModeColour::Red),
         ("Find Key", ModeColour::Blue),
+        ("Crack", ModeColour::Purple),
+        ("Crib Drag", ModeColour::Blue),
     ])
     .lens(VigenereState::mode);
 
     let plaintext = Flex::column()
         .cross_axis_alignment(CrossAxisAlignment::Start)
-        .with_child(input_label("PLAINTEXT").lens(VigenereState::mode.map(|x| *x == 1, |_, _| {})))
-        .with_child(TextBox::new().lens(VigenereState::plaintext).expand_width())
-        .controller(DisableWithMode(1));
+        .with_child(
+            Flex::row()
+                .with_child(input_label("PLAINTEXT").lens(VigenereState::mode.map(|x| [1, 3, 4].contains(x), |_, _| {})))
+                .with_flex_spacer(1.0)
+                .with_child(text_file_buttons(VigenereState::plaintext, VigenereState::status)),
+        )
+        .with_child(
+            TextArea::new(3)
+                .lens(VigenereState::plaintext)
+                .expand_width()
+                .controller(DisableWithMode(&[1, 3, 4])),
+        );
 
     let key = Flex::column()
         .cross_axis_alignment(CrossAxisAlignment::Start)
-        .with_child(input_label("KEY").lens(VigenereState::mode.map(|x| *x == 2, |_, _| {})))
+        .with_child(input_label("KEY").lens(VigenereState::mode.map(|x| *x == 2 || *x == 3, |_, _| {})))
         .with_child(TextBox::new().lens(VigenereState::key).expand_width())
-        .controller(DisableWithMode(2));
+        .controller(DisableWithMode(&[2, 3]));
+
+    let alphabet = alphabet_editor().lens(VigenereState::alphabet);
+
+    let options = text_options(true).lens(VigenereState::options);
+
+    let variant = Flex::row()
+        .with_child(soft_label("VARIANT"))
+        .with_child(Label::dynamic(|variant: &usize, _| String::from(VARIANTS[*variant].name())).lens(VigenereState::variant))
+        .with_child(
+            Stepper::new()
+                .with_range(0.0, (VARIANTS.len() - 1) as f64)
+                .with_step(1.0)
+                .with_wraparound(true)
+                .lens(VigenereState::variant.map(|x| *x as f64, |x, y| *x = y as usize)),
+        )
+        .with_spacer(8.0)
+        .with_child(Checkbox::new("Show tabula recta").lens(VigenereState::show_table));
+
+    let table = Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(soft_label("TABULA RECTA (plaintext along the top, key down the side)"))
+        .with_flex_child(Scroll::new(LetterGrid.lens(VigenereState::table)), 1.0);
 
     let ciphertext = Flex::column()
         .cross_axis_alignment(CrossAxisAlignment::Start)
-        .with_child(input_label("CIPHERTEXT").lens(VigenereState::mode.map(|x| *x == 0, |_, _| {})))
-        .with_child(TextBox::new().lens(VigenereState::ciphertext).expand_width())
-        .controller(DisableWithMode(0));
+        .with_child(
+            Flex::row()
+                .with_child(input_label("CIPHERTEXT").lens(VigenereState::mode.map(|x| *x == 0, |_, _| {})))
+                .with_flex_spacer(1.0)
+                .with_child(text_file_buttons(VigenereState::ciphertext, VigenereState::status)),
+        )
+        .with_child(
+            TextArea::new(3)
+                .lens(VigenereState::ciphertext)
+                .expand_width()
+                .controller(DisableWithMode(&[0])),
+        );
+
+    let crack = Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(soft_label("KEY LENGTH"))
+        .with_child(Label::dynamic(|estimate: &String, _| estimate.clone()).lens(VigenereState::estimate))
+        .with_child(
+            BarChart::new()
+                .with_reference_line(ENGLISH_IOC)
+                .with_reference_line(RANDOM_IOC)
+                .lens(VigenereState::column_ioc),
+        )
+        .with_child(soft_label("CANDIDATES"))
+        .with_flex_child(
+            candidate_list().lens(lens::Id.map(
+                |data: &VigenereState| (Some(data.key.clone()), data.candidates.clone()),
+                |data: &mut VigenereState, (key, _)| {
+                    if let Some(key) = key {
+                        data.key = key;
+                    }
+                },
+            )),
+            1.0,
+        );
+
+    let comparison = Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(soft_label("COMPARISON (pale red: doesn't line up, purple: wrong case, red: doesn't fit the key)"))
+        .with_flex_child(Scroll::new(LetterGrid.lens(VigenereState::comparison)), 1.0);
+
+    let crib = Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(soft_label("CRIB"))
+        .with_child(TextBox::new().lens(VigenereState::crib).expand_width())
+        .with_child(Checkbox::new("Only show likely offsets").lens(VigenereState::likely_only))
+        .with_child(soft_label("OFFSETS (green: the key repeats, blue: the key looks like English)"))
+        .with_flex_child(
+            fragment_list().lens(lens::Id.map(
+                |data: &VigenereState| (data.key.clone(), data.fragments.clone()),
+                |data: &mut VigenereState, (key, _)| data.key = key,
+            )),
+            1.0,
+        );
 
     let column = Flex::column()
         .main_axis_alignment(MainAxisAlignment::Center)
@@ -200,13 +453,39 @@ pub fn build_vigenere_widget() -> impl Widget<VigenereState> {
         .with_spacer(2.0)
         .with_child(key)
         .with_spacer(2.0)
+        .with_child(variant)
+        .with_child(alphabet)
+        .with_child(options)
+        .with_spacer(2.0)
         .with_child(ciphertext)
+        .with_spacer(2.0)
+        .with_flex_child(
+            Either::new(|data: &VigenereState, _| data.mode == 2, comparison, SizedBox::empty()),
+            1.0,
+        )
+        .with_flex_child(
+            Either::new(|data: &VigenereState, _| data.mode == 3, crack, SizedBox::empty()),
+            1.0,
+        )
+        .with_flex_child(
+            Either::new(|data: &VigenereState, _| data.mode == 4, crib, SizedBox::empty()),
+            1.0,
+        )
+        .with_flex_child(
+            Either::new(|data: &VigenereState, _| data.show_table, table, SizedBox::empty()),
+            1.0,
+        )
         .expand_height();
 
-    titled_panel(
-        "Vigenère Cipher",
-        " - Shifts each character using the repeated key.",
-        column,
+    Recompute::new(
+        titled_panel(
+            "Vigenère Cipher",
+            " - Shifts each character using the repeated key, or one of its variants.",
+            VigenereState::status,
+            column,
+        ),
+        recompute,
+        |data| data.plaintext.len() + data.ciphertext.len(),
+        VigenereState::status,
     )
-    .controller(VigenereController)
 }
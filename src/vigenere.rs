@@ -0,0 +1,665 @@
+use crate::{
+    alphabet::Alphabet,
+    analysis::{bigram_score, index_of_coincidence, Quadgrams, ENGLISH_IOC, RANDOM_IOC},
+    shift,
+    text_options::{most_common, Column, Mark, TextOptions},
+    Cipher,
+    CipherError,
+};
+use std::collections::HashMap;
+
+/// The Vigenère cipher and its relatives over an alphabet. The key is a word
+/// whose letters give the shift for each character in turn.
+#[derive(Clone, Debug, Default)]
+pub struct Vigenere {
+    pub alphabet: Alphabet,
+    pub options: TextOptions,
+    pub variant: Variant,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Variant {
+    /// Adds the key to the plaintext.
+    #[default]
+    Vigenere,
+    /// Subtracts the plaintext from the key, so encrypting and decrypting are
+    /// the same.
+    Beaufort,
+    /// Subtracts the key from the plaintext.
+    VariantBeaufort,
+    /// Adds a key of digits to the plaintext.
+    Gronsfeld,
+    /// The key is a primer followed by the plaintext. The key only moves on
+    /// after letters.
+    PlaintextAutokey,
+    /// The key is a primer followed by the ciphertext. The key only moves on
+    /// after letters.
+    CiphertextAutokey,
+    /// The key is a text, like a passage from a book, at least as long as the
+    /// message. Anything in it that isn't in the alphabet is skipped.
+    RunningKey,
+    /// Swaps each letter with one from the other half of the alphabet, with a
+    /// table for each pair of key letters. Encrypting and decrypting are the
+    /// same, and the alphabet must have an even number of letters.
+    Porta,
+}
+
+pub const VARIANTS: [Variant; 8] = [
+    Variant::Vigenere,
+    Variant::Beaufort,
+    Variant::VariantBeaufort,
+    Variant::Gronsfeld,
+    Variant::PlaintextAutokey,
+    Variant::CiphertextAutokey,
+    Variant::RunningKey,
+    Variant::Porta,
+];
+
+impl Variant {
+    pub fn name(self) -> &'static str {
+        match self {
+            Variant::Vigenere => "Vigenère",
+            Variant::Beaufort => "Beaufort",
+            Variant::VariantBeaufort => "Variant Beaufort",
+            Variant::Gronsfeld => "Gronsfeld",
+            Variant::PlaintextAutokey => "Autokey (plaintext)",
+            Variant::CiphertextAutokey => "Autokey (ciphertext)",
+            Variant::RunningKey => "Running Key",
+            Variant::Porta => "Porta",
+        }
+    }
+
+    /// What the variant is called on the command line and in recipes.
+    pub fn id(self) -> &'static str {
+        match self {
+            Variant::Vigenere => "vigenere",
+            Variant::Beaufort => "beaufort",
+            Variant::VariantBeaufort => "variant-beaufort",
+            Variant::Gronsfeld => "gronsfeld",
+            Variant::PlaintextAutokey => "autokey",
+            Variant::CiphertextAutokey => "ciphertext-autokey",
+            Variant::RunningKey => "running-key",
+            Variant::Porta => "porta",
+        }
+    }
+
+    /// The variant with the given [`id`](Variant::id).
+    pub fn parse(id: &str) -> Option<Self> {
+        VARIANTS.iter().copied().find(|variant| variant.id() == id)
+    }
+
+    fn is_autokey(self) -> bool {
+        self == Variant::PlaintextAutokey || self == Variant::CiphertextAutokey
+    }
+}
+
+/// The shift given by each character of `key`. Fails if the key is empty or
+/// has anything in it that the variant can't use.
+fn key_shifts(key: &str, cipher: &Vigenere) -> Result<Vec<u32>, CipherError> {
+    let alphabet = &cipher.alphabet;
+    check_porta(cipher)?;
+    let shift = |(i, c): (usize, char)| match cipher.variant {
+        Variant::Gronsfeld => c.to_digit(10).ok_or(CipherError::InvalidKeyChar(i)),
+        _ => alphabet.index(c).ok_or(CipherError::InvalidKeyChar(i)),
+    };
+    let shifts = match cipher.variant {
+        Variant::RunningKey => key.chars().filter_map(|c| alphabet.index(c)).collect(),
+        _ => key.chars().enumerate().map(shift).collect::<Result<Vec<_>, _>>()?,
+    };
+    if shifts.is_empty() {
+        return Err(CipherError::InvalidKey(String::from("the key has no letters")));
+    }
+    Ok(shifts)
+}
+
+/// Porta pairs up the letters, so there has to be an even number of them.
+fn check_porta(cipher: &Vigenere) -> Result<(), CipherError> {
+    if cipher.variant == Variant::Porta && !cipher.alphabet.len().is_multiple_of(2) {
+        return Err(CipherError::InvalidKey(String::from("Porta needs an alphabet with an even number of letters")));
+    }
+    Ok(())
+}
+
+/// Encrypts or decrypts the letter at `x` with the key letter at `k`, in an
+/// alphabet of `n` letters.
+fn apply(variant: Variant, x: u32, k: u32, n: u32, decrypt: bool) -> u32 {
+    let k = k % n;
+    let subtract = match variant {
+        Variant::Beaufort => return (k + n - x) % n,
+        Variant::Porta => {
+            let (half, k) = (n / 2, k / 2);
+            return if x < half { (x + k) % half + half } else { (x - k) % half };
+        },
+        Variant::VariantBeaufort => !decrypt,
+        _ => decrypt,
+    };
+    if subtract {
+        (x + n - k) % n
+    } else {
+        (x + k) % n
+    }
+}
+
+/// The key letter that takes the letter at `p` to the one at `c`, if there is one.
+fn key_between(variant: Variant, p: u32, c: u32, n: u32) -> Option<u32> {
+    match variant {
+        Variant::Beaufort => Some((c + p) % n),
+        Variant::VariantBeaufort => Some((p + n - c) % n),
+        Variant::Gronsfeld => Some((c + n - p) % n).filter(|&k| k < 10),
+        Variant::Porta => {
+            let half = n / 2;
+            match (p < half, c < half) {
+                (true, false) => Some((c - p) % half * 2),
+                (false, true) => Some((p - c) % half * 2),
+                _ => None,
+            }
+        },
+        _ => Some((c + n - p) % n),
+    }
+}
+
+/// The character for a key letter.
+fn key_char(k: u32, cipher: &Vigenere) -> char {
+    match cipher.variant {
+        Variant::Gronsfeld => std::char::from_digit(k, 10).unwrap(),
+        _ => cipher.alphabet.letter(k, false),
+    }
+}
+
+/// Encrypts or decrypts each character of `text` with the next letter of the key.
+fn shift_by_key(text: &str, key: &str, cipher: &Vigenere, decrypt: bool) -> Result<String, CipherError> {
+    let (alphabet, options, variant) = (&cipher.alphabet, &cipher.options, cipher.variant);
+    let mut shifts = key_shifts(key, cipher)?;
+    let primer = shifts.len();
+    let mut position = 0;
+    let mut output = String::new();
+    for c in text.chars() {
+        let (x, upper) = match alphabet.find(c) {
+            Some(found) => found,
+            None => {
+                output.push(c);
+                if !options.key_on_letters_only() && !variant.is_autokey() {
+                    position += 1;
+                }
+                continue;
+            },
+        };
+        let k = match variant {
+            // These keys never repeat, so a running key has to be long enough.
+            Variant::RunningKey | Variant::PlaintextAutokey | Variant::CiphertextAutokey => {
+                *shifts.get(position).ok_or_else(|| {
+                    CipherError::InvalidKey(String::from("the running key is shorter than the text"))
+                })?
+            },
+            _ => shifts[position % primer],
+        };
+        let y = apply(variant, x, k, alphabet.len(), decrypt);
+        output.push(alphabet.letter(y, upper));
+        if variant.is_autokey() {
+            let (plain, cipher) = if decrypt { (y, x) } else { (x, y) };
+            shifts.push(if variant == Variant::PlaintextAutokey { plain } else { cipher });
+        }
+        position += 1;
+    }
+    Ok(options.format(&output, alphabet, !decrypt))
+}
+
+/// Fails if the key is invalid, or too short for a running key.
+pub fn encrypt(plaintext: &str, key: &str, cipher: &Vigenere) -> Result<String, CipherError> {
+    shift_by_key(plaintext, key, cipher, false)
+}
+
+/// Fails if the key is invalid, or too short for a running key.
+pub fn decrypt(ciphertext: &str, key: &str, cipher: &Vigenere) -> Result<String, CipherError> {
+    shift_by_key(ciphertext, key, cipher, true)
+}
+
+/// The options for lining up a plaintext with its ciphertext. An autokey only
+/// moves on after letters, since the key is made of them.
+fn key_options(cipher: &Vigenere) -> TextOptions {
+    TextOptions {
+        key_on_letters: cipher.options.key_on_letters || cipher.variant.is_autokey(),
+        ..cipher.options
+    }
+}
+
+/// Finds the shortest key that encrypts `plaintext` to `ciphertext`. A running
+/// key is as long as the text.
+pub fn find_key(plaintext: &str, ciphertext: &str, cipher: &Vigenere) -> Result<String, CipherError> {
+    let (alphabet, variant) = (&cipher.alphabet, cipher.variant);
+    let options = key_options(cipher);
+    check_porta(cipher)?;
+    // Only letters tell us what the key was, so positions where the text
+    // isn't a letter are left unknown.
+    let pairs = options.align(plaintext, ciphertext, alphabet)?;
+    if pairs.is_empty() {
+        return Err(shift::no_letters());
+    }
+    let len = if options.key_on_letters_only() {
+        pairs.len()
+    } else {
+        plaintext.chars().count()
+    };
+    let mut shifts = vec![None; len];
+    for pair in &pairs {
+        let shift = key_between(variant, pair.plain, pair.cipher, alphabet.len());
+        shifts[pair.position] = Some(shift.ok_or(CipherError::InconsistentKey(pair.index))?);
+    }
+
+    let len = match variant {
+        Variant::RunningKey => len,
+        Variant::PlaintextAutokey | Variant::CiphertextAutokey => {
+            // After the primer, the key is the text itself.
+            let stream = pairs
+                .iter()
+                .map(|pair| if variant == Variant::PlaintextAutokey { pair.plain } else { pair.cipher })
+                .collect::<Vec<_>>();
+            (1..=len)
+                .find(|&primer| (primer..len).all(|i| shifts[i] == Some(stream[i - primer])))
+                .unwrap_or(len)
+        },
+        _ => shortest_period(&shifts),
+    };
+    let key = (0..len)
+        .map(|i| {
+            let shift = shifts.iter().skip(i).step_by(len).find_map(|x| *x);
+            key_char(shift.unwrap_or(0), cipher)
+        })
+        .collect();
+    Ok(key)
+}
+
+/// Lines up `plaintext` with `ciphertext`, with the key letter that takes each
+/// plaintext letter to its ciphertext letter. For keys that repeat, the key
+/// length is picked to need the fewest letters plus exceptions, and letters
+/// that break from the rest of their column are marked as inconsistent.
+pub fn compare(plaintext: &str, ciphertext: &str, cipher: &Vigenere) -> Vec<Column> {
+    let (alphabet, variant) = (&cipher.alphabet, cipher.variant);
+    let mut columns = key_options(cipher).compare(plaintext, ciphertext, alphabet);
+    if check_porta(cipher).is_err() {
+        return columns.into_iter().map(|(column, _)| column).collect();
+    }
+    let mut shifts = Vec::new();
+    for (column, pair) in &mut columns {
+        if let Some(pair) = pair {
+            match key_between(variant, pair.plain, pair.cipher, alphabet.len()) {
+                Some(shift) => {
+                    column.key = Some(key_char(shift, cipher));
+                    shifts.push((pair.position, shift));
+                },
+                None => column.mark = Mark::Inconsistent,
+            }
+        }
+    }
+
+    let repeats = !matches!(
+        variant,
+        Variant::RunningKey | Variant::PlaintextAutokey | Variant::CiphertextAutokey
+    );
+    let len = shifts.iter().map(|&(position, _)| position + 1).max().unwrap_or(0);
+    if repeats && len > 1 {
+        // Each column of a key of this length, with its most common shift.
+        let key = |period: usize| {
+            (0..period)
+                .map(|i| most_common(shifts.iter().filter(|&&(p, _)| p % period == i).map(|&(_, x)| x)))
+                .collect::<Vec<_>>()
+        };
+        let exceptions = |period: usize, key: &[Option<u32>]| {
+            shifts.iter().filter(|&&(p, x)| key[p % period] != Some(x)).count()
+        };
+        let mut best = (len, len);
+        for period in 1..len {
+            if period >= best.0 {
+                break;
+            }
+            let cost = period + exceptions(period, &key(period));
+            if cost < best.0 {
+                best = (cost, period);
+            }
+        }
+        let period = best.1;
+        let key = key(period);
+        for (column, pair) in &mut columns {
+            if let Some(pair) = pair {
+                let shift = key_between(variant, pair.plain, pair.cipher, alphabet.len());
+                if column.mark == Mark::Fine && shift.is_some() && key[pair.position % period] != shift {
+                    column.mark = Mark::Inconsistent;
+                }
+            }
+        }
+    }
+    columns.into_iter().map(|(column, _)| column).collect()
+}
+
+/// The table of ciphertext letters for each key letter and plaintext letter,
+/// as rows labelled with their key letters. Porta has a row for each pair of
+/// key letters.
+pub fn tabula_recta(cipher: &Vigenere) -> Vec<(String, Vec<char>)> {
+    let (alphabet, variant) = (&cipher.alphabet, cipher.variant);
+    let n = alphabet.len();
+    let keys = match variant {
+        Variant::Gronsfeld => (0..10).collect::<Vec<_>>(),
+        Variant::Porta if !n.is_multiple_of(2) => Vec::new(),
+        Variant::Porta => (0..n).step_by(2).collect(),
+        _ => (0..n).collect(),
+    };
+    keys.into_iter()
+        .map(|k| {
+            let label = match variant {
+                Variant::Gronsfeld => k.to_string(),
+                Variant::Porta => [alphabet.letter(k, true), alphabet.letter(k + 1, true)].iter().collect(),
+                _ => alphabet.letter(k, true).to_string(),
+            };
+            let row = (0..n).map(|x| alphabet.letter(apply(variant, x, k, n, false), true)).collect();
+            (label, row)
+        })
+        .collect()
+}
+
+/// Finds the shortest key that repeats at least once and agrees with every
+/// known shift, falling back to the whole thing.
+fn shortest_period(shifts: &[Option<u32>]) -> usize {
+    (1..=shifts.len() / 2)
+        .find(|&len| {
+            (0..len).all(|i| {
+                let mut known = shifts.iter().skip(i).step_by(len).flatten();
+                match known.next() {
+                    Some(first) => known.all(|x| x == first),
+                    None => true,
+                }
+            })
+        })
+        .unwrap_or(shifts.len())
+}
+
+/// The [`Quadgrams`] score under which a key fragment looks like English.
+const ENGLISH_FRAGMENT_SCORE: f64 = 6.5;
+
+/// The key that a crib gives at one place in the ciphertext, as found by
+/// [`crib_drag`].
+#[derive(Clone, Debug)]
+pub struct CribFragment {
+    /// How many letters into the ciphertext the crib starts.
+    pub offset: usize,
+    /// The key letters under the crib.
+    pub fragment: String,
+    /// The shortest length the fragment repeats with, if it repeats at least
+    /// twice over.
+    pub period: Option<usize>,
+    /// The whole key, lined up with the start of the ciphertext, if the
+    /// fragment repeats.
+    pub key: Option<String>,
+    /// Whether the fragment looks like English, as keys are often words.
+    pub english: bool,
+}
+
+/// Slides `crib` along the letters of `ciphertext`, finding the key letters
+/// that would encrypt it at each offset. Offsets where no key fits are left
+/// out. Fragments of running keys and autokeys never repeat.
+pub fn crib_drag(ciphertext: &str, crib: &str, cipher: &Vigenere) -> Result<Vec<CribFragment>, CipherError> {
+    let (alphabet, variant) = (&cipher.alphabet, cipher.variant);
+    let n = alphabet.len();
+    check_porta(cipher)?;
+    let crib = crib.chars().filter_map(|c| alphabet.index(c)).collect::<Vec<_>>();
+    if crib.is_empty() {
+        return Err(CipherError::InvalidText(String::from("the crib has no letters")));
+    }
+    let options = key_options(cipher);
+    let letters_only = options.key_on_letters_only();
+    let mut letters = Vec::new();
+    let mut position = 0;
+    for c in ciphertext.chars() {
+        match alphabet.index(c) {
+            Some(x) => {
+                letters.push((position, x));
+                position += 1;
+            },
+            None if !letters_only => position += 1,
+            None => {},
+        }
+    }
+
+    let repeats = !matches!(
+        variant,
+        Variant::RunningKey | Variant::PlaintextAutokey | Variant::CiphertextAutokey
+    );
+    let quadgrams = Quadgrams::english();
+    let mut fragments = Vec::new();
+    for (offset, window) in letters.windows(crib.len()).enumerate() {
+        let shifts = window
+            .iter()
+            .zip(&crib)
+            .map(|(&(position, c), &p)| Some((position, key_between(variant, p, c, n)?)))
+            .collect::<Option<Vec<_>>>();
+        let shifts = match shifts {
+            Some(shifts) => shifts,
+            None => continue,
+        };
+        let fragment = shifts.iter().map(|&(_, k)| key_char(k, cipher)).collect::<String>();
+        let shift_at = shifts.iter().copied().collect::<HashMap<_, _>>();
+        let period = (1..shifts.len()).filter(|_| repeats).find(|&period| {
+            let overlaps = shifts
+                .iter()
+                .filter_map(|&(position, k)| Some((k, *shift_at.get(&(position + period))?)))
+                .collect::<Vec<_>>();
+            overlaps.len() >= 2 && overlaps.iter().all(|(a, b)| a == b)
+        });
+        let key = period.map(|period| {
+            (0..period)
+                .map(|i| {
+                    let shift = shifts.iter().find(|&&(position, _)| position % period == i).map(|&(_, k)| k);
+                    key_char(shift.unwrap_or(0), cipher)
+                })
+                .collect()
+        });
+        let english_letters = fragment.chars().filter_map(shift::letter_index).collect::<Vec<_>>();
+        let english = english_letters.len() >= 4 && quadgrams.score_letters(&english_letters) < ENGLISH_FRAGMENT_SCORE;
+        fragments.push(CribFragment {
+            offset,
+            fragment,
+            period,
+            key,
+            english,
+        });
+    }
+    Ok(fragments)
+}
+
+/// A possible key for a ciphertext, as found by [`crack`].
+#[derive(Clone, Debug)]
+pub struct Candidate {
+    pub key: String,
+    pub plaintext: String,
+    /// The [`bigram_score`] of the plaintext; lower is better.
+    pub score: f64,
+}
+
+/// The evidence for each key length, and the keys it led to.
+#[derive(Clone, Debug)]
+pub struct Crack {
+    /// The average index of coincidence of the columns for each key length,
+    /// starting from 1. Lengths near the true one are close to English.
+    pub column_ioc: Vec<f64>,
+    /// How many spacings between repeated trigrams each key length divides,
+    /// starting from 1.
+    pub kasiski: Vec<usize>,
+    /// The key length estimated by the Friedman test, if the text isn't too
+    /// random to make one.
+    pub friedman: Option<f64>,
+    /// The most English-looking decryption comes first.
+    pub candidates: Vec<Candidate>,
+}
+
+/// Splits the letters of `ciphertext` by which letter of a `len`-letter key
+/// they were encrypted with.
+fn columns(ciphertext: &str, len: usize, alphabet: &Alphabet) -> Vec<String> {
+    let mut columns = vec![String::new(); len];
+    for (i, c) in ciphertext.chars().enumerate() {
+        if alphabet.index(c).is_some() {
+            columns[i % len].push(c);
+        }
+    }
+    columns
+}
+
+/// The average index of coincidence of the columns for a key of length `len`.
+pub fn column_ioc(ciphertext: &str, len: usize, alphabet: &Alphabet) -> f64 {
+    let columns = columns(ciphertext, len, alphabet);
+    columns.iter().map(|column| index_of_coincidence(column)).sum::<f64>() / len as f64
+}
+
+/// Kasiski examination: for each key length up to `max_len`, counts how many
+/// of the spacings between repeated trigrams it divides.
+pub fn kasiski(ciphertext: &str, max_len: usize, alphabet: &Alphabet) -> Vec<usize> {
+    let chars = ciphertext.chars().collect::<Vec<_>>();
+    let mut last_seen = HashMap::new();
+    let mut counts = vec![0; max_len];
+    for (i, trigram) in chars.windows(3).enumerate() {
+        let trigram = trigram.iter().map(|&c| alphabet.index(c)).collect::<Option<Vec<_>>>();
+        if let Some(trigram) = trigram {
+            if let Some(last) = last_seen.insert(trigram, i) {
+                let spacing = i - last;
+                for len in 1..=max_len {
+                    if spacing % len == 0 {
+                        counts[len - 1] += 1;
+                    }
+                }
+            }
+        }
+    }
+    counts
+}
+
+/// The Friedman test's estimate of the key length, from how far the index of
+/// coincidence of the whole ciphertext is from English.
+pub fn friedman(ciphertext: &str) -> Option<f64> {
+    let ioc = index_of_coincidence(ciphertext);
+    if ioc <= RANDOM_IOC {
+        return None;
+    }
+    Some((ENGLISH_IOC - RANDOM_IOC) / (ioc - RANDOM_IOC))
+}
+
+/// Finds the most English-looking key of length `len` by solving each column
+/// as a shift cipher.
+pub fn solve_key(ciphertext: &str, len: usize, alphabet: &Alphabet) -> String {
+    columns(ciphertext, len, alphabet)
+        .iter()
+        .map(|column| alphabet.letter(shift::crack(column, alphabet)[0].count, false))
+        .collect()
+}
+
+/// Recovers the key from `ciphertext` alone, trying key lengths up to `max_len`.
+/// The statistics only look at the English letters in the alphabet.
+///
+/// Returns `None` for the variants other than Vigenère, Beaufort and Variant
+/// Beaufort, whose keys can't be found a column at a time.
+pub fn crack(ciphertext: &str, max_len: usize, cipher: &Vigenere) -> Option<Crack> {
+    let (alphabet, options) = (&cipher.alphabet, &cipher.options);
+    let n = alphabet.len();
+    // Variant Beaufort is Vigenère with the key negated, and Beaufort is
+    // Variant Beaufort with the ciphertext negated.
+    let (negate_text, negate_key) = match cipher.variant {
+        Variant::Vigenere => (false, false),
+        Variant::VariantBeaufort => (false, true),
+        Variant::Beaufort => (true, true),
+        _ => return None,
+    };
+    let letters = ciphertext.chars().filter(|&c| alphabet.index(c).is_some()).count();
+    let max_len = max_len.min(letters / 2).max(1);
+    // If the key only moves on after letters, it lines up with the letters
+    // once everything else is taken out.
+    let text = ciphertext
+        .chars()
+        .filter(|&c| !options.key_on_letters_only() || alphabet.index(c).is_some())
+        .map(|c| match alphabet.find(c) {
+            Some((x, upper)) if negate_text => alphabet.letter(n - x, upper),
+            _ => c,
+        })
+        .collect::<String>();
+    let column_ioc = (1..=max_len)
+        .map(|len| column_ioc(&text, len, alphabet))
+        .collect::<Vec<_>>();
+    let kasiski = kasiski(&text, max_len, alphabet);
+    let friedman = friedman(ciphertext);
+
+    // Multiples of the key length have just as high an IoC as the key length
+    // itself, so only try them if they do noticeably better than every
+    // divisor, and no divisor already looks like English.
+    let mut lengths = (1..=max_len)
+        .filter(|&len| {
+            (1..len).filter(|divisor| len % divisor == 0).all(|divisor| {
+                let ioc = column_ioc[divisor - 1];
+                ioc < 0.9 * ENGLISH_IOC && ioc < 0.9 * column_ioc[len - 1]
+            })
+        })
+        .collect::<Vec<_>>();
+    lengths.sort_by(|a, b| column_ioc[b - 1].partial_cmp(&column_ioc[a - 1]).unwrap());
+    lengths.truncate(5);
+
+    // Every length divides at least as many spacings as its multiples do, so
+    // take the longest that is still close to the best.
+    let most_spacings = kasiski.iter().skip(1).copied().max().unwrap_or(0);
+    if most_spacings > 0 {
+        let len = (2..=max_len)
+            .rev()
+            .find(|&len| kasiski[len - 1] as f64 >= 0.6 * most_spacings as f64)
+            .unwrap();
+        lengths.push(len);
+    }
+    if let Some(estimate) = friedman {
+        lengths.push((estimate.round() as usize).max(1).min(max_len));
+    }
+
+    let mut candidates = Vec::<Candidate>::new();
+    for len in lengths {
+        let shifts = solve_key(&text, len, alphabet)
+            .chars()
+            .map(|c| alphabet.index(c).map(|k| if negate_key { (n - k) % n } else { k }))
+            .collect::<Vec<_>>();
+        let key = shifts
+            .iter()
+            .take(shortest_period(&shifts))
+            .map(|k| alphabet.letter(k.unwrap(), false))
+            .collect::<String>();
+        if candidates.iter().any(|candidate| candidate.key == key) {
+            continue;
+        }
+        let plaintext = decrypt(ciphertext, &key, cipher).unwrap();
+        candidates.push(Candidate {
+            score: bigram_score(&plaintext),
+            key,
+            plaintext,
+        });
+    }
+    candidates.sort_by(|a, b| a.score.partial_cmp(&b.score).unwrap());
+
+    Some(Crack {
+        column_ioc,
+        kasiski,
+        friedman,
+        candidates,
+    })
+}
+
+impl Cipher for Vigenere {
+    type Key = String;
+
+    /// A running key also has to be long enough for the text, which this
+    /// doesn't check.
+    fn is_valid_key(&self, key: &String) -> bool {
+        key_shifts(key, self).is_ok()
+    }
+
+    fn encrypt(&self, plaintext: &str, key: &String) -> Result<String, CipherError> {
+        encrypt(plaintext, key, self)
+    }
+
+    fn decrypt(&self, ciphertext: &str, key: &String) -> Result<String, CipherError> {
+        decrypt(ciphertext, key, self)
+    }
+
+    fn find_key(&self, plaintext: &str, ciphertext: &str) -> Result<String, CipherError> {
+        find_key(plaintext, ciphertext, self)
+    }
+}
@@ -0,0 +1,126 @@
+//! Just enough of the WAV format to write a tone and read one back.
+
+use std::fmt;
+
+/// Why some bytes couldn't be read as a WAV file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WavError {
+    /// The bytes don't start with a RIFF WAVE header.
+    NotWav,
+    /// There is no format chunk, or no data chunk.
+    MissingChunk,
+    /// The format has no channels, a sample rate of 0 or samples under a byte.
+    BadFormat,
+    /// The samples are in this encoding, with this many bits, which can't be
+    /// read.
+    Unsupported { encoding: u16, bits: u16 },
+}
+
+impl fmt::Display for WavError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WavError::NotWav => write!(f, "this isn't a WAV file"),
+            WavError::MissingChunk => write!(f, "the WAV file is missing its format or its audio"),
+            WavError::BadFormat => write!(f, "the WAV file's format doesn't make sense"),
+            WavError::Unsupported { encoding, bits } => write!(
+                f,
+                "only PCM and 32-bit float WAV files can be read, not encoding {} with {} bits",
+                encoding, bits
+            ),
+        }
+    }
+}
+
+/// Mono audio, with samples from -1 to 1.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Wav {
+    pub sample_rate: u32,
+    pub samples: Vec<f32>,
+}
+
+impl Wav {
+    /// How long the audio lasts, in seconds.
+    pub fn duration(&self) -> f32 {
+        self.samples.len() as f32 / self.sample_rate as f32
+    }
+
+    /// Writes the audio as 16-bit PCM.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let data_len = self.samples.len() as u32 * 2;
+        let mut bytes = Vec::with_capacity(44 + data_len as usize);
+        bytes.extend_from_slice(b"RIFF");
+        bytes.extend_from_slice(&(36 + data_len).to_le_bytes());
+        bytes.extend_from_slice(b"WAVE");
+        bytes.extend_from_slice(b"fmt ");
+        bytes.extend_from_slice(&16u32.to_le_bytes());
+        bytes.extend_from_slice(&1u16.to_le_bytes());
+        bytes.extend_from_slice(&1u16.to_le_bytes());
+        bytes.extend_from_slice(&self.sample_rate.to_le_bytes());
+        bytes.extend_from_slice(&(self.sample_rate * 2).to_le_bytes());
+        bytes.extend_from_slice(&2u16.to_le_bytes());
+        bytes.extend_from_slice(&16u16.to_le_bytes());
+        bytes.extend_from_slice(b"data");
+        bytes.extend_from_slice(&data_len.to_le_bytes());
+        for &sample in &self.samples {
+            let sample = (sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16;
+            bytes.extend_from_slice(&sample.to_le_bytes());
+        }
+        bytes
+    }
+
+    /// Reads 8, 16, 24 or 32-bit PCM, or 32-bit float. Any channels after the
+    /// first are ignored.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, WavError> {
+        if bytes.len() < 12 || &bytes[..4] != b"RIFF" || &bytes[8..12] != b"WAVE" {
+            return Err(WavError::NotWav);
+        }
+        let mut format = None;
+        let mut data = None;
+        let mut rest = &bytes[12..];
+        while rest.len() >= 8 {
+            let id = &rest[..4];
+            let len = u32::from_le_bytes([rest[4], rest[5], rest[6], rest[7]]) as usize;
+            let body = &rest[8..rest.len().min(8 + len)];
+            match id {
+                b"fmt " if body.len() >= 16 => format = Some(body),
+                b"data" => data = Some(body),
+                _ => {},
+            }
+            // Chunks are padded to an even length.
+            let next = (8 + len + len % 2).min(rest.len());
+            rest = &rest[next..];
+        }
+        let (format, data) = match (format, data) {
+            (Some(format), Some(data)) => (format, data),
+            _ => return Err(WavError::MissingChunk),
+        };
+
+        let read_u16 = |i: usize| u16::from_le_bytes([format[i], format[i + 1]]);
+        let mut encoding = read_u16(0);
+        let channels = read_u16(2) as usize;
+        let sample_rate = u32::from_le_bytes([format[4], format[5], format[6], format[7]]);
+        let bits = read_u16(14);
+        // WAVE_FORMAT_EXTENSIBLE keeps the real encoding at the start of its
+        // sub-format.
+        if encoding == 0xfffe && format.len() >= 26 {
+            encoding = read_u16(24);
+        }
+        let sample_len = bits as usize / 8;
+        if channels == 0 || sample_rate == 0 || sample_len == 0 {
+            return Err(WavError::BadFormat);
+        }
+        let convert: fn(&[u8]) -> f32 = match (encoding, bits) {
+            (1, 8) => |x| (x[0] as f32 - 128.0) / 128.0,
+            (1, 16) => |x| i16::from_le_bytes([x[0], x[1]]) as f32 / 32768.0,
+            (1, 24) => |x| i32::from_le_bytes([0, x[0], x[1], x[2]]) as f32 / 2_147_483_648.0,
+            (1, 32) => |x| i32::from_le_bytes([x[0], x[1], x[2], x[3]]) as f32 / 2_147_483_648.0,
+            (3, 32) => |x| f32::from_le_bytes([x[0], x[1], x[2], x[3]]),
+            _ => return Err(WavError::Unsupported { encoding, bits }),
+        };
+        let samples = data
+            .chunks_exact(sample_len * channels)
+            .map(|frame| convert(&frame[..sample_len]))
+            .collect();
+        Ok(Wav { sample_rate, samples })
+    }
+}
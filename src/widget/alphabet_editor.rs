@@ -0,0 +1,35 @@
+use crate::widget::soft_label;
+use ciphertools::alphabet::PRESETS;
+use druid::{
+    lens,
+    widget::{Flex, Label, Stepper, TextBox},
+    LensExt, Widget, WidgetExt,
+};
+
+/// The preset that `letters` is, if it is one.
+fn preset_index(letters: &str) -> Option<usize> {
+    let letters = letters.chars().filter(|c| !c.is_whitespace()).collect::<String>();
+    PRESETS.iter().position(|preset| preset.letters == letters)
+}
+
+/// Edits the letters of an alphabet, with a stepper to go through the presets.
+pub fn alphabet_editor() -> impl Widget<String> {
+    Flex::row()
+        .with_child(soft_label("ALPHABET"))
+        .with_child(Label::dynamic(|letters: &String, _| match preset_index(letters) {
+            Some(i) => String::from(PRESETS[i].name),
+            None => String::from("Custom"),
+        }))
+        .with_child(
+            Stepper::new()
+                .with_range(0.0, (PRESETS.len() - 1) as f64)
+                .with_step(1.0)
+                .with_wraparound(true)
+                .lens(lens::Id.map(
+                    |letters: &String| preset_index(letters).unwrap_or(0) as f64,
+                    |letters: &mut String, x: f64| *letters = String::from(PRESETS[x as usize].letters),
+                )),
+        )
+        .with_spacer(4.0)
+        .with_flex_child(TextBox::new().expand_width(), 1.0)
+}
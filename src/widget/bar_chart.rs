@@ -0,0 +1,131 @@
+use crate::theme::{BLUE, PALE_RED, RED};
+use druid::{
+    kurbo::Line,
+    piet::{FontBuilder, Text, TextLayout, TextLayoutBuilder},
+    theme::{FONT_NAME, FOREGROUND_DARK},
+    BoxConstraints, Data, Env, Event, EventCtx, LayoutCtx, Lens, LifeCycle, LifeCycleCtx,
+    PaintCtx, Point, Rect, RenderContext, Size, UpdateCtx, Widget,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+const LABEL_HEIGHT: f64 = 16.0;
+const LABEL_SIZE: f64 = 10.0;
+
+/// One bar of a [`BarChart`], optionally marked with the value it is expected
+/// to have.
+#[derive(Clone, Data, Lens, Serialize, Deserialize)]
+pub struct Bar {
+    pub label: String,
+    pub value: f64,
+    pub reference: Option<f64>,
+}
+
+/// A bar chart, scaled so that the tallest bar or reference fills its height.
+pub struct BarChart {
+    reference_lines: Vec<f64>,
+}
+
+impl BarChart {
+    pub fn new() -> Self {
+        BarChart {
+            reference_lines: Vec::new(),
+        }
+    }
+
+    /// Draws a horizontal line across the whole chart at `value`.
+    pub fn with_reference_line(mut self, value: f64) -> Self {
+        self.reference_lines.push(value);
+        self
+    }
+}
+
+impl Widget<Arc<Vec<Bar>>> for BarChart {
+    fn event(&mut self, _ctx: &mut EventCtx, _event: &Event, _data: &mut Arc<Vec<Bar>>, _env: &Env) {}
+
+    fn lifecycle(
+        &mut self,
+        _ctx: &mut LifeCycleCtx,
+        _event: &LifeCycle,
+        _data: &Arc<Vec<Bar>>,
+        _env: &Env,
+    ) {
+    }
+
+    fn update(
+        &mut self,
+        ctx: &mut UpdateCtx,
+        old_data: &Arc<Vec<Bar>>,
+        data: &Arc<Vec<Bar>>,
+        _env: &Env,
+    ) {
+        if !old_data.same(data) {
+            ctx.request_paint();
+        }
+    }
+
+    fn layout(
+        &mut self,
+        _ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        _data: &Arc<Vec<Bar>>,
+        _env: &Env,
+    ) -> Size {
+        let width = if bc.is_width_bounded() {
+            bc.max().width
+        } else {
+            300.0
+        };
+        bc.constrain(Size::new(width, 120.0))
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &Arc<Vec<Bar>>, env: &Env) {
+        let size = ctx.size();
+        let chart_height = size.height - LABEL_HEIGHT;
+        let max = data
+            .iter()
+            .flat_map(|bar| bar.reference.into_iter().chain(Some(bar.value)))
+            .chain(self.reference_lines.iter().copied())
+            .fold(0.0, f64::max);
+        let y = |value: f64| {
+            if max > 0.0 {
+                chart_height - value / max * chart_height
+            } else {
+                chart_height
+            }
+        };
+
+        let font = ctx
+            .text()
+            .new_font_by_name(env.get(FONT_NAME), LABEL_SIZE)
+            .build()
+            .unwrap();
+        let bar_width = size.width / data.len().max(1) as f64;
+        for (i, bar) in data.iter().enumerate() {
+            let left = i as f64 * bar_width;
+            let rect = Rect::new(left + 1.0, y(bar.value), left + bar_width - 1.0, chart_height);
+            ctx.fill(rect, &env.get(BLUE));
+
+            if let Some(reference) = bar.reference {
+                let line = Line::new((left, y(reference)), (left + bar_width, y(reference)));
+                ctx.stroke(line, &env.get(RED), 2.0);
+            }
+
+            let layout = ctx
+                .text()
+                .new_text_layout(&font, &bar.label, f64::INFINITY)
+                .build()
+                .unwrap();
+            let origin = Point::new(
+                left + (bar_width - layout.width()) / 2.0,
+                size.height - (LABEL_HEIGHT - LABEL_SIZE) / 2.0,
+            );
+            ctx.draw_text(&layout, origin, &env.get(FOREGROUND_DARK));
+        }
+
+        for &value in &self.reference_lines {
+            let line = Line::new((0.0, y(value)), (size.width, y(value)));
+            ctx.stroke(line, &env.get(PALE_RED), 1.0);
+        }
+    }
+}
@@ -0,0 +1,51 @@
+use crate::theme::HOT_COLOUR;
+use druid::{
+    theme::{BUTTON_BORDER_RADIUS, PRIMARY_DARK},
+    widget::{Label, List, Painter, Scroll},
+    Data, RenderContext, Widget, WidgetExt,
+};
+use serde::{Deserialize, Serialize};
+use std::{fmt::Display, sync::Arc};
+
+/// One row of a [`candidate_list`]: a key and what it decrypts the ciphertext to.
+#[derive(Clone, Data, Serialize, Deserialize)]
+pub struct Candidate<K> {
+    pub key: K,
+    pub plaintext: String,
+    pub score: f64,
+}
+
+/// The selected key, along with every candidate to choose from.
+pub type Candidates<K> = (Option<K>, Arc<Vec<Candidate<K>>>);
+
+/// A ranked list of candidate decryptions. Clicking on one selects its key.
+pub fn candidate_list<K: Data + Display + PartialEq>() -> impl Widget<Candidates<K>> {
+    Scroll::new(List::new(|| {
+        Label::dynamic(|(_, candidate): &(Option<K>, Candidate<K>), _| {
+            format!(
+                "{}  ({:.1})  {}",
+                candidate.key, candidate.score, candidate.plaintext
+            )
+        })
+        .padding(2.0)
+        .expand_width()
+        .background(Painter::new(
+            |ctx, (selected, candidate): &(Option<K>, Candidate<K>), env| {
+                let colour = if selected.as_ref() == Some(&candidate.key) {
+                    env.get(PRIMARY_DARK)
+                } else if ctx.is_hot() {
+                    env.get(HOT_COLOUR)
+                } else {
+                    return;
+                };
+                let background = ctx
+                    .size()
+                    .to_rect()
+                    .to_rounded_rect(env.get(BUTTON_BORDER_RADIUS));
+                ctx.fill(background, &colour);
+            },
+        ))
+        .on_click(|_, (selected, candidate), _| *selected = Some(candidate.key.clone()))
+    }))
+    .vertical()
+}
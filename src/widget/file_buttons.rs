@@ -0,0 +1,93 @@
+use ciphertools::hex::{self, HexOptions};
+use druid::{
+    commands,
+    widget::{Button, Controller, Flex},
+    Data, Env, Event, EventCtx, Lens, LensExt, Selector, Widget, WidgetExt, WidgetId,
+};
+use std::{fs, sync::Arc};
+
+/// Asks the app delegate to show an open dialog, and send the chosen file to
+/// the widget with this id as an `OPEN_FILE` command.
+pub const OPEN_FOR: Selector<WidgetId> = Selector::new("ciphertools.open-for");
+/// Like `OPEN_FOR`, but with a save dialog and `SAVE_FILE`.
+pub const SAVE_FOR: Selector<WidgetId> = Selector::new("ciphertools.save-for");
+
+type Open<T> = Box<dyn Fn(&mut T, Vec<u8>)>;
+type Save<T> = Box<dyn Fn(&T) -> Vec<u8>>;
+
+struct FileIo<T, L> {
+    status: L,
+    open: Open<T>,
+    save: Save<T>,
+}
+
+impl<T: Data, L: Lens<T, String>, W: Widget<T>> Controller<T, W> for FileIo<T, L> {
+    fn event(&mut self, child: &mut W, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        let cmd = match event {
+            Event::Command(cmd) => cmd,
+            _ => return child.event(ctx, event, data, env),
+        };
+        if let Some(info) = cmd.get(commands::OPEN_FILE) {
+            match fs::read(info.path()) {
+                Ok(bytes) => (self.open)(data, bytes),
+                Err(e) => self.status.put(data, format!("Couldn't open {}: {}", info.path().display(), e)),
+            }
+            ctx.set_handled();
+        } else if let Some(Some(info)) = cmd.get(commands::SAVE_FILE) {
+            if let Err(e) = fs::write(info.path(), (self.save)(data)) {
+                self.status.put(data, format!("Couldn't save {}: {}", info.path().display(), e));
+            }
+            ctx.set_handled();
+        } else {
+            child.event(ctx, event, data, env);
+        }
+    }
+}
+
+/// Open and Save buttons for a field. `open` is given the bytes of the chosen
+/// file, and `save` gives the bytes to write. Errors go in `status`.
+pub fn file_buttons<T: Data>(
+    status: impl Lens<T, String> + 'static,
+    open: impl Fn(&mut T, Vec<u8>) + 'static,
+    save: impl Fn(&T) -> Vec<u8> + 'static,
+) -> impl Widget<T> {
+    let id = WidgetId::next();
+    Flex::row()
+        .with_child(Button::new("Open").on_click(move |ctx, _, _| ctx.submit_command(OPEN_FOR.with(id), None)))
+        .with_child(Button::new("Save").on_click(move |ctx, _, _| ctx.submit_command(SAVE_FOR.with(id), None)))
+        .controller(FileIo {
+            status,
+            open: Box::new(open),
+            save: Box::new(save),
+        })
+        .with_id(id)
+}
+
+/// Open and Save buttons for a text field. Files that aren't UTF-8 are read
+/// as best they can be.
+pub fn text_file_buttons<T: Data>(
+    text: impl Lens<T, String> + Clone + 'static,
+    status: impl Lens<T, String> + 'static,
+) -> impl Widget<T> {
+    let save = text.clone();
+    file_buttons(
+        status,
+        move |data, bytes| text.put(data, String::from_utf8_lossy(&bytes).into_owned()),
+        move |data| save.with(data, |text| text.as_bytes().to_vec()),
+    )
+}
+
+/// Open and Save buttons for the bytes behind a plaintext field, so that
+/// files of any kind can be encoded and decoded. Opening a file sets `hex`,
+/// which the tool decodes into `bytes`.
+pub fn byte_file_buttons<T: Data>(
+    bytes: impl Lens<T, Arc<Vec<u8>>> + 'static,
+    hex_field: impl Lens<T, String> + 'static,
+    status: impl Lens<T, String> + 'static,
+) -> impl Widget<T> {
+    file_buttons(
+        status,
+        move |data, file| hex_field.put(data, hex::encode_bytes(&file, &HexOptions::SPACED)),
+        move |data| bytes.with(data, |bytes| bytes.to_vec()),
+    )
+}
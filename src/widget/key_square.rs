@@ -0,0 +1,117 @@
+use crate::theme::{BLUE, GREEN, PURPLE};
+use druid::{
+    piet::{FontBuilder, Text, TextLayout, TextLayoutBuilder},
+    theme::{FONT_NAME, FOREGROUND_LIGHT},
+    BoxConstraints, Data, Env, Event, EventCtx, LayoutCtx, Lens, LifeCycle, LifeCycleCtx,
+    PaintCtx, Point, Rect, RenderContext, Size, UpdateCtx, Widget,
+};
+use serde::{Deserialize, Serialize};
+
+const CELL_SIZE: f64 = 32.0;
+const LETTER_SIZE: f64 = 18.0;
+
+/// A pair of letters going through the square: where they are, and where the
+/// letters they turn into are.
+#[derive(Clone, Copy, Data, PartialEq, Serialize, Deserialize)]
+pub struct Highlight {
+    pub from: (usize, usize),
+    pub to: (usize, usize),
+}
+
+#[derive(Clone, Data, Lens, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KeySquareData {
+    /// The 25 letters of the square, row by row.
+    pub letters: String,
+    pub highlight: Option<Highlight>,
+}
+
+/// A Playfair key square. The highlighted input letters are filled in blue and
+/// the output letters outlined in green, with the row, column or rectangle
+/// that connects them outlined in purple.
+pub struct KeySquare;
+
+fn cell(index: usize) -> Rect {
+    let (row, column) = ((index / 5) as f64, (index % 5) as f64);
+    Rect::new(
+        column * CELL_SIZE,
+        row * CELL_SIZE,
+        (column + 1.0) * CELL_SIZE,
+        (row + 1.0) * CELL_SIZE,
+    )
+}
+
+impl Widget<KeySquareData> for KeySquare {
+    fn event(&mut self, _ctx: &mut EventCtx, _event: &Event, _data: &mut KeySquareData, _env: &Env) {}
+
+    fn lifecycle(
+        &mut self,
+        _ctx: &mut LifeCycleCtx,
+        _event: &LifeCycle,
+        _data: &KeySquareData,
+        _env: &Env,
+    ) {
+    }
+
+    fn update(
+        &mut self,
+        ctx: &mut UpdateCtx,
+        old_data: &KeySquareData,
+        data: &KeySquareData,
+        _env: &Env,
+    ) {
+        if !old_data.same(data) {
+            ctx.request_paint();
+        }
+    }
+
+    fn layout(
+        &mut self,
+        _ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        _data: &KeySquareData,
+        _env: &Env,
+    ) -> Size {
+        bc.constrain(Size::new(CELL_SIZE * 5.0, CELL_SIZE * 5.0))
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &KeySquareData, env: &Env) {
+        if let Some(highlight) = data.highlight {
+            let (a, b) = highlight.from;
+            ctx.fill(cell(a).inset(-1.0), &env.get(BLUE));
+            ctx.fill(cell(b).inset(-1.0), &env.get(BLUE));
+            for &x in &[highlight.to.0, highlight.to.1] {
+                ctx.stroke(cell(x).inset(-3.0), &env.get(GREEN), 2.0);
+            }
+
+            let (r1, c1, r2, c2) = (a / 5, a % 5, b / 5, b % 5);
+            let rule = if r1 == r2 {
+                cell(r1 * 5).union(cell(r1 * 5 + 4))
+            } else if c1 == c2 {
+                cell(c1).union(cell(20 + c1))
+            } else {
+                cell(a).union(cell(b))
+            };
+            ctx.stroke(rule, &env.get(PURPLE), 2.0);
+        }
+
+        let font = ctx
+            .text()
+            .new_font_by_name(env.get(FONT_NAME), LETTER_SIZE)
+            .build()
+            .unwrap();
+        for (i, letter) in data.letters.chars().take(25).enumerate() {
+            let layout = ctx
+                .text()
+                .new_text_layout(&font, &letter.to_string(), f64::INFINITY)
+                .build()
+                .unwrap();
+            let rect = cell(i);
+            let origin = Point::new(
+                rect.x0 + (CELL_SIZE - layout.width()) / 2.0,
+                rect.y1 - (CELL_SIZE - LETTER_SIZE) / 2.0 - 2.0,
+            );
+            ctx.draw_text(&layout, origin, &env.get(FOREGROUND_LIGHT));
+        }
+    }
+}
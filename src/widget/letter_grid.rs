@@ -0,0 +1,164 @@
+use crate::theme::{PALE_BLUE, PALE_PURPLE, PALE_RED, RED};
+use ciphertools::text_options::{Column, Mark};
+use druid::{
+    piet::{FontBuilder, Text, TextLayout, TextLayoutBuilder},
+    theme::{BACKGROUND_DARK, FONT_NAME, FOREGROUND_DARK, FOREGROUND_LIGHT},
+    BoxConstraints, Data, Env, Event, EventCtx, LayoutCtx, Lens, LifeCycle, LifeCycleCtx,
+    PaintCtx, Point, Rect, RenderContext, Size, UpdateCtx, Widget,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+const CELL_WIDTH: f64 = 24.0;
+const CELL_HEIGHT: f64 = 32.0;
+const LETTER_SIZE: f64 = 16.0;
+const NOTE_SIZE: f64 = 9.0;
+
+/// How far through a comparison each row of a [`comparison_grid`] goes.
+const COMPARISON_WIDTH: usize = 30;
+
+/// A colour to pick a cell out with, over the top of being a header.
+#[derive(Clone, Copy, Data, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub enum Highlight {
+    #[default]
+    None,
+    Red,
+    PaleRed,
+    PalePurple,
+}
+
+/// One square of a [`LetterGrid`]. Cells with no text are left blank.
+#[derive(Clone, Data, Lens, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Cell {
+    pub text: String,
+    /// A small label under the text, like where the letter ends up.
+    pub note: String,
+    /// Header cells, like the letters of a keyword, are drawn highlighted.
+    pub header: bool,
+    pub highlight: Highlight,
+}
+
+impl Cell {
+    /// A cell for a character of the text being transposed. Spaces are shown
+    /// as dots so that they can be followed too.
+    pub fn character(c: char, note: String) -> Self {
+        Cell {
+            text: if c == ' ' { String::from("·") } else { c.to_string() },
+            note,
+            header: false,
+            highlight: Highlight::None,
+        }
+    }
+}
+
+#[derive(Clone, Data, Lens, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Grid {
+    pub columns: usize,
+    /// The cells, row by row.
+    pub cells: Arc<Vec<Cell>>,
+}
+
+/// Lays out a plaintext over its ciphertext, with the key letters under the
+/// ciphertext. Characters that don't line up are highlighted: pale red where
+/// only one is a letter or the other text ran out, pale purple where the case
+/// is different, and red where the key letter doesn't fit the others.
+pub fn comparison_grid(columns: &[Column]) -> Grid {
+    let width = COMPARISON_WIDTH.min(columns.len()).max(1);
+    let mut cells = Vec::new();
+    for (i, chunk) in columns.chunks(width).enumerate() {
+        if i > 0 {
+            cells.extend((0..width).map(|_| Cell::default()));
+        }
+        let highlight = |column: &Column| match column.mark {
+            Mark::Fine => Highlight::None,
+            Mark::NonAlphabetic | Mark::Unmatched => Highlight::PaleRed,
+            Mark::Case => Highlight::PalePurple,
+            Mark::Inconsistent => Highlight::Red,
+        };
+        let cell = |c: Option<char>, note: Option<char>, column: &Column| Cell {
+            highlight: highlight(column),
+            ..c.map(|c| Cell::character(c, note.map(String::from).unwrap_or_default()))
+                .unwrap_or_default()
+        };
+        let blank = width - chunk.len();
+        cells.extend(chunk.iter().map(|column| cell(column.plain, None, column)));
+        cells.extend((0..blank).map(|_| Cell::default()));
+        cells.extend(chunk.iter().map(|column| cell(column.cipher, column.key, column)));
+        cells.extend((0..blank).map(|_| Cell::default()));
+    }
+    Grid {
+        columns: width,
+        cells: Arc::new(cells),
+    }
+}
+
+/// A grid of characters, for showing how a transposition moves them around.
+pub struct LetterGrid;
+
+impl Widget<Grid> for LetterGrid {
+    fn event(&mut self, _ctx: &mut EventCtx, _event: &Event, _data: &mut Grid, _env: &Env) {}
+
+    fn lifecycle(&mut self, _ctx: &mut LifeCycleCtx, _event: &LifeCycle, _data: &Grid, _env: &Env) {}
+
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &Grid, data: &Grid, _env: &Env) {
+        if !old_data.same(data) {
+            ctx.request_layout();
+            ctx.request_paint();
+        }
+    }
+
+    fn layout(&mut self, _ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &Grid, _env: &Env) -> Size {
+        let columns = data.columns.max(1);
+        let rows = data.cells.len().div_ceil(columns);
+        bc.constrain(Size::new(columns as f64 * CELL_WIDTH, rows as f64 * CELL_HEIGHT))
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &Grid, env: &Env) {
+        let columns = data.columns.max(1);
+        let letter_font = ctx
+            .text()
+            .new_font_by_name(env.get(FONT_NAME), LETTER_SIZE)
+            .build()
+            .unwrap();
+        let note_font = ctx
+            .text()
+            .new_font_by_name(env.get(FONT_NAME), NOTE_SIZE)
+            .build()
+            .unwrap();
+
+        for (i, cell) in data.cells.iter().enumerate() {
+            if cell.text.is_empty() {
+                continue;
+            }
+            let left = (i % columns) as f64 * CELL_WIDTH;
+            let top = (i / columns) as f64 * CELL_HEIGHT;
+            let rect = Rect::new(left, top, left + CELL_WIDTH, top + CELL_HEIGHT).inset(-1.0);
+            let (background, foreground) = match (cell.highlight, cell.header) {
+                (Highlight::Red, _) => (env.get(RED), env.get(BACKGROUND_DARK)),
+                (Highlight::PaleRed, _) => (env.get(PALE_RED), env.get(BACKGROUND_DARK)),
+                (Highlight::PalePurple, _) => (env.get(PALE_PURPLE), env.get(BACKGROUND_DARK)),
+                (Highlight::None, true) => (env.get(PALE_BLUE), env.get(BACKGROUND_DARK)),
+                (Highlight::None, false) => (env.get(BACKGROUND_DARK), env.get(FOREGROUND_LIGHT)),
+            };
+            ctx.fill(rect, &background);
+
+            let layout = ctx
+                .text()
+                .new_text_layout(&letter_font, &cell.text, f64::INFINITY)
+                .build()
+                .unwrap();
+            let origin = Point::new(left + (CELL_WIDTH - layout.width()) / 2.0, top + LETTER_SIZE + 2.0);
+            ctx.draw_text(&layout, origin, &foreground);
+
+            let layout = ctx
+                .text()
+                .new_text_layout(&note_font, &cell.note, f64::INFINITY)
+                .build()
+                .unwrap();
+            let origin = Point::new(left + (CELL_WIDTH - layout.width()) / 2.0, top + CELL_HEIGHT - 3.0);
+            ctx.draw_text(&layout, origin, &env.get(FOREGROUND_DARK));
+        }
+    }
+}
@@ -1,11 +1,21 @@
 use druid::{
     theme::{FOREGROUND_DARK, FOREGROUND_LIGHT},
     widget::{CrossAxisAlignment, Flex, Label, MainAxisAlignment},
-    Data, Widget, WidgetExt
+    Data, Lens, Widget, WidgetExt
 };
+use std::fmt;
 
+pub mod alphabet_editor;
+pub mod bar_chart;
+pub mod candidate_list;
+pub mod file_buttons;
+pub mod key_square;
+pub mod letter_grid;
 pub mod mode_selector;
+pub mod recompute;
 pub mod tab_selector;
+pub mod text_area;
+pub mod text_options;
 
 pub fn soft_label<T: Data>(text: &str) -> impl Widget<T> {
     Label::new(text.to_string())
@@ -15,7 +25,13 @@ pub fn soft_label<T: Data>(text: &str) -> impl Widget<T> {
         .padding(2.0)
 }
 
-pub fn titled_panel<D, W>(title: &'static str, subtitle: &'static str, inner: W) -> impl Widget<D>
+/// A panel with a title, and a status line under it for errors and the like.
+pub fn titled_panel<D, W>(
+    title: &'static str,
+    subtitle: &'static str,
+    status: impl Lens<D, String> + 'static,
+    inner: W,
+) -> impl Widget<D>
 where
     D: Data,
     W: Widget<D> + 'static,
@@ -36,6 +52,17 @@ where
                 ),
         )
         .with_flex_child(inner, 1.0)
+        .with_child(Label::dynamic(|status: &String, _| status.clone()).lens(status))
+}
+
+/// An error as it's shown in a panel's status line, starting with a capital.
+pub fn error_status(error: &impl fmt::Display) -> String {
+    let error = error.to_string();
+    let mut chars = error.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => error,
+    }
 }
 
 pub fn input_label(name: &'static str) -> impl Widget<bool> {
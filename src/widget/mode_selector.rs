@@ -10,6 +10,7 @@ pub enum ModeColour {
     Red,
     Green,
     Blue,
+    Purple,
 }
 
 impl ModeColour {
@@ -23,14 +24,16 @@ impl ModeColour {
             (Green, false) => PALE_GREEN,
             (Blue, true) => BLUE,
             (Blue, false) => PALE_BLUE,
+            (Purple, true) => PURPLE,
+            (Purple, false) => PALE_PURPLE,
         }
     }
 }
 
 pub fn mode_selector(modes: &[(&'static str, ModeColour)]) -> impl Widget<usize> {
     let mut flex = Flex::row().main_axis_alignment(MainAxisAlignment::Center).cross_axis_alignment(CrossAxisAlignment::Center);
-    for (idx, (name, colour)) in modes.into_iter().enumerate() {
-        flex.add_flex_child(Mode::new(name.clone(), *colour, idx, idx == 0, idx == modes.len() - 1), 1.0);
+    for (idx, (name, colour)) in modes.iter().enumerate() {
+        flex.add_flex_child(Mode::new(*name, *colour, idx, idx == 0, idx == modes.len() - 1), 1.0);
     }
     flex.expand_width()
 }
@@ -65,12 +68,9 @@ impl Mode {
 
 impl Widget<usize> for Mode {
     fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut usize, _env: &Env) {
-        match event {
-            Event::MouseDown(_) => {
-                *data = self.id;
-                ctx.request_paint();
-            }
-            _ => (),
+        if let Event::MouseDown(_) = event {
+            *data = self.id;
+            ctx.request_paint();
         }
     }
 
@@ -0,0 +1,223 @@
+use druid::{
+    kurbo::{Circle, Vec2},
+    theme::FOREGROUND_DARK,
+    BoxConstraints, Data, Env, Event, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx, PaintCtx, Point, Rect,
+    Lens, LensExt, RenderContext, Size, TimerToken, UpdateCtx, Widget, WidgetPod,
+};
+use std::{
+    f64::consts::PI,
+    sync::{
+        mpsc::{self, Receiver, Sender},
+        Arc, Condvar, Mutex,
+    },
+    thread,
+    time::Duration,
+};
+
+/// Inputs at least this many bytes long are worked on in the background, so
+/// that the window doesn't freeze while typing into them.
+pub const LARGE_INPUT: usize = 20_000;
+
+/// How often to check whether the background work is done.
+const POLL: Duration = Duration::from_millis(40);
+const SPINNER_RADIUS: f64 = 8.0;
+const SPINNER_DOTS: usize = 8;
+
+type CopyStatus<S> = Box<dyn Fn(&mut S, &S)>;
+
+/// Wraps a tool, calling `recompute(data, old)` to bring its outputs up to
+/// date whenever something in it changes, where `old` is how it was before.
+/// Changes to the status alone, like an error from the file buttons, don't
+/// count.
+///
+/// Once `input_len` reaches [`LARGE_INPUT`], this happens on a worker thread
+/// with a spinner in the top right corner, and the result is only kept if
+/// nothing has changed since it started. The worker only ever takes the newest
+/// change, so typing quickly doesn't pile up work.
+pub struct Recompute<S> {
+    child: WidgetPod<S, Box<dyn Widget<S>>>,
+    recompute: fn(&mut S, &S),
+    input_len: fn(&S) -> usize,
+    /// Copies the status of the second state into the first.
+    copy_status: CopyStatus<S>,
+    /// Counts the changes, so that only the latest result is kept.
+    generation: u64,
+    /// The state that the work in the background started from.
+    running: Option<S>,
+    results: Receiver<(u64, S)>,
+    sender: Sender<(u64, S)>,
+    queue: Queue<S>,
+    /// Whether the worker thread has been started.
+    working: bool,
+    timer: TimerToken,
+    /// How far round the spinner is, from 0 to 1.
+    turn: f64,
+}
+
+/// The next change for the worker to bring up to date, as the generation, the
+/// state and how it was before.
+struct Jobs<S> {
+    next: Option<(u64, S, S)>,
+    closed: bool,
+}
+
+type Queue<S> = Arc<(Mutex<Jobs<S>>, Condvar)>;
+
+/// Runs the newest job in `queue` until the tool is closed.
+fn work<S>(queue: Queue<S>, recompute: fn(&mut S, &S), sender: Sender<(u64, S)>) {
+    let (jobs, ready) = &*queue;
+    loop {
+        let (generation, mut result, old) = {
+            let mut jobs = jobs.lock().unwrap();
+            loop {
+                if jobs.closed {
+                    return;
+                }
+                if let Some(job) = jobs.next.take() {
+                    break job;
+                }
+                jobs = ready.wait(jobs).unwrap();
+            }
+        };
+        recompute(&mut result, &old);
+        if sender.send((generation, result)).is_err() {
+            return;
+        }
+    }
+}
+
+impl<S: Data + Send> Recompute<S> {
+    pub fn new(
+        child: impl Widget<S> + 'static,
+        recompute: fn(&mut S, &S),
+        input_len: fn(&S) -> usize,
+        status: impl Lens<S, String> + 'static,
+    ) -> Self {
+        let (sender, results) = mpsc::channel();
+        Recompute {
+            child: WidgetPod::new(child).boxed(),
+            recompute,
+            input_len,
+            copy_status: Box::new(move |data, from| status.put(data, status.get(from))),
+            generation: 0,
+            running: None,
+            results,
+            sender,
+            queue: Arc::new((Mutex::new(Jobs { next: None, closed: false }), Condvar::new())),
+            working: false,
+            timer: TimerToken::INVALID,
+            turn: 0.0,
+        }
+    }
+
+    fn only_status_changed(&self, old: &S, data: &S) -> bool {
+        let mut without = data.clone();
+        (self.copy_status)(&mut without, old);
+        without.same(old)
+    }
+
+    fn start(&mut self, ctx: &mut EventCtx, data: &mut S, old: S) {
+        self.generation += 1;
+        if (self.input_len)(data) < LARGE_INPUT {
+            (self.recompute)(data, &old);
+            self.running = None;
+            return;
+        }
+
+        let (jobs, ready) = &*self.queue;
+        // Any job still waiting is out of date, so this replaces it.
+        jobs.lock().unwrap().next = Some((self.generation, data.clone(), old));
+        ready.notify_one();
+        if !self.working {
+            let (queue, recompute, sender) = (self.queue.clone(), self.recompute, self.sender.clone());
+            thread::spawn(move || work(queue, recompute, sender));
+            self.working = true;
+        }
+        if self.running.is_none() {
+            self.timer = ctx.request_timer(POLL);
+        }
+        self.running = Some(data.clone());
+        ctx.request_paint();
+    }
+
+    /// Takes in whatever the background work has finished.
+    fn poll(&mut self, ctx: &mut EventCtx, data: &mut S) {
+        while let Ok((generation, result)) = self.results.try_recv() {
+            if generation != self.generation {
+                continue;
+            }
+            match self.running.take() {
+                Some(start) if start.same(data) => *data = result,
+                Some(start) if self.only_status_changed(&start, data) => {
+                    let status = data.clone();
+                    *data = result;
+                    (self.copy_status)(data, &status);
+                },
+                // Something else changed it, like opening a session, so the
+                // work has to start again.
+                Some(start) => self.start(ctx, data, start),
+                None => {},
+            }
+        }
+    }
+}
+
+impl<S> Drop for Recompute<S> {
+    fn drop(&mut self) {
+        let (jobs, ready) = &*self.queue;
+        jobs.lock().unwrap().closed = true;
+        ready.notify_one();
+    }
+}
+
+impl<S: Data + Send> Widget<S> for Recompute<S> {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut S, env: &Env) {
+        if let Event::Timer(token) = event {
+            if *token == self.timer {
+                self.poll(ctx, data);
+                if self.running.is_some() {
+                    self.turn = (self.turn + 1.0 / SPINNER_DOTS as f64) % 1.0;
+                    self.timer = ctx.request_timer(POLL);
+                }
+                ctx.request_paint();
+                return;
+            }
+        }
+
+        let old = data.clone();
+        self.child.event(ctx, event, data, env);
+        if !old.same(data) && !self.only_status_changed(&old, data) {
+            self.start(ctx, data, old);
+        }
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &S, env: &Env) {
+        self.child.lifecycle(ctx, event, data, env);
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &S, data: &S, env: &Env) {
+        self.child.update(ctx, data, env);
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &S, env: &Env) -> Size {
+        let size = self.child.layout(ctx, bc, data, env);
+        self.child.set_layout_rect(ctx, data, env, Rect::from_origin_size(Point::ORIGIN, size));
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &S, env: &Env) {
+        self.child.paint(ctx, data, env);
+        if self.running.is_none() {
+            return;
+        }
+        let centre = Point::new(ctx.size().width - SPINNER_RADIUS * 2.0, SPINNER_RADIUS * 2.0);
+        let colour = env.get(FOREGROUND_DARK);
+        for i in 0..SPINNER_DOTS {
+            let step = i as f64 / SPINNER_DOTS as f64;
+            let position = centre + Vec2::from_angle(2.0 * PI * step) * SPINNER_RADIUS;
+            // The dots fade out behind the one in front.
+            let fade = (step - self.turn + 1.0) % 1.0;
+            ctx.fill(Circle::new(position, 2.0), &colour.clone().with_alpha(0.2 + 0.8 * fade));
+        }
+    }
+}
@@ -26,7 +26,7 @@ pub fn tab_selector<T: Data>(
                 col.add_child(Tab::new(name, tab_id));
                 tab_id += 1;
             }
-            Entry::Category(name) => col.add_child(soft_label(&name)),
+            Entry::Category(name) => col.add_child(soft_label(name)),
         }
     }
     let col = col.fix_width(128.0);
@@ -57,12 +57,9 @@ impl Tab {
 
 impl Widget<usize> for Tab {
     fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut usize, _env: &Env) {
-        match event {
-            Event::MouseDown(_) => {
-                *data = self.id;
-                ctx.request_paint();
-            }
-            _ => (),
+        if let Event::MouseDown(_) = event {
+            *data = self.id;
+            ctx.request_paint();
         }
     }
 
@@ -0,0 +1,429 @@
+use druid::{
+    kurbo::{Line, RoundedRect},
+    piet::{FontBuilder, PietFont, PietText, Text, TextLayout, TextLayoutBuilder},
+    text::{
+        movement, offset_for_delete_backwards, BasicTextInput, EditAction, EditableText, Movement, Selection,
+        TextInput,
+    },
+    theme, Application, BoxConstraints, Cursor, Env, Event, EventCtx, HotKey, KeyCode, LayoutCtx, LifeCycle,
+    LifeCycleCtx, PaintCtx, Point, Rect, RenderContext, Selector, Size, SysMods, TimerToken, UpdateCtx, Widget,
+};
+use std::{ops::Range, time::Duration};
+
+const PADDING: f64 = 4.0;
+const SCROLLBAR_WIDTH: f64 = 6.0;
+const BLINK: Duration = Duration::from_millis(500);
+const RESET_BLINK: Selector = Selector::new("ciphertools.text-area-reset-blink");
+
+#[cfg(target_os = "windows")]
+const MONOSPACE: &str = "Consolas";
+#[cfg(target_os = "macos")]
+const MONOSPACE: &str = "Menlo";
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+const MONOSPACE: &str = "monospace";
+
+/// A text box for long text, which wraps it onto as many lines as it needs
+/// and scrolls through them.
+///
+/// It uses a monospace font, so only the lines that are showing ever need to
+/// be laid out, which keeps it quick with hundreds of kilobytes in it.
+pub struct TextArea {
+    /// How many lines tall it is.
+    lines: usize,
+    selection: Selection,
+    /// Where each line on screen starts and ends in the text, leaving out
+    /// line breaks.
+    rows: Vec<Range<usize>>,
+    /// How many characters fit on a line.
+    columns: usize,
+    char_width: f64,
+    line_height: f64,
+    /// How far down the text has been scrolled.
+    scroll: f64,
+    cursor_timer: TimerToken,
+    cursor_on: bool,
+}
+
+impl TextArea {
+    pub fn new(lines: usize) -> Self {
+        TextArea {
+            lines,
+            selection: Selection::caret(0),
+            rows: Vec::new(),
+            columns: 1,
+            char_width: 8.0,
+            line_height: 16.0,
+            scroll: 0.0,
+            cursor_timer: TimerToken::INVALID,
+            cursor_on: false,
+        }
+    }
+
+    fn font(text: &mut PietText, env: &Env) -> PietFont {
+        text.new_font_by_name(MONOSPACE, env.get(theme::TEXT_SIZE_NORMAL))
+            .build()
+            .unwrap()
+    }
+
+    /// Splits `text` into lines at line breaks, and wherever a line gets too
+    /// long to fit.
+    fn wrap(&mut self, text: &str) {
+        self.rows.clear();
+        let mut start = 0;
+        let mut count = 0;
+        for (i, c) in text.char_indices() {
+            if c == '\n' {
+                self.rows.push(start..i);
+                start = i + 1;
+                count = 0;
+                continue;
+            }
+            if count == self.columns {
+                self.rows.push(start..i);
+                start = i;
+                count = 0;
+            }
+            count += 1;
+        }
+        self.rows.push(start..text.len());
+    }
+
+    /// The line and column that `offset` is at.
+    fn position(&self, text: &str, offset: usize) -> (usize, usize) {
+        let row = self.rows.partition_point(|row| row.start <= offset).max(1) - 1;
+        let start = self.rows[row].start.min(offset);
+        (row, text[start..offset].chars().count())
+    }
+
+    /// The offset of the character at `column` on `row`, or the end of the
+    /// row if it's shorter than that.
+    fn offset(&self, text: &str, row: usize, column: usize) -> usize {
+        let row = &self.rows[row.min(self.rows.len() - 1)];
+        text[row.clone()]
+            .char_indices()
+            .nth(column)
+            .map_or(row.end, |(i, _)| row.start + i)
+    }
+
+    fn offset_for_point(&self, text: &str, point: Point) -> usize {
+        let row = ((point.y - PADDING + self.scroll) / self.line_height).floor().max(0.0) as usize;
+        let column = ((point.x - PADDING) / self.char_width).round().max(0.0) as usize;
+        self.offset(text, row, column)
+    }
+
+    fn visible_rows(&self) -> usize {
+        self.lines.max(1)
+    }
+
+    fn max_scroll(&self) -> f64 {
+        (self.rows.len().saturating_sub(self.visible_rows()) as f64 * self.line_height).max(0.0)
+    }
+
+    fn scroll_by(&mut self, delta: f64) {
+        self.scroll = (self.scroll + delta).max(0.0).min(self.max_scroll());
+    }
+
+    /// Scrolls so that the cursor is showing.
+    fn scroll_to_cursor(&mut self, text: &str) {
+        let (row, _) = self.position(text, self.selection.end);
+        let top = row as f64 * self.line_height;
+        let bottom = top + self.line_height;
+        let height = self.visible_rows() as f64 * self.line_height;
+        if top < self.scroll {
+            self.scroll = top;
+        } else if bottom > self.scroll + height {
+            self.scroll = bottom - height;
+        }
+    }
+
+    /// Moves the cursor `rows` lines up or down, staying in the same column.
+    fn move_rows(&mut self, text: &str, rows: isize, modify: bool) {
+        let (row, column) = self.position(text, self.selection.end);
+        let row = (row as isize + rows).max(0).min(self.rows.len() as isize - 1) as usize;
+        self.move_to(self.offset(text, row, column), modify);
+    }
+
+    fn move_to(&mut self, offset: usize, modify: bool) {
+        let start = if modify { self.selection.start } else { offset };
+        self.selection = Selection::new(start, offset);
+    }
+
+    fn insert(&mut self, text: &mut String, new: &str) {
+        let selection = self.selection.constrain_to(text);
+        text.edit(selection.range(), new);
+        self.selection = Selection::caret(selection.min() + new.len());
+    }
+
+    fn delete_backward(&mut self, text: &mut String) {
+        if self.selection.is_caret() {
+            let cursor = self.selection.end;
+            let new_cursor = offset_for_delete_backwards(&self.selection, text);
+            text.edit(new_cursor..cursor, "");
+            self.selection = Selection::caret(new_cursor);
+        } else {
+            text.edit(self.selection.range(), "");
+            self.selection = Selection::caret(self.selection.min());
+        }
+    }
+
+    fn delete_forward(&mut self, text: &mut String) {
+        if self.selection.is_caret() {
+            if text.next_grapheme_offset(self.selection.end).is_some() {
+                self.selection = movement(Movement::Right, self.selection, text, false);
+                self.delete_backward(text);
+            }
+        } else {
+            self.delete_backward(text);
+        }
+    }
+
+    fn do_edit_action(&mut self, action: EditAction, text: &mut String) {
+        match action {
+            EditAction::Insert(chars) | EditAction::Paste(chars) => self.insert(text, &chars),
+            EditAction::Backspace => self.delete_backward(text),
+            EditAction::Delete => self.delete_forward(text),
+            EditAction::Move(m) => self.selection = movement(m, self.selection, text, false),
+            EditAction::ModifySelection(m) => self.selection = movement(m, self.selection, text, true),
+            EditAction::SelectAll => self.selection.all(text),
+            EditAction::Click(action) => self.move_to(action.column, action.mods.shift),
+            EditAction::Drag(action) => self.selection.end = action.column,
+        }
+    }
+
+    /// Handles the keys that mean something different over several lines.
+    fn handle_key(&mut self, ctx: &mut EventCtx, key: &druid::KeyEvent, text: &mut String) -> bool {
+        let page = self.visible_rows() as isize;
+        let (row, _) = self.position(text, self.selection.end);
+        match key {
+            k if HotKey::new(None, KeyCode::Tab).matches(k) => ctx.focus_next(),
+            k if HotKey::new(SysMods::Shift, KeyCode::Tab).matches(k) => ctx.focus_prev(),
+            k if HotKey::new(None, KeyCode::Return).matches(k) => self.insert(text, "\n"),
+            k if HotKey::new(None, KeyCode::ArrowUp).matches(k) => self.move_rows(text, -1, false),
+            k if HotKey::new(SysMods::Shift, KeyCode::ArrowUp).matches(k) => self.move_rows(text, -1, true),
+            k if HotKey::new(None, KeyCode::ArrowDown).matches(k) => self.move_rows(text, 1, false),
+            k if HotKey::new(SysMods::Shift, KeyCode::ArrowDown).matches(k) => self.move_rows(text, 1, true),
+            k if HotKey::new(None, KeyCode::PageUp).matches(k) => self.move_rows(text, -page, false),
+            k if HotKey::new(None, KeyCode::PageDown).matches(k) => self.move_rows(text, page, false),
+            k if HotKey::new(None, KeyCode::Home).matches(k) => self.move_to(self.rows[row].start, false),
+            k if HotKey::new(SysMods::Shift, KeyCode::Home).matches(k) => self.move_to(self.rows[row].start, true),
+            k if HotKey::new(None, KeyCode::End).matches(k) => self.move_to(self.rows[row].end, false),
+            k if HotKey::new(SysMods::Shift, KeyCode::End).matches(k) => self.move_to(self.rows[row].end, true),
+            _ => return false,
+        }
+        true
+    }
+
+    fn reset_cursor_blink(&mut self, ctx: &mut EventCtx) {
+        self.cursor_on = true;
+        self.cursor_timer = ctx.request_timer(BLINK);
+    }
+}
+
+impl Widget<String> for TextArea {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut String, _env: &Env) {
+        self.selection = self.selection.constrain_to(data);
+        let mut edit_action = None;
+        let mut moved = false;
+
+        match event {
+            Event::MouseDown(mouse) => {
+                ctx.request_focus();
+                ctx.set_active(true);
+                if !mouse.focus {
+                    let offset = self.offset_for_point(data, mouse.pos);
+                    self.move_to(offset, mouse.mods.shift);
+                    self.reset_cursor_blink(ctx);
+                }
+                ctx.request_paint();
+            },
+            Event::MouseMove(mouse) => {
+                ctx.set_cursor(&Cursor::IBeam);
+                if ctx.is_active() {
+                    // Dragging past the top or bottom scrolls.
+                    if mouse.pos.y < 0.0 {
+                        self.scroll_by(-self.line_height);
+                    } else if mouse.pos.y > ctx.size().height {
+                        self.scroll_by(self.line_height);
+                    }
+                    self.selection.end = self.offset_for_point(data, mouse.pos);
+                    ctx.request_paint();
+                }
+            },
+            Event::MouseUp(_) if ctx.is_active() => {
+                ctx.set_active(false);
+                ctx.request_paint();
+            },
+            Event::Wheel(mouse) => {
+                let old_scroll = self.scroll;
+                self.scroll_by(mouse.wheel_delta.y);
+                if self.scroll != old_scroll {
+                    ctx.set_handled();
+                    ctx.request_paint();
+                }
+            },
+            Event::Timer(id) if *id == self.cursor_timer => {
+                self.cursor_on = !self.cursor_on;
+                self.cursor_timer = ctx.request_timer(BLINK);
+                ctx.request_paint();
+            },
+            Event::Command(cmd)
+                if ctx.is_focused() && (cmd.is(druid::commands::COPY) || cmd.is(druid::commands::CUT)) =>
+            {
+                if let Some(text) = data.slice(self.selection.range()) {
+                    Application::global().clipboard().put_string(text);
+                }
+                if !self.selection.is_caret() && cmd.is(druid::commands::CUT) {
+                    edit_action = Some(EditAction::Delete);
+                }
+                ctx.set_handled();
+            },
+            Event::Command(cmd) if cmd.is(RESET_BLINK) => self.reset_cursor_blink(ctx),
+            Event::Paste(item) => {
+                if let Some(string) = item.get_string() {
+                    edit_action = Some(EditAction::Paste(string));
+                }
+            },
+            Event::KeyDown(key) => {
+                let old_len = data.len();
+                if self.handle_key(ctx, key, data) {
+                    if data.len() != old_len {
+                        self.wrap(data);
+                    }
+                    moved = true;
+                    self.reset_cursor_blink(ctx);
+                } else {
+                    edit_action = BasicTextInput::new().handle_event(key);
+                }
+                ctx.request_paint();
+            },
+            _ => {},
+        }
+
+        if let Some(action) = edit_action {
+            self.do_edit_action(action, data);
+            self.wrap(data);
+            self.reset_cursor_blink(ctx);
+            moved = true;
+        }
+        if moved {
+            self.scroll_to_cursor(data);
+            ctx.request_paint();
+        }
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &String, _env: &Env) {
+        match event {
+            LifeCycle::WidgetAdded => {
+                ctx.register_for_focus();
+                self.wrap(data);
+            },
+            LifeCycle::FocusChanged(true) => ctx.submit_command(RESET_BLINK, ctx.widget_id()),
+            _ => {},
+        }
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &String, data: &String, _env: &Env) {
+        if old_data != data {
+            self.wrap(data);
+            self.scroll = self.scroll.min(self.max_scroll());
+            ctx.request_paint();
+        }
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &String, env: &Env) -> Size {
+        let font = Self::font(&mut ctx.text(), env);
+        let sample = ctx.text().new_text_layout(&font, "0000000000", f64::INFINITY).build().unwrap();
+        self.char_width = sample.width() / 10.0;
+        self.line_height = env.get(theme::TEXT_SIZE_NORMAL) * 1.3;
+
+        let width = if bc.is_width_bounded() {
+            bc.max().width
+        } else {
+            env.get(theme::WIDE_WIDGET_WIDTH)
+        };
+        let height = self.visible_rows() as f64 * self.line_height + PADDING * 2.0;
+        let size = bc.constrain(Size::new(width, height));
+
+        let columns = ((size.width - PADDING * 2.0 - SCROLLBAR_WIDTH) / self.char_width).floor().max(1.0) as usize;
+        if columns != self.columns {
+            self.columns = columns;
+            self.wrap(data);
+            self.scroll = self.scroll.min(self.max_scroll());
+        }
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &String, env: &Env) {
+        self.selection = self.selection.constrain_to(data);
+        let size = ctx.size();
+        let font_size = env.get(theme::TEXT_SIZE_NORMAL);
+        let radius = env.get(theme::TEXTBOX_BORDER_RADIUS);
+        let border = RoundedRect::from_origin_size(Point::ORIGIN, size.to_vec2(), radius);
+        let border_colour = if ctx.is_focused() {
+            env.get(theme::PRIMARY_LIGHT)
+        } else {
+            env.get(theme::BORDER_DARK)
+        };
+        ctx.fill(border, &env.get(theme::BACKGROUND_LIGHT));
+
+        let font = Self::font(&mut ctx.text(), env);
+        let first = (self.scroll / self.line_height).floor() as usize;
+        let last = (first + self.visible_rows() + 1).min(self.rows.len());
+        let (cursor_row, cursor_column) = self.position(data, self.selection.end);
+        let selection = self.selection.range();
+        let text_colour = env.get(theme::LABEL_COLOR);
+        let selection_colour = env.get(theme::SELECTION_COLOR);
+        let cursor_colour = env.get(theme::CURSOR_COLOR);
+        let focused = ctx.is_focused();
+
+        ctx.with_save(|ctx| {
+            ctx.clip(Rect::from_origin_size(Point::ORIGIN, size).inset(-1.0));
+            for (i, row) in self.rows[first..last].iter().enumerate() {
+                let row_index = first + i;
+                let top = PADDING + row_index as f64 * self.line_height - self.scroll;
+
+                if selection.start < row.end.max(row.start + 1) && selection.end > row.start {
+                    let from = selection.start.max(row.start);
+                    let to = selection.end.min(row.end);
+                    let left = data[row.start..from].chars().count() as f64;
+                    let mut width = data[from..to.max(from)].chars().count() as f64;
+                    if selection.end > row.end {
+                        // Show that the line break is selected too.
+                        width += 0.5;
+                    }
+                    let rect = Rect::from_origin_size(
+                        (PADDING + left * self.char_width, top),
+                        (width * self.char_width, self.line_height),
+                    );
+                    ctx.fill(rect, &selection_colour);
+                }
+
+                if !row.is_empty() {
+                    let layout = ctx.text().new_text_layout(&font, &data[row.clone()], f64::INFINITY).build().unwrap();
+                    let baseline = top + (self.line_height + font_size * 0.7) / 2.0;
+                    ctx.draw_text(&layout, (PADDING, baseline), &text_colour);
+                }
+
+                if focused && self.cursor_on && self.selection.is_caret() && row_index == cursor_row {
+                    let x = PADDING + cursor_column as f64 * self.char_width;
+                    ctx.stroke(Line::new((x, top), (x, top + self.line_height)), &cursor_colour, 1.0);
+                }
+            }
+
+            if self.rows.len() > self.visible_rows() {
+                let height = size.height - PADDING * 2.0;
+                let total = self.rows.len() as f64 * self.line_height;
+                let bar_height = (height * height / total).max(SCROLLBAR_WIDTH * 2.0);
+                let bar_top = PADDING + (height - bar_height) * self.scroll / self.max_scroll();
+                let bar = RoundedRect::from_origin_size(
+                    (size.width - SCROLLBAR_WIDTH - 2.0, bar_top),
+                    (SCROLLBAR_WIDTH, bar_height),
+                    SCROLLBAR_WIDTH / 2.0,
+                );
+                ctx.fill(bar, &env.get(theme::SCROLLBAR_COLOR));
+            }
+        });
+
+        ctx.stroke(border, &border_colour, 1.0);
+    }
+}
@@ -0,0 +1,47 @@
+use crate::widget::soft_label;
+use ciphertools::text_options::{Case, TextOptions};
+use druid::{
+    widget::{Checkbox, Flex, Radio},
+    Data, Lens, Widget, WidgetExt,
+};
+use serde::{Deserialize, Serialize};
+
+const CASES: [Case; 3] = [Case::Keep, Case::Upper, Case::Lower];
+
+/// The [`TextOptions`] for a tool, as edited by [`text_options`].
+#[derive(Clone, Data, Lens, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TextOptionsState {
+    key_on_letters: bool,
+    strip: bool,
+    case: usize,
+    groups: bool,
+}
+
+impl TextOptionsState {
+    pub fn options(&self) -> TextOptions {
+        TextOptions {
+            key_on_letters: self.key_on_letters,
+            strip: self.strip,
+            case: CASES[self.case],
+            groups: self.groups,
+        }
+    }
+}
+
+/// Checkboxes and radio buttons for each option. The option to only move the
+/// key on after letters is left out for ciphers without a running key.
+pub fn text_options(has_key_option: bool) -> impl Widget<TextOptionsState> {
+    let mut row = Flex::row().with_child(soft_label("OPTIONS"));
+    if has_key_option {
+        row.add_child(Checkbox::new("Key skips non-letters").lens(TextOptionsState::key_on_letters));
+        row.add_spacer(8.0);
+    }
+    row.with_child(Checkbox::new("Strip non-letters").lens(TextOptionsState::strip))
+        .with_spacer(8.0)
+        .with_child(Checkbox::new("Groups of five").lens(TextOptionsState::groups))
+        .with_spacer(8.0)
+        .with_child(Radio::new("Keep case", 0).lens(TextOptionsState::case))
+        .with_child(Radio::new("UPPER", 1).lens(TextOptionsState::case))
+        .with_child(Radio::new("lower", 2).lens(TextOptionsState::case))
+}
@@ -0,0 +1,43 @@
+use ciphertools::{
+    affine::{self, Affine, AffineKey, VALID_A},
+    Cipher,
+};
+
+const PARAGRAPH: &str = "Call me Ishmael. Some years ago, never mind how long precisely, having little or no money \
+    in my purse, and nothing particular to interest me on shore, I thought I would sail about a little and see \
+    the watery part of the world.";
+
+#[test]
+fn affine_cipher() {
+    let key = AffineKey { a: 5, b: 8 };
+    assert_eq!(Affine.encrypt("AFFINE CIPHER", &key).unwrap(), "IHHWVC SWFRCP");
+    assert_eq!(Affine.decrypt("IHHWVC SWFRCP", &key).unwrap(), "AFFINE CIPHER");
+    assert_eq!(Affine.find_key("AFFINE CIPHER", "IHHWVC SWFRCP").unwrap(), key);
+}
+
+#[test]
+fn invalid_keys() {
+    assert_eq!(affine::mod_inverse(5), Some(21));
+    for a in [0, 2, 13, 26] {
+        assert_eq!(affine::mod_inverse(a), None);
+        assert!(Affine.encrypt("text", &AffineKey { a, b: 1 }).is_err());
+    }
+}
+
+#[test]
+fn round_trip() {
+    for &a in &VALID_A {
+        let key = AffineKey { a, b: 7 };
+        let ciphertext = Affine.encrypt(PARAGRAPH, &key).unwrap();
+        assert_eq!(Affine.decrypt(&ciphertext, &key).unwrap(), PARAGRAPH);
+    }
+}
+
+#[test]
+fn crack_paragraph() {
+    let key = AffineKey { a: 17, b: 20 };
+    let ciphertext = Affine.encrypt(PARAGRAPH, &key).unwrap();
+    let best = &affine::crack(&ciphertext)[0];
+    assert_eq!(best.key, key);
+    assert_eq!(best.plaintext, PARAGRAPH);
+}
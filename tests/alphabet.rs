@@ -0,0 +1,104 @@
+use ciphertools::{
+    alphabet::{self, Alphabet, PRESETS},
+    shift::Shift,
+    text_options::TextOptions,
+    vigenere::Vigenere,
+    Cipher,
+};
+
+#[test]
+fn presets() {
+    for preset in &PRESETS {
+        let alphabet = Alphabet::preset(preset.name).unwrap();
+        assert_eq!(alphabet.letters().iter().collect::<String>(), preset.letters);
+        assert_eq!(Alphabet::preset(&preset.name.to_uppercase()), Some(alphabet.clone()));
+        assert_eq!(Alphabet::parse(preset.name), Ok(alphabet));
+    }
+    assert_eq!(Alphabet::preset("English").unwrap(), Alphabet::english());
+    assert_eq!(Alphabet::preset("german").unwrap().len(), 30);
+    assert_eq!(Alphabet::preset("russian").unwrap().len(), 33);
+    assert_eq!(Alphabet::preset("greek").unwrap().len(), 24);
+    assert_eq!(Alphabet::preset("klingon"), None);
+    // Anything that isn't a preset is taken as the letters.
+    assert_eq!(Alphabet::parse("abc").unwrap().letters(), ['a', 'b', 'c']);
+}
+
+#[test]
+fn errors() {
+    assert_eq!(alphabet::error("abc"), None);
+    assert_eq!(alphabet::error("abca"), Some(String::from("a is in the alphabet twice")));
+    let russian = format!("{}ё", PRESETS[3].letters);
+    assert_eq!(alphabet::error(&russian), Some(String::from("ё is in the alphabet twice")));
+    assert_eq!(alphabet::error(""), Some(String::from("the alphabet is empty")));
+    assert_eq!(alphabet::error(" \n"), Some(String::from("the alphabet is empty")));
+    assert_eq!(Alphabet::new("a b a"), None);
+    assert_eq!(Alphabet::parse("xyzzy"), Err(String::from("z is in the alphabet twice")));
+    // Whitespace is left out, and the cases are different letters.
+    assert_eq!(Alphabet::new("a b\tc").unwrap().letters(), ['a', 'b', 'c']);
+    assert_eq!(alphabet::error("aA"), None);
+}
+
+#[test]
+fn case_mapping() {
+    let german = Alphabet::preset("German").unwrap();
+    assert_eq!(german.find('ß'), Some((29, false)));
+    // Capital ß lowercases to ß, but ß has no one-character uppercase, since
+    // it uppercases to SS. So it comes out lowercase.
+    assert_eq!(german.find('ẞ'), Some((29, true)));
+    assert_eq!(german.letter(29, true), 'ß');
+    assert_eq!(german.shift('Ü', 1), 'ß');
+    assert_eq!(german.shift('Z', 1), 'Ä');
+    assert_eq!(german.shift('ẞ', 1), 'A');
+    assert_eq!(german.index('Ö'), Some(27));
+    assert_eq!(german.shift('é', 1), 'é');
+
+    let russian = Alphabet::preset("Russian").unwrap();
+    assert_eq!(russian.find('Ё'), Some((6, true)));
+    assert_eq!(russian.shift('Я', 1), 'А');
+    assert_eq!(russian.shift('я', 34), 'а');
+
+    // With both cases in the alphabet, they are different letters.
+    let mixed = Alphabet::new("abcABC").unwrap();
+    assert_eq!(mixed.shift('c', 1), 'A');
+    assert_eq!(mixed.find('A'), Some((3, false)));
+}
+
+#[test]
+fn shift_round_trip() {
+    let cipher = Shift {
+        alphabet: Alphabet::preset("Russian").unwrap(),
+        options: TextOptions::default(),
+    };
+    assert_eq!(cipher.encrypt("Ёлка, привет!", &3).unwrap(), "Ионг, тулезх!");
+    assert_eq!(cipher.decrypt("Ионг, тулезх!", &3).unwrap(), "Ёлка, привет!");
+    assert_eq!(cipher.find_key("Ёлка, привет!", "Ионг, тулезх!").unwrap(), 3);
+
+    let cipher = Shift {
+        alphabet: Alphabet::preset("German").unwrap(),
+        options: TextOptions::default(),
+    };
+    assert_eq!(cipher.encrypt("Grüße aus Köln", &3).unwrap(), "Jubch dxv Naoq");
+    assert_eq!(cipher.decrypt("Jubch dxv Naoq", &3).unwrap(), "Grüße aus Köln");
+    assert!(!cipher.is_valid_key(&30));
+}
+
+#[test]
+fn vigenere_round_trip() {
+    let cipher = Vigenere {
+        alphabet: Alphabet::preset("Russian").unwrap(),
+        ..Vigenere::default()
+    };
+    let plaintext = "Съешь же ещё этих мягких булок";
+    let ciphertext = cipher.encrypt(plaintext, &String::from("ключ")).unwrap();
+    assert_eq!(ciphertext, "Ьёгпж еь рчэ ираа кцнцжм мсгщц");
+    assert_eq!(cipher.decrypt(&ciphertext, &String::from("ключ")).unwrap(), plaintext);
+
+    let cipher = Vigenere {
+        alphabet: Alphabet::preset("Greek").unwrap(),
+        ..Vigenere::default()
+    };
+    let ciphertext = cipher.encrypt("Καλημερα κοσμε", &String::from("κλειδι")).unwrap();
+    assert_eq!(cipher.decrypt(&ciphertext, &String::from("κλειδι")).unwrap(), "Καλημερα κοσμε");
+    // Accented letters aren't in the alphabet, so they can't be in the key.
+    assert!(cipher.encrypt("Καλημερα", &String::from("κλειδί")).is_err());
+}
@@ -0,0 +1,50 @@
+use ciphertools::ascii85::{self, Ascii85Options, Ascii85Variant};
+
+const Z85: Ascii85Options = Ascii85Options {
+    variant: Ascii85Variant::Z85,
+    delimiters: false,
+};
+
+#[test]
+fn adobe_examples() {
+    let options = Ascii85Options::default();
+    assert_eq!(ascii85::encode("Man ", &options).unwrap(), "9jqo^");
+    assert_eq!(ascii85::encode("Man is distinguished", &options).unwrap(), "9jqo^BlbD-BleB1DJ+*+F(f,q");
+    assert_eq!(ascii85::decode("9jqo^BlbD-BleB1DJ+*+F(f,q", &options).unwrap(), "Man is distinguished");
+    // Four zero bytes are written as z.
+    assert_eq!(ascii85::encode_bytes(&[0; 8], &options).unwrap(), "zz");
+    assert_eq!(ascii85::decode_bytes("zz", &options).unwrap(), [0; 8]);
+}
+
+#[test]
+fn adobe_delimiters() {
+    let options = Ascii85Options {
+        delimiters: true,
+        ..Default::default()
+    };
+    assert_eq!(ascii85::encode("Man ", &options).unwrap(), "<~9jqo^~>");
+    assert_eq!(ascii85::decode("<~9jqo^~>", &Ascii85Options::default()).unwrap(), "Man ");
+}
+
+#[test]
+fn z85_spec_example() {
+    let bytes = [0x86, 0x4f, 0xd2, 0x6f, 0xb5, 0x59, 0xf7, 0x5b];
+    assert_eq!(ascii85::encode_bytes(&bytes, &Z85).unwrap(), "HelloWorld");
+    assert_eq!(ascii85::decode_bytes("HelloWorld", &Z85).unwrap(), bytes);
+    // Z85 can only encode whole groups of four bytes.
+    assert_eq!(ascii85::encode_bytes(&bytes[..7], &Z85), None);
+}
+
+#[test]
+fn round_trip() {
+    let bytes = (0..=255).collect::<Vec<u8>>();
+    for options in [Ascii85Options::default(), Z85] {
+        let encoded = ascii85::encode_bytes(&bytes, &options).unwrap();
+        assert_eq!(ascii85::decode_bytes(&encoded, &options).unwrap(), bytes);
+    }
+    let options = Ascii85Options::default();
+    for len in 1..4 {
+        let encoded = ascii85::encode_bytes(&bytes[250..250 + len], &options).unwrap();
+        assert_eq!(ascii85::decode_bytes(&encoded, &options).unwrap(), bytes[250..250 + len]);
+    }
+}
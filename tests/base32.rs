@@ -0,0 +1,50 @@
+use ciphertools::base32::{self, Base32Alphabet, Base32Options};
+
+/// The test vectors from section 10 of RFC 4648.
+const RFC_4648: [(&str, &str); 7] = [
+    ("", ""),
+    ("f", "MY======"),
+    ("fo", "MZXQ===="),
+    ("foo", "MZXW6==="),
+    ("foob", "MZXW6YQ="),
+    ("fooba", "MZXW6YTB"),
+    ("foobar", "MZXW6YTBOI======"),
+];
+
+#[test]
+fn rfc_4648_vectors() {
+    let options = Base32Options::default();
+    for &(plaintext, encoded) in &RFC_4648 {
+        assert_eq!(base32::encode(plaintext, &options), encoded);
+        assert_eq!(base32::decode(encoded, &options).unwrap(), plaintext);
+    }
+}
+
+#[test]
+fn unpadded() {
+    let options = Base32Options {
+        no_padding: true,
+        ..Default::default()
+    };
+    for &(plaintext, encoded) in &RFC_4648 {
+        assert_eq!(base32::encode(plaintext, &options), encoded.trim_end_matches('='));
+    }
+}
+
+#[test]
+fn crockford_round_trip() {
+    let options = Base32Options {
+        alphabet: Base32Alphabet::Crockford,
+        ..Default::default()
+    };
+    let bytes = (0..=255).collect::<Vec<u8>>();
+    let encoded = base32::encode_bytes(&bytes, &options);
+    assert!(!encoded.contains(|c| "ILOU=".contains(c)));
+    assert_eq!(base32::decode_bytes(&encoded, &options).unwrap(), bytes);
+    // O, I and L are read as 0, 1 and 1.
+    assert_eq!(base32::decode_bytes("CSQPY", &options), base32::decode_bytes("csqpy", &options));
+    assert_eq!(
+        base32::decode_bytes("OILoil00", &options).unwrap(),
+        base32::decode_bytes("01101100", &options).unwrap()
+    );
+}
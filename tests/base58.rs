@@ -0,0 +1,45 @@
+use ciphertools::{base58, hex};
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    hex::encode_bytes(&base58::sha256(bytes), &Default::default())
+}
+
+#[test]
+fn sha256_nist_vectors() {
+    assert_eq!(sha256_hex(b""), "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855");
+    assert_eq!(sha256_hex(b"abc"), "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+    // Long enough to need a second block.
+    assert_eq!(
+        sha256_hex(b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"),
+        "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1"
+    );
+}
+
+#[test]
+fn plain_base58() {
+    assert_eq!(base58::encode("Hello World!", false), "2NEpo7TZRRrLZSi2U");
+    assert_eq!(base58::encode_bytes(&[0, 0, 0x28, 0x7f, 0xb4, 0xcd], false), "11233QC4");
+    assert_eq!(base58::decode("2NEpo7TZRRrLZSi2U", false).unwrap(), "Hello World!");
+}
+
+#[test]
+fn bitcoin_address() {
+    // Version 0, then the RIPEMD-160 hash of a public key.
+    let payload = hex::decode_bytes("00 010966776006953D5567439E5E39F86A0D273BEE").unwrap();
+    let address = "16UwLL9Risc3QfPqBUvKofHmBQ7wMtjvM";
+    assert_eq!(base58::encode_bytes(&payload, true), address);
+    assert_eq!(base58::decode_bytes(address, true).unwrap(), payload);
+    assert_eq!(
+        base58::decode_bytes(address, false).unwrap()[21..],
+        hex::decode_bytes("D61967F6").unwrap()[..]
+    );
+    assert!(base58::decode_bytes("16UwLL9Risc3QfPqBUvKofHmBQ7wMtjvN", true).is_err());
+}
+
+#[test]
+fn round_trip() {
+    let bytes = (0..=255).collect::<Vec<u8>>();
+    for check in [false, true] {
+        assert_eq!(base58::decode_bytes(&base58::encode_bytes(&bytes, check), check).unwrap(), bytes);
+    }
+}
@@ -0,0 +1,104 @@
+use ciphertools::base64::{self, Base64Options, MIME_LINE_LENGTH};
+
+/// The test vectors from section 10 of RFC 4648.
+const RFC_4648: [(&str, &str); 7] = [
+    ("", ""),
+    ("f", "Zg=="),
+    ("fo", "Zm8="),
+    ("foo", "Zm9v"),
+    ("foob", "Zm9vYg=="),
+    ("fooba", "Zm9vYmE="),
+    ("foobar", "Zm9vYmFy"),
+];
+
+const URL_SAFE: Base64Options = Base64Options {
+    url_safe: true,
+    no_padding: false,
+    wrap: false,
+};
+
+#[test]
+fn rfc_4648_vectors() {
+    let options = Base64Options::default();
+    for &(plaintext, encoded) in &RFC_4648 {
+        assert_eq!(base64::encode(plaintext, &options), encoded);
+        assert_eq!(base64::decode(encoded, &options).unwrap(), plaintext);
+        // The letters and digits are the same in the URL-safe alphabet.
+        assert_eq!(base64::encode(plaintext, &URL_SAFE), encoded);
+    }
+}
+
+#[test]
+fn unpadded() {
+    let options = Base64Options {
+        no_padding: true,
+        ..Default::default()
+    };
+    for &(plaintext, encoded) in &RFC_4648 {
+        let unpadded = encoded.trim_end_matches('=');
+        assert_eq!(base64::encode(plaintext, &options), unpadded);
+        assert_eq!(base64::decode(unpadded, &options).unwrap(), plaintext);
+        assert_eq!(base64::decode(encoded, &Base64Options::default()).unwrap(), plaintext);
+    }
+}
+
+#[test]
+fn url_safe() {
+    let bytes = [0xfb, 0xff, 0xbf, 0xfe];
+    assert_eq!(base64::encode_bytes(&bytes, &Base64Options::default()), "+/+//g==");
+    assert_eq!(base64::encode_bytes(&bytes, &URL_SAFE), "-_-__g==");
+    assert_eq!(base64::decode_bytes("-_-__g==", &URL_SAFE).unwrap(), bytes);
+    assert_eq!(base64::decode_bytes("+/+//g==", &Base64Options::default()).unwrap(), bytes);
+}
+
+#[test]
+fn mime_wrapping() {
+    let options = Base64Options {
+        wrap: true,
+        ..Default::default()
+    };
+    let bytes = (0..=255).collect::<Vec<u8>>();
+    let wrapped = base64::encode_bytes(&bytes, &options);
+    let lines = wrapped.split("\r\n").collect::<Vec<_>>();
+    // 256 bytes make 344 characters: four full lines and a shorter one.
+    assert_eq!(lines.len(), 5);
+    assert!(lines[..4].iter().all(|line| line.len() == MIME_LINE_LENGTH));
+    assert_eq!(lines[4], &base64::encode_bytes(&bytes, &Base64Options::default())[4 * MIME_LINE_LENGTH..]);
+    assert_eq!(base64::decode_bytes(&wrapped, &options).unwrap(), bytes);
+    assert_eq!(base64::encode("short", &options), "c2hvcnQ=");
+}
+
+#[test]
+fn bad_characters() {
+    let options = Base64Options::default();
+    let error = base64::decode_bytes("aGV*bG8=", &options).unwrap_err();
+    assert_eq!(error.position, Some(3));
+    assert_eq!(error.reason, "* isn't a Base64 character");
+    // Positions count the whitespace that is skipped.
+    let error = base64::decode_bytes("aGVs\r\nbG!=", &options).unwrap_err();
+    assert_eq!(error.position, Some(8));
+    assert_eq!(error.to_string(), "at character 9: ! isn't a Base64 character");
+
+    let error = base64::decode_bytes("-_-_", &options).unwrap_err();
+    assert_eq!(error.position, Some(0));
+    assert_eq!(error.reason, "- is only in the URL-safe alphabet");
+    let error = base64::decode_bytes("ab+/", &URL_SAFE).unwrap_err();
+    assert_eq!(error.position, Some(2));
+    assert_eq!(error.reason, "+ isn't in the URL-safe alphabet");
+}
+
+#[test]
+fn bad_padding() {
+    let options = Base64Options::default();
+    let error = base64::decode_bytes("Zg==Zm8=", &options).unwrap_err();
+    assert_eq!(error.position, Some(2));
+    assert_eq!(error.reason, "padding can only come at the end");
+
+    let error = base64::decode_bytes("Zm9vY", &options).unwrap_err();
+    assert_eq!(error.position, Some(5));
+
+    // h is 100001, and only the first two bits fit in the one byte.
+    let error = base64::decode_bytes("Zh==", &options).unwrap_err();
+    assert_eq!(error.position, Some(1));
+    assert!(error.reason.starts_with("h has leftover bits set"));
+}
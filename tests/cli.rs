@@ -0,0 +1,39 @@
+use std::{
+    env, fs,
+    io::Write,
+    process::{Command, Stdio},
+};
+
+fn cli(args: &[&str], stdin: &[u8]) -> String {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_ciphertools-cli"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(stdin).unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    String::from_utf8(output.stdout).unwrap().trim_end().to_string()
+}
+
+#[test]
+fn encodes_files_byte_for_byte() {
+    let path = env::temp_dir().join(format!("ciphertools-cli-test-{}", std::process::id()));
+    fs::write(&path, "hello\n").unwrap();
+    let path = path.to_str().unwrap();
+    assert_eq!(cli(&["base64", "encode", "-i", path], b""), "aGVsbG8K");
+    assert_eq!(cli(&["hex", "encode", "-i", path], b""), "68656c6c6f0a");
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn encodes_stdin_byte_for_byte() {
+    assert_eq!(cli(&["base64", "encode"], b"hello\n"), "aGVsbG8K");
+    assert_eq!(cli(&["base32", "encode"], b"hello\n"), "NBSWY3DPBI======");
+}
+
+#[test]
+fn text_argument_is_text() {
+    assert_eq!(cli(&["base64", "encode", "hello"], b""), "aGVsbG8=");
+}
@@ -0,0 +1,50 @@
+use ciphertools::{
+    columnar::{self, Columnar, ColumnarKey},
+    Cipher,
+};
+
+fn key(keyword: &str, second_keyword: Option<&str>, padding: Option<char>) -> ColumnarKey {
+    ColumnarKey {
+        keyword: String::from(keyword),
+        second_keyword: second_keyword.map(String::from),
+        padding,
+    }
+}
+
+#[test]
+fn zebras() {
+    assert_eq!(columnar::column_ranks("ZEBRAS").unwrap(), [5, 2, 1, 3, 0, 4]);
+    let plaintext = "WEAREDISCOVEREDFLEEATONCE";
+
+    let regular = key("ZEBRAS", None, Some('X'));
+    let ciphertext = "EVLNXACDTXESEAXROFOXDEECXWIREE";
+    assert_eq!(Columnar.encrypt(plaintext, &regular).unwrap(), ciphertext);
+    assert_eq!(Columnar.decrypt(ciphertext, &regular).unwrap(), format!("{}XXXXX", plaintext));
+
+    let irregular = key("ZEBRAS", None, None);
+    let ciphertext = "EVLNACDTESEAROFODEECWIREE";
+    assert_eq!(Columnar.encrypt(plaintext, &irregular).unwrap(), ciphertext);
+    assert_eq!(Columnar.decrypt(ciphertext, &irregular).unwrap(), plaintext);
+}
+
+#[test]
+fn find_key() {
+    let plaintext = "WEAREDISCOVEREDFLEEATONCE";
+    let found = Columnar.find_key(plaintext, "EVLNACDTESEAROFODEECWIREE").unwrap();
+    assert_eq!(columnar::column_ranks(&found.keyword), columnar::column_ranks("ZEBRAS"));
+}
+
+#[test]
+fn round_trip() {
+    let plaintext = "Meet me by the old oak tree at midnight.";
+    for key in [
+        key("GERMAN", None, None),
+        key("GERMAN", None, Some('Q')),
+        key("GERMAN", Some("CIPHER"), None),
+        key("LETTERS", Some("ABC"), Some('X')),
+    ] {
+        let ciphertext = Columnar.encrypt(plaintext, &key).unwrap();
+        let decrypted = Columnar.decrypt(&ciphertext, &key).unwrap();
+        assert_eq!(decrypted.trim_end_matches(|c| Some(c) == key.padding), plaintext);
+    }
+}
@@ -0,0 +1,138 @@
+use ciphertools::enigma::{self, EnigmaKey, Machine, Model, RotorSetting};
+
+fn rotor(rotor: usize, ring: char, position: char) -> RotorSetting {
+    RotorSetting {
+        rotor,
+        ring: ring as u32 - 65,
+        position: position as u32 - 65,
+    }
+}
+
+fn strip_spaces(text: &str) -> String {
+    text.split_whitespace().collect()
+}
+
+#[test]
+fn no_plugs_or_rings() {
+    let key = EnigmaKey {
+        model: Model::EnigmaI,
+        reflector: 1,
+        rotors: vec![rotor(0, 'A', 'A'), rotor(1, 'A', 'A'), rotor(2, 'A', 'A')],
+        plugboard: String::new(),
+    };
+    assert_eq!(enigma::encrypt("AAAAA", &key).unwrap(), "BDZGO");
+    assert_eq!(enigma::decrypt("BDZGO", &key).unwrap(), "AAAAA");
+}
+
+#[test]
+fn double_stepping() {
+    let key = EnigmaKey {
+        model: Model::EnigmaI,
+        reflector: 1,
+        rotors: vec![rotor(0, 'A', 'A'), rotor(1, 'A', 'D'), rotor(2, 'A', 'U')],
+        plugboard: String::new(),
+    };
+    let mut machine = Machine::new(&key).unwrap();
+    let windows = (0..3)
+        .map(|_| {
+            machine.press(0);
+            machine
+                .positions()
+                .iter()
+                .map(|&x| std::char::from_u32(x + 65).unwrap())
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(windows, ["ADV", "AEW", "BFX"]);
+}
+
+#[test]
+fn m4_with_beta_at_a_matches_m3() {
+    let m3 = EnigmaKey {
+        model: Model::M3,
+        reflector: 1,
+        rotors: vec![rotor(0, 'C', 'X'), rotor(5, 'Q', 'M'), rotor(7, 'F', 'Y')],
+        plugboard: String::from("AV BS CG DL FU HZ IN KM OW RX"),
+    };
+    let m4 = EnigmaKey {
+        model: Model::M4,
+        reflector: 3,
+        rotors: [vec![rotor(8, 'A', 'A')], m3.rotors.clone()].concat(),
+        ..m3.clone()
+    };
+    let text = "THEQUICKBROWNFOXJUMPSOVERTHELAZYDOG";
+    assert_eq!(enigma::encrypt(text, &m3), enigma::encrypt(text, &m4));
+}
+
+/// The first part of a message sent by the German army on the first day of
+/// Operation Barbarossa, 1941.
+#[test]
+fn operation_barbarossa() {
+    let key = EnigmaKey {
+        model: Model::EnigmaI,
+        reflector: 1,
+        rotors: vec![rotor(1, 'B', 'B'), rotor(3, 'U', 'L'), rotor(4, 'L', 'A')],
+        plugboard: String::from("AV BS CG DL FU HZ IN KM OW RX"),
+    };
+    let ciphertext = "EDPUD NRGYS ZRCXN UYTPO MRMBO FKTBZ REZKM LXLVE FGUEY SIOZV EQMIK UBPMM YLKLT TDEIS \
+                      MDICA GYKUA CTCDO MOHWX MUUIA UBSTS LRNBZ SZWNR FXWFY SSXJZ VIJHI DISHP RKLKA YUPAD \
+                      TXQSP INQMA TLPIF SVKDA SCTAC DPBOP VHJK";
+    let plaintext = "AUFKL XABTE ILUNG XVONX KURTI NOWAX KURTI NOWAX NORDW ESTLX SEBEZ XSEBE ZXUAF FLIEG \
+                     ERSTR ASZER IQTUN GXDUB ROWKI XDUBR OWKIX OPOTS CHKAX OPOTS CHKAX UMXEI NSAQT DREIN \
+                     ULLXU HRANG ETRET ENXAN GRIFF XINFX RGTX";
+    assert_eq!(
+        enigma::decrypt(&strip_spaces(ciphertext), &key).unwrap(),
+        strip_spaces(plaintext)
+    );
+}
+
+/// A message sent by U-264 in 1942 on the four-rotor naval Enigma, first
+/// broken by the M4 Project in 2006.
+#[test]
+fn u264() {
+    let key = EnigmaKey {
+        model: Model::M4,
+        reflector: 3,
+        rotors: vec![
+            rotor(8, 'A', 'V'),
+            rotor(1, 'A', 'J'),
+            rotor(3, 'A', 'N'),
+            rotor(0, 'V', 'A'),
+        ],
+        plugboard: String::from("AT BL DF GJ HM NW OP QY RZ VX"),
+    };
+    let ciphertext = "NCZW VUSX PNYM INHZ XMQX SFWX WLKJ AHSH NMCO CCAK UQPM KCSM HKSE INJU SBLK IOSX CKUB \
+                      HMLL XCSJ USRR DVKO HULX WCCB GVLI YXEO AHXR HKKF VDRE WEZL XOBA FGYU JQUK GRTV UKAM \
+                      EURB VEKS UHHV OYHA BCJW MAKL FKLM YFVN RIZR VVRT KOFD ANJM OLBG FFLE OPRG TFLV RHOW \
+                      OPBE KVWM UQFM PWPA RMFH AGKX IIBG";
+    let plaintext = "VONV ONJL OOKS JHFF TTTE INSE INSD REIZ WOYY QNNS NEUN INHA LTXX BEIA NGRI FFUN TERW \
+                     ASSE RGED RUEC KTYW ABOS XLET ZTER GEGN ERST ANDN ULAC HTDR EINU LUHR MARQ UANT ONJO \
+                     TANE UNAC HTSE YHSD REIY ZWOZ WONU LGRA DYAC HTSM YSTO SSEN ACHX EKNS VIER MBFA ELLT \
+                     YNNN NNNO OOVI ERYS ICHT EINS NULL";
+    assert_eq!(
+        enigma::decrypt(&strip_spaces(ciphertext), &key).unwrap(),
+        strip_spaces(plaintext)
+    );
+}
+
+#[test]
+fn invalid_keys() {
+    let key = EnigmaKey {
+        model: Model::EnigmaI,
+        reflector: 1,
+        rotors: vec![rotor(0, 'A', 'A'), rotor(0, 'A', 'A'), rotor(2, 'A', 'A')],
+        plugboard: String::new(),
+    };
+    assert!(enigma::key_error(&key).is_some());
+    let key = EnigmaKey {
+        rotors: vec![rotor(0, 'A', 'A'), rotor(1, 'A', 'A'), rotor(5, 'A', 'A')],
+        ..key
+    };
+    assert!(enigma::key_error(&key).is_some());
+    let key = EnigmaKey {
+        model: Model::M3,
+        plugboard: String::from("AB BC"),
+        ..key
+    };
+    assert!(enigma::key_error(&key).is_some());
+}
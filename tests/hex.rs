@@ -0,0 +1,36 @@
+use ciphertools::hex::{self, HexOptions, Separator};
+
+#[test]
+fn rfc_4648_vectors() {
+    let options = HexOptions {
+        upper: true,
+        ..Default::default()
+    };
+    let vectors = [("", ""), ("f", "66"), ("fo", "666F"), ("foo", "666F6F"), ("foobar", "666F6F626172")];
+    for &(plaintext, encoded) in &vectors {
+        assert_eq!(hex::encode(plaintext, &options), encoded);
+        assert_eq!(hex::decode(encoded).unwrap(), plaintext);
+        assert_eq!(hex::decode(&encoded.to_lowercase()).unwrap(), plaintext);
+    }
+}
+
+#[test]
+fn separators() {
+    let bytes = [0xde, 0xad, 0xbe, 0xef];
+    assert_eq!(hex::encode_bytes(&bytes, &HexOptions::SPACED), "de ad be ef");
+    let colons = HexOptions {
+        separator: Separator::Colon,
+        upper: true,
+    };
+    assert_eq!(hex::encode_bytes(&bytes, &colons), "DE:AD:BE:EF");
+    assert_eq!(hex::decode_bytes("DE:AD:BE:EF").unwrap(), bytes);
+    assert_eq!(hex::decode_bytes("de-ad be\nef").unwrap(), bytes);
+    assert!(hex::decode_bytes("d:e").is_err());
+    assert!(hex::decode_bytes("dea").is_err());
+}
+
+#[test]
+fn round_trip() {
+    let bytes = (0..=255).collect::<Vec<u8>>();
+    assert_eq!(hex::decode_bytes(&hex::encode_bytes(&bytes, &HexOptions::default())).unwrap(), bytes);
+}
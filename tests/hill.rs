@@ -0,0 +1,84 @@
+use ciphertools::{
+    hill::{self, Hill, Matrix},
+    Cipher,
+    CipherError,
+};
+
+#[test]
+fn two_by_two() {
+    let key = Matrix::parse("3,3;2,5").unwrap();
+    assert_eq!(key.determinant(), 9);
+    assert_eq!(key.inverse().unwrap(), Matrix::parse("15,17;20,9").unwrap());
+    assert_eq!(Hill.encrypt("HELP", &key).unwrap(), "HIAT");
+    assert_eq!(Hill.decrypt("HIAT", &key).unwrap(), "HELP");
+    assert_eq!(Hill.find_key("HELP", "HIAT").unwrap(), key);
+}
+
+#[test]
+fn three_by_three() {
+    let key = Matrix::from_keyword("GYBNQKURP", 3).unwrap();
+    assert_eq!(key, Matrix::parse("6,24,1;13,16,10;20,17,15").unwrap());
+    assert_eq!(key.determinant(), 25);
+    assert_eq!(key.inverse().unwrap(), Matrix::parse("8,5,10;21,8,21;21,12,8").unwrap());
+    assert_eq!(Hill.encrypt("ACT", &key).unwrap(), "POH");
+    assert_eq!(Hill.decrypt("POH", &key).unwrap(), "ACT");
+}
+
+#[test]
+fn singular_keys() {
+    // The determinants are 2 and 13.
+    for key in ["2,0;0,1", "13,0;0,1"] {
+        let key = Matrix::parse(key).unwrap();
+        assert!(key.inverse().is_none());
+        assert!(hill::key_error(&key).is_some());
+        assert!(Hill.encrypt("HELP", &key).is_err());
+    }
+}
+
+#[test]
+fn large_key() {
+    // Upper triangular with ones on the diagonal, so the determinant is 1.
+    let size = 12;
+    let entries = (0..size * size)
+        .map(|i| match (i / size, i % size) {
+            (r, c) if r == c => 1,
+            (r, c) if r < c => (r * 7 + c * 3) as u32 % 26,
+            _ => 0,
+        })
+        .collect();
+    let key = Matrix { size, entries };
+    assert_eq!(key.determinant(), 1);
+    let plaintext = "It is a truth universally acknowledged, that a single man in possession of a good fortune, \
+        must be in want of a wife.";
+    assert!(key.inverse().is_some());
+    let ciphertext = Hill.encrypt(plaintext, &key).unwrap();
+    // The 92 letters are padded to a whole number of blocks.
+    assert_eq!(Hill.decrypt(&ciphertext, &key).unwrap(), format!("{}xxxx", plaintext));
+}
+
+#[test]
+fn round_trip() {
+    let key = Matrix::parse("3,3;2,5").unwrap();
+    let ciphertext = Hill.encrypt("Attack at dawn!", &key).unwrap();
+    assert_eq!(Hill.decrypt(&ciphertext, &key).unwrap(), "Attack at dawn!");
+    // The last block is padded with X's.
+    let ciphertext = Hill.encrypt("Attack now", &key).unwrap();
+    assert_eq!(Hill.decrypt(&ciphertext, &key).unwrap(), "Attack nowx");
+}
+
+#[test]
+fn huge_sizes() {
+    let size = usize::MAX / 2;
+    assert_eq!(Matrix::identity(size), None);
+    assert_eq!(Matrix::identity(3).unwrap().to_string(), "1,0,0;0,1,0;0,0,1");
+    assert_eq!(Matrix::from_keyword("GYBNQKURP", size), None);
+    let key = Matrix {
+        size,
+        entries: vec![1; 4],
+    };
+    assert!(matches!(Hill.encrypt("hello", &key), Err(CipherError::InvalidKey(_))));
+    assert!(matches!(
+        hill::find_key("attackatdawn", "wnmkkqcwvxrv", size),
+        Err(CipherError::InvalidText(_))
+    ));
+}
@@ -0,0 +1,59 @@
+use ciphertools::{
+    base64::{self, Base64Options},
+    hex::{self, HexOptions},
+    identify::{self, Family},
+    morse::{self, MorseOptions},
+    rail_fence::{RailFence, RailFenceKey},
+    shift::Shift,
+    vigenere::Vigenere,
+    Cipher,
+};
+
+const PARAGRAPH: &str = "It was the best of times, it was the worst of times, it was the age of wisdom, \
+    it was the age of foolishness, it was the epoch of belief, it was the epoch of incredulity, \
+    it was the season of Light, it was the season of Darkness.";
+
+fn best_guess(text: &str) -> Family {
+    identify::guess(text, &identify::statistics(text))[0].family
+}
+
+#[test]
+fn statistics() {
+    let statistics = identify::statistics("Hello, World");
+    assert_eq!((statistics.length, statistics.letters), (11, 10));
+    assert!(statistics.has_upper && statistics.has_lower && statistics.has_symbols);
+    assert!(!statistics.has_digits && !statistics.digits_only);
+    assert_eq!(statistics.doubled_letters, 1);
+    assert!(statistics.even_length());
+
+    let statistics = identify::statistics(PARAGRAPH);
+    assert!(statistics.ioc > 0.06);
+}
+
+#[test]
+fn encodings() {
+    assert_eq!(best_guess(&base64::encode(PARAGRAPH, &Base64Options::default())), Family::Base64);
+    assert_eq!(best_guess(&hex::encode(PARAGRAPH, &HexOptions::SPACED)), Family::Hex);
+    assert_eq!(best_guess(&hex::encode(PARAGRAPH, &HexOptions::default())), Family::Hex);
+    let code = morse::encode(PARAGRAPH, &MorseOptions::default()).unwrap();
+    assert_eq!(best_guess(&code), Family::Morse);
+}
+
+#[test]
+fn ciphers() {
+    assert_eq!(best_guess(&Shift::default().encrypt(PARAGRAPH, &7).unwrap()), Family::Shift);
+
+    let letters = PARAGRAPH.chars().filter(char::is_ascii_alphabetic).collect::<String>();
+    let ciphertext = Vigenere::default().encrypt(&letters, &String::from("cipher")).unwrap();
+    assert_eq!(identify::statistics(&ciphertext).period(), Some(6));
+    assert_eq!(best_guess(&ciphertext), Family::Vigenere);
+
+    // The letters are English, just out of order.
+    let ciphertext = RailFence.encrypt(&letters, &RailFenceKey { rails: 3, offset: 0 }).unwrap();
+    assert!(matches!(best_guess(&ciphertext), Family::RailFence | Family::Columnar));
+}
+
+#[test]
+fn empty_text() {
+    assert!(identify::guess("", &identify::statistics("")).is_empty());
+}
@@ -0,0 +1,97 @@
+use ciphertools::{
+    encoding::DecodeError,
+    morse::{self, AudioError, Keying, Morse, MorseOptions},
+    wav::Wav,
+    Cipher,
+};
+
+#[test]
+fn known_answers() {
+    let options = MorseOptions::default();
+    let code = ".... . .-.. .-.. --- / .-- --- .-. .-.. -..";
+    assert_eq!(morse::encode("Hello world", &options).unwrap(), code);
+    assert_eq!(morse::decode(code, &options).unwrap(), "HELLO WORLD");
+    assert_eq!(morse::encode("SOS 73!", &options).unwrap(), "... --- ... / --... ...-- -.-.--");
+
+    let options = MorseOptions {
+        dot: '0',
+        dash: '1',
+        letter_separator: String::from(","),
+        word_separator: String::from(";"),
+    };
+    assert_eq!(morse::encode("ab c", &options).unwrap(), "01,1000;1010");
+    assert_eq!(Morse { options }.decrypt("01,1000;1010", &()).unwrap(), "AB C");
+}
+
+#[test]
+fn prosigns() {
+    let options = MorseOptions::default();
+    assert_eq!(morse::encode("<SK>", &options).unwrap(), "...-.-");
+    assert_eq!(morse::decode("...-.-", &options).unwrap(), "<SK>");
+    assert_eq!(morse::encode("<SOS> help", &options).unwrap(), "...---... / .... . .-.. .--.");
+    // AR shares its code with +, so it comes back as that.
+    assert_eq!(morse::encode("<AR>", &options).unwrap(), ".-.-.");
+    assert_eq!(morse::decode(".-.-.", &options).unwrap(), "+");
+
+    let error = morse::encode("ok <>", &options).unwrap_err();
+    assert_eq!((error.position, error.reason.as_str()), (Some(3), "the prosign is empty"));
+    assert_eq!(morse::encode("<S K>", &options).unwrap_err().position, Some(2));
+    assert_eq!(morse::encode("go <SK", &options).unwrap_err().position, Some(3));
+}
+
+#[test]
+fn unknown_characters() {
+    let options = MorseOptions::default();
+    let error = morse::encode("C#", &options).unwrap_err();
+    assert_eq!(
+        error,
+        DecodeError {
+            position: Some(1),
+            reason: String::from("# has no Morse code"),
+        }
+    );
+    assert_eq!(morse::decode("... ........-", &options).unwrap_err().position, Some(4));
+    assert!(morse::decode("... ..x", &options).is_err());
+    assert!(Morse { options }.encrypt("naïve", &()).is_err());
+}
+
+#[test]
+fn audio_round_trip() {
+    let options = MorseOptions::default();
+    let code = morse::encode("CQ DE <SK> 73", &options).unwrap();
+    for keying in [
+        Keying::default(),
+        Keying {
+            wpm: 25,
+            farnsworth_wpm: 10,
+            frequency: 700,
+            sample_rate: 11025,
+        },
+    ] {
+        let wav = morse::to_audio(&code, &options, &keying).unwrap();
+        assert_eq!(wav.sample_rate, keying.sample_rate);
+        let wav = Wav::from_bytes(&wav.to_bytes()).unwrap();
+        let heard = morse::from_audio(&wav, &options).unwrap();
+        assert_eq!(heard, code);
+        assert_eq!(morse::decode(&heard, &options).unwrap(), "CQ DE <SK> 73");
+    }
+}
+
+#[test]
+fn audio_errors() {
+    let options = MorseOptions::default();
+    let keying = Keying {
+        wpm: 0,
+        ..Keying::default()
+    };
+    assert!(matches!(morse::to_audio("...", &options, &keying), Err(AudioError::InvalidKeying(_))));
+    assert!(matches!(
+        morse::to_audio("..x", &options, &Keying::default()),
+        Err(AudioError::InvalidMorse(_))
+    ));
+    let silence = Wav {
+        sample_rate: 8000,
+        samples: vec![0.0; 8000],
+    };
+    assert_eq!(morse::from_audio(&silence, &options), Err(AudioError::Silent));
+}
@@ -0,0 +1,73 @@
+use ciphertools::{
+    playfair::{self, Merge, Playfair, PlayfairKey, Square},
+    Cipher,
+    CipherError,
+};
+
+fn key(keyword: &str, merge: Merge, filler: char) -> PlayfairKey {
+    PlayfairKey {
+        keyword: String::from(keyword),
+        merge,
+        filler,
+    }
+}
+
+#[test]
+fn playfair_example() {
+    let key = key("playfair example", Merge::JIntoI, 'X');
+    let square = Square::new(&key.keyword, key.merge).letters().iter().collect::<String>();
+    assert_eq!(square, "PLAYFIREXMBCDGHKNOQSTUVWZ");
+    let ciphertext = "BMODZBXDNABEKUDMUIXMMOUVIF";
+    assert_eq!(Playfair.encrypt("Hide the gold in the tree stump", &key).unwrap(), ciphertext);
+    // The X that split up the doubled E stays in.
+    assert_eq!(Playfair.decrypt(ciphertext, &key).unwrap(), "HIDETHEGOLDINTHETREXESTUMP");
+}
+
+#[test]
+fn fillers() {
+    let key = key("playfair example", Merge::JIntoI, 'X');
+    let pairs = |text| playfair::prepare(text, &key).unwrap();
+    // Between doubled letters, and after an odd letter at the end.
+    assert_eq!(pairs("balloon"), [['B', 'A'], ['L', 'X'], ['L', 'O'], ['O', 'N']]);
+    assert_eq!(pairs("cat"), [['C', 'A'], ['T', 'X']]);
+    // A doubled filler is split up with Z instead.
+    assert_eq!(pairs("xx"), [['X', 'Z'], ['X', 'Z']]);
+}
+
+#[test]
+fn merges() {
+    let j_into_i = key("jump", Merge::JIntoI, 'X');
+    let letters = Square::new(&j_into_i.keyword, j_into_i.merge).letters().iter().collect::<String>();
+    assert_eq!(letters, "IUMPABCDEFGHKLNOQRSTVWXYZ");
+    assert_eq!(
+        Playfair.decrypt(&Playfair.encrypt("jam", &j_into_i).unwrap(), &j_into_i).unwrap(),
+        "IAMX"
+    );
+
+    let omit_q = key("quiz", Merge::OmitQ, 'X');
+    let letters = Square::new(&omit_q.keyword, omit_q.merge).letters().iter().collect::<String>();
+    assert_eq!(letters, "UIZABCDEFGHJKLMNOPRSTVWXY");
+    assert_eq!(
+        Playfair.decrypt(&Playfair.encrypt("quick jump", &omit_q).unwrap(), &omit_q).unwrap(),
+        "UICKJUMP"
+    );
+}
+
+#[test]
+fn bad_keys() {
+    for key in [key("keyword", Merge::JIntoI, '1'), key("keyword", Merge::OmitQ, 'Q')] {
+        assert!(!Playfair.is_valid_key(&key));
+        assert!(matches!(Playfair.encrypt("text", &key), Err(CipherError::InvalidKey(_))));
+    }
+    assert!(Playfair.is_valid_key(&key("keyword", Merge::JIntoI, 'Q')));
+    assert_eq!(Playfair.find_key("HELLO", "KGYVRV"), Err(CipherError::Unsupported));
+}
+
+#[test]
+fn bad_ciphertexts() {
+    let example = key("playfair example", Merge::JIntoI, 'X');
+    assert!(matches!(Playfair.decrypt("BMO", &example), Err(CipherError::InvalidText(_))));
+    assert!(matches!(Playfair.decrypt("BMOO", &example), Err(CipherError::InvalidText(_))));
+    let omit_q = key("keyword", Merge::OmitQ, 'X');
+    assert!(matches!(Playfair.decrypt("QA", &omit_q), Err(CipherError::InvalidText(_))));
+}
@@ -0,0 +1,35 @@
+use ciphertools::{
+    rail_fence::{self, RailFence, RailFenceKey},
+    Cipher,
+};
+
+#[test]
+fn three_rails() {
+    let key = RailFenceKey { rails: 3, offset: 0 };
+    let ciphertext = "WECRLTEERDSOEEFEAOCAIVDEN";
+    assert_eq!(RailFence.encrypt("WEAREDISCOVEREDFLEEATONCE", &key).unwrap(), ciphertext);
+    assert_eq!(RailFence.decrypt(ciphertext, &key).unwrap(), "WEAREDISCOVEREDFLEEATONCE");
+    assert_eq!(RailFence.find_key("WEAREDISCOVEREDFLEEATONCE", ciphertext).unwrap(), key);
+}
+
+#[test]
+fn zigzag() {
+    let key = RailFenceKey { rails: 3, offset: 0 };
+    let rails = (0..8).map(|i| rail_fence::rail(i, key)).collect::<Vec<_>>();
+    assert_eq!(rails, [0, 1, 2, 1, 0, 1, 2, 1]);
+    let key = RailFenceKey { rails: 3, offset: 2 };
+    assert_eq!(rail_fence::rail(0, key), 2);
+}
+
+#[test]
+fn round_trip() {
+    let plaintext = "Meet me by the old oak tree at midnight.";
+    for rails in 1..10 {
+        for offset in 0..2 * rails {
+            let key = RailFenceKey { rails, offset };
+            let ciphertext = RailFence.encrypt(plaintext, &key).unwrap();
+            assert_eq!(RailFence.decrypt(&ciphertext, &key).unwrap(), plaintext);
+        }
+    }
+    assert!(RailFence.encrypt(plaintext, &RailFenceKey { rails: 0, offset: 0 }).is_err());
+}
@@ -0,0 +1,90 @@
+use ciphertools::{
+    recipe::{Recipe, RecipeError, Step, StepError},
+    CipherError,
+};
+
+#[test]
+fn round_trip() {
+    let recipe = Recipe::parse(
+        "# Encrypt, then encode\n\
+         vigenere encrypt key=lemon\n\
+         \n\
+         base64 encode\n\
+         base64 decode\n\
+         vigenere decrypt key=lemon\n",
+    )
+    .unwrap();
+    assert_eq!(recipe.steps.len(), 4);
+    let outputs = recipe.run("Attack at dawn").into_iter().collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(outputs, ["Lxfopv mh oeib", "THhmb3B2IG1oIG9laWI=", "Lxfopv mh oeib", "Attack at dawn"]);
+}
+
+#[test]
+fn quoted_settings() {
+    let recipe = Recipe::parse("enigma encrypt rotors=I,II,III plugboard=\"AB CD\"").unwrap();
+    assert_eq!(recipe.steps[0].operation, "enigma encrypt");
+    assert_eq!(recipe.steps[0].settings, "rotors=I,II,III plugboard=\"AB CD\"");
+    assert_eq!(Recipe::parse(&recipe.to_string()).unwrap(), recipe);
+}
+
+#[test]
+fn unknown_step() {
+    let error = Recipe::parse("shift encrypt key=3\nrot13 encode").unwrap_err();
+    assert_eq!(error, RecipeError::Line(2, StepError::UnknownOperation(String::from("rot13 encode"))));
+    assert_eq!(error.to_string(), "line 2: there is no operation called rot13 encode");
+}
+
+#[test]
+fn bad_setting() {
+    let error = Recipe::parse("\nshift encrypt key=3 colour=red").unwrap_err();
+    let reason = String::from("there is no setting called colour");
+    assert_eq!(error, RecipeError::Line(2, StepError::InvalidSetting(reason)));
+    assert!(matches!(
+        Recipe::parse("vigenere decrypt key=lemon variant=caesar"),
+        Err(RecipeError::Line(1, StepError::InvalidSetting(_)))
+    ));
+    assert!(matches!(
+        Recipe::parse("enigma encrypt plugboard=\"AB CD"),
+        Err(RecipeError::Line(1, StepError::InvalidSetting(_)))
+    ));
+    assert!(matches!(Recipe::parse("vigenere encrypt"), Err(RecipeError::Line(1, StepError::InvalidSetting(_)))));
+}
+
+#[test]
+fn bad_key() {
+    let error = Recipe::parse("affine encrypt key=3").unwrap_err();
+    assert_eq!(
+        error,
+        RecipeError::Line(1, StepError::Cipher(CipherError::InvalidKey(String::from("must be two numbers, A,B"))))
+    );
+    assert!(matches!(
+        Recipe::parse("enigma encrypt rotors=I,II,IX"),
+        Err(RecipeError::Line(1, StepError::Cipher(CipherError::InvalidKey(_))))
+    ));
+    assert!(matches!(
+        Recipe::parse("shift encrypt key=26"),
+        Err(RecipeError::Line(1, StepError::Cipher(CipherError::InvalidKey(_))))
+    ));
+}
+
+#[test]
+fn failing_step() {
+    // A key that parses but can't be used is only found when the step runs.
+    let recipe = Recipe::parse("rail-fence encrypt key=3\naffine encrypt key=2,3\nbase64 encode").unwrap();
+    let outputs = recipe.run("hello");
+    assert_eq!(outputs.len(), 2);
+    assert!(outputs[0].is_ok());
+    assert_eq!(
+        outputs[1],
+        Err(RecipeError::Step(
+            2,
+            StepError::Cipher(CipherError::InvalidKey(String::from("A must be coprime to 26, so odd and not 13")))
+        ))
+    );
+
+    let step = Step {
+        operation: String::from("base64 decode"),
+        settings: String::new(),
+    };
+    assert!(matches!(step.apply("aGVsbG8*"), Err(StepError::Cipher(CipherError::Decode(_)))));
+}
@@ -0,0 +1,39 @@
+use ciphertools::{
+    alphabet::Alphabet,
+    shift::{self, Shift},
+    Cipher,
+};
+
+const PARAGRAPH: &str = "It was the best of times, it was the worst of times, it was the age of wisdom, \
+    it was the age of foolishness, it was the epoch of belief, it was the epoch of incredulity, \
+    it was the season of Light, it was the season of Darkness.";
+
+#[test]
+fn caesar() {
+    let plaintext = "THE QUICK BROWN FOX JUMPS OVER THE LAZY DOG";
+    let ciphertext = "WKH TXLFN EURZQ IRA MXPSV RYHU WKH ODCB GRJ";
+    assert_eq!(Shift::default().encrypt(plaintext, &3).unwrap(), ciphertext);
+    assert_eq!(Shift::default().decrypt(ciphertext, &3).unwrap(), plaintext);
+    assert_eq!(Shift::default().find_key(plaintext, ciphertext).unwrap(), 3);
+}
+
+#[test]
+fn round_trip() {
+    let cipher = Shift::default();
+    for count in 0..26 {
+        let ciphertext = cipher.encrypt(PARAGRAPH, &count).unwrap();
+        assert_eq!(cipher.decrypt(&ciphertext, &count).unwrap(), PARAGRAPH);
+    }
+    assert!(cipher.encrypt(PARAGRAPH, &26).is_err());
+}
+
+#[test]
+fn crack_paragraph() {
+    let cipher = Shift::default();
+    for count in [1, 13, 25] {
+        let ciphertext = cipher.encrypt(PARAGRAPH, &count).unwrap();
+        let best = &shift::crack(&ciphertext, &Alphabet::english())[0];
+        assert_eq!(best.count, count);
+        assert_eq!(best.plaintext, PARAGRAPH);
+    }
+}
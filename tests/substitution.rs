@@ -0,0 +1,41 @@
+use ciphertools::{
+    substitution::{self, Substitution},
+    Cipher,
+};
+
+const PARAGRAPH: &str = "Call me Ishmael. Some years ago, never mind how long precisely, having little or no money \
+    in my purse, and nothing particular to interest me on shore, I thought I would sail about a little and see \
+    the watery part of the world. It is a way I have of driving off the spleen and regulating the circulation. \
+    Whenever I find myself growing grim about the mouth; whenever it is a damp, drizzly November in my soul; \
+    whenever I find myself involuntarily pausing before coffin warehouses, and bringing up the rear of every \
+    funeral I meet; then, I account it high time to get to sea as soon as I can.";
+
+#[test]
+fn zebras() {
+    let key = substitution::keyword_alphabet("zebras");
+    assert_eq!(key, "ZEBRASCDFGHIJKLMNOPQTUVWXY");
+    let ciphertext = "SIAA ZQ LKBA. VA ZOA RFPBLUAOAR!";
+    assert_eq!(Substitution.encrypt("FLEE AT ONCE. WE ARE DISCOVERED!", &key).unwrap(), ciphertext);
+    assert_eq!(Substitution.decrypt(ciphertext, &key).unwrap(), "FLEE AT ONCE. WE ARE DISCOVERED!");
+}
+
+#[test]
+fn invalid_keys() {
+    assert!(Substitution.encrypt("text", &String::from("ABC")).is_err());
+    assert!(Substitution.encrypt("text", &String::from("AACDEFGHIJKLMNOPQRSTUVWXYZ")).is_err());
+}
+
+#[test]
+fn round_trip() {
+    let key = substitution::keyword_alphabet("Moby Dick");
+    let ciphertext = Substitution.encrypt(PARAGRAPH, &key).unwrap();
+    assert_eq!(Substitution.decrypt(&ciphertext, &key).unwrap(), PARAGRAPH);
+    assert_eq!(Substitution.find_key(PARAGRAPH, &ciphertext).unwrap(), key);
+}
+
+#[test]
+fn crack_paragraph() {
+    let key = substitution::keyword_alphabet("The white whale");
+    let ciphertext = Substitution.encrypt(PARAGRAPH, &key).unwrap();
+    assert_eq!(substitution::crack(&ciphertext, 10).plaintext, PARAGRAPH);
+}
@@ -0,0 +1,110 @@
+use ciphertools::{
+    alphabet::Alphabet,
+    shift::Shift,
+    text_options::{Case, TextOptions},
+    vigenere::Vigenere,
+    Cipher,
+    CipherError,
+};
+
+fn options(key_on_letters: bool, strip: bool, case: Case, groups: bool) -> TextOptions {
+    TextOptions {
+        key_on_letters,
+        strip,
+        case,
+        groups,
+    }
+}
+
+fn vigenere(options: TextOptions) -> Vigenere {
+    Vigenere {
+        options,
+        ..Vigenere::default()
+    }
+}
+
+#[test]
+fn format() {
+    let english = Alphabet::english();
+    let text = "Attack at dawn, 5am!";
+    assert_eq!(TextOptions::default().format(text, &english, true), text);
+    assert_eq!(options(false, true, Case::Keep, false).format(text, &english, true), "Attackatdawnam");
+    assert_eq!(options(false, false, Case::Upper, false).format(text, &english, true), "ATTACK AT DAWN, 5AM!");
+    assert_eq!(options(false, false, Case::Lower, false).format(text, &english, false), "attack at dawn, 5am!");
+    assert_eq!(options(false, false, Case::Upper, true).format(text, &english, true), "ATTAC KATDA WNAM");
+    // Plaintext is stripped by groups, but not split.
+    assert_eq!(options(false, false, Case::Keep, true).format(text, &english, false), "Attackatdawnam");
+
+    // ß has no one-character uppercase, so it stays as it is.
+    let german = Alphabet::preset("German").unwrap();
+    assert_eq!(options(false, false, Case::Upper, false).format("Straße", &german, true), "STRAßE");
+    assert_eq!(options(false, true, Case::Lower, false).format("Ä-Ö-Ü", &german, true), "äöü");
+}
+
+#[test]
+fn key_on_letters() {
+    let key = String::from("lemon");
+    let cipher = vigenere(TextOptions::default());
+    assert_eq!(cipher.encrypt("attack at dawn", &key).unwrap(), "lxfopv mh oeib");
+    let cipher = vigenere(options(true, false, Case::Keep, false));
+    assert_eq!(cipher.encrypt("attack at dawn", &key).unwrap(), "lxfopv ef rnhr");
+    // Stripping and grouping leave the key lined up with the letters too.
+    let cipher = vigenere(options(false, true, Case::Upper, false));
+    assert_eq!(cipher.encrypt("attack at dawn", &key).unwrap(), "LXFOPVEFRNHR");
+    let cipher = vigenere(options(false, false, Case::Upper, true));
+    assert_eq!(cipher.encrypt("attack at dawn", &key).unwrap(), "LXFOP VEFRN HR");
+}
+
+#[test]
+fn round_trips() {
+    let english = Alphabet::english();
+    let plaintext = "Attack at dawn, then retreat!";
+    let key = String::from("lemon");
+    for &key_on_letters in &[false, true] {
+        for &strip in &[false, true] {
+            for &case in &[Case::Keep, Case::Upper, Case::Lower] {
+                for &groups in &[false, true] {
+                    let options = options(key_on_letters, strip, case, groups);
+                    let expected = options.format(plaintext, &english, false);
+                    let cipher = vigenere(options);
+                    let ciphertext = cipher.encrypt(plaintext, &key).unwrap();
+                    assert_eq!(cipher.decrypt(&ciphertext, &key).unwrap(), expected, "{:?}", options);
+                    assert_eq!(cipher.find_key(plaintext, &ciphertext).unwrap(), key, "{:?}", options);
+
+                    let cipher = Shift {
+                        alphabet: english.clone(),
+                        options,
+                    };
+                    let ciphertext = cipher.encrypt(plaintext, &7).unwrap();
+                    assert_eq!(cipher.decrypt(&ciphertext, &7).unwrap(), expected, "{:?}", options);
+                    assert_eq!(cipher.find_key(plaintext, &ciphertext).unwrap(), 7, "{:?}", options);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn alignment() {
+    let cipher = Shift {
+        alphabet: Alphabet::english(),
+        options: TextOptions::default(),
+    };
+    assert_eq!(cipher.find_key("Hello, world", "Khoor, zruog").unwrap(), 3);
+    assert_eq!(cipher.find_key("Hello", "khoor"), Err(CipherError::MismatchedCase(0)));
+    assert_eq!(cipher.find_key("Hello, world", "Khoor zruog"), Err(CipherError::NonAlphabeticMismatch(5)));
+    assert_eq!(cipher.find_key("Hello", "Khoo"), Err(CipherError::MismatchedLengths));
+
+    // Without keeping case, any case lines up, and stripped or grouped
+    // ciphertext lines up by its letters.
+    let cipher = Shift {
+        alphabet: Alphabet::english(),
+        options: options(false, false, Case::Upper, true),
+    };
+    assert_eq!(cipher.find_key("Hello, world", "khoor zruog").unwrap(), 3);
+    let cipher = Shift {
+        alphabet: Alphabet::english(),
+        options: options(false, true, Case::Keep, false),
+    };
+    assert_eq!(cipher.find_key("Hello, world", "Khoorzruog").unwrap(), 3);
+}
@@ -0,0 +1,158 @@
+use ciphertools::{
+    alphabet::Alphabet,
+    shift::Shift,
+    text_options::TextOptions,
+    vigenere::{self, Variant, Vigenere, VARIANTS},
+    Cipher,
+};
+
+const PARAGRAPH: &str = "It was the best of times, it was the worst of times, it was the age of wisdom, \
+    it was the age of foolishness, it was the epoch of belief, it was the epoch of incredulity, \
+    it was the season of Light, it was the season of Darkness, it was the spring of hope, \
+    it was the winter of despair, we had everything before us, we had nothing before us, \
+    we were all going direct to Heaven, we were all going direct the other way.";
+
+#[test]
+fn lemon() {
+    let cipher = Vigenere::default();
+    let key = String::from("LEMON");
+    assert_eq!(cipher.encrypt("ATTACKATDAWN", &key).unwrap(), "LXFOPVEFRNHR");
+    assert_eq!(cipher.decrypt("LXFOPVEFRNHR", &key).unwrap(), "ATTACKATDAWN");
+    assert_eq!(cipher.find_key("ATTACKATDAWN", "LXFOPVEFRNHR").unwrap(), "lemon");
+}
+
+#[test]
+fn no_letters() {
+    let cipher = Vigenere::default();
+    for text in ["", "123, 456!"] {
+        let error = cipher.find_key(text, text).unwrap_err();
+        assert_eq!(error, Shift::default().find_key(text, text).unwrap_err());
+        assert_eq!(error.to_string(), "invalid text: there are no letters to find the key from");
+    }
+}
+
+fn with_variant(variant: Variant) -> Vigenere {
+    Vigenere {
+        variant,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn round_trip() {
+    let cipher = Vigenere::default();
+    let key = String::from("Dickens");
+    let ciphertext = cipher.encrypt(PARAGRAPH, &key).unwrap();
+    assert_ne!(ciphertext, PARAGRAPH);
+    assert_eq!(cipher.decrypt(&ciphertext, &key).unwrap(), PARAGRAPH);
+}
+
+#[test]
+fn crack_paragraph() {
+    let cipher = Vigenere::default();
+    let ciphertext = cipher.encrypt(PARAGRAPH, &String::from("CIPHER")).unwrap();
+    let crack = vigenere::crack(&ciphertext, 12, &cipher).unwrap();
+    assert_eq!(crack.candidates[0].key, "cipher");
+    assert_eq!(crack.candidates[0].plaintext, PARAGRAPH);
+    // Multiples of the key length have English-looking columns too.
+    let ioc = |len: usize| crack.column_ioc[len - 1];
+    assert!(ioc(6) > 0.06 && ioc(12) > 0.06);
+    assert!((1..=12).filter(|len| len % 6 != 0).all(|len| ioc(len) < 0.06));
+}
+
+#[test]
+fn variant_vectors() {
+    let vectors = [
+        (Variant::Beaufort, "FORTIFICATION", "DEFENDTHEEASTWALLOFTHECASTLE", "CKMPVCPVWPIWUJOGIUAPVWRIWUUK"),
+        (Variant::Porta, "FORTIFICATION", "DEFENDTHEEASTWALLOFTHECASTLE", "SYNNJSCVRNRLAHUTUKUCVRYRLANY"),
+        (Variant::PlaintextAutokey, "QUEENLY", "ATTACKATDAWN", "QNXEPVYTWTWP"),
+        (Variant::Gronsfeld, "31415", "ATTACKATDAWN", "DUXBHNBXEFZO"),
+        (Variant::VariantBeaufort, "LEMON", "LXFOPVEFRNHR", "ATTACKATDAWN"),
+    ];
+    for (variant, key, plaintext, ciphertext) in vectors {
+        let cipher = with_variant(variant);
+        let key = String::from(key);
+        assert_eq!(cipher.encrypt(plaintext, &key).unwrap(), ciphertext, "{}", variant.name());
+        assert_eq!(cipher.decrypt(ciphertext, &key).unwrap(), plaintext, "{}", variant.name());
+    }
+}
+
+#[test]
+fn porta_table() {
+    let cipher = with_variant(Variant::Porta);
+    let alphabet = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+    // Each pair of key letters shares a row of the table.
+    let rows = [
+        ("A", "NOPQRSTUVWXYZABCDEFGHIJKLM"),
+        ("B", "NOPQRSTUVWXYZABCDEFGHIJKLM"),
+        ("C", "OPQRSTUVWXYZNMABCDEFGHIJKL"),
+        ("Z", "ZNOPQRSTUVWXYBCDEFGHIJKLMA"),
+    ];
+    for (key, row) in rows {
+        assert_eq!(cipher.encrypt(alphabet, &String::from(key)).unwrap(), row);
+    }
+    // The alphabet has to split into two halves.
+    let odd = Vigenere {
+        alphabet: Alphabet::new("ABCDEFGHIJKLMNOPQRSTUVWXY").unwrap(),
+        ..cipher
+    };
+    assert!(odd.encrypt("ABC", &String::from("KEY")).is_err());
+}
+
+#[test]
+fn running_key_is_a_long_vigenere_key() {
+    let book = "Whan that Aprille with his shoures soote, the droghte of March hath perced to the roote";
+    let plaintext = "Meet me at the usual place at ten";
+    let running = with_variant(Variant::RunningKey).encrypt(plaintext, &String::from(book)).unwrap();
+    let letters = book.chars().filter(char::is_ascii_alphabetic).collect::<String>();
+    assert_eq!(running, Vigenere::default().encrypt(plaintext, &letters).unwrap());
+}
+
+#[test]
+fn variants_round_trip() {
+    for variant in VARIANTS {
+        let cipher = with_variant(variant);
+        let key = match variant {
+            Variant::Gronsfeld => String::from("2718281828"),
+            // The key moves on after spaces and punctuation too.
+            Variant::RunningKey => PARAGRAPH.repeat(2),
+            _ => String::from("Dickens"),
+        };
+        let ciphertext = cipher.encrypt(PARAGRAPH, &key).unwrap();
+        assert_ne!(ciphertext, PARAGRAPH, "{}", variant.name());
+        assert_eq!(cipher.decrypt(&ciphertext, &key).unwrap(), PARAGRAPH, "{}", variant.name());
+    }
+}
+
+#[test]
+fn crib_drag() {
+    let cipher = Vigenere::default();
+    let fragments = vigenere::crib_drag("LXFOPVEFRNHR", "ATTACKAT", &cipher).unwrap();
+    assert_eq!(fragments.len(), 5);
+    let first = &fragments[0];
+    assert_eq!((first.offset, first.fragment.as_str()), (0, "lemonlem"));
+    assert_eq!(first.period, Some(5));
+    assert_eq!(first.key.as_deref(), Some("lemon"));
+
+    assert!(vigenere::crib_drag("LXFOPVEFRNHR", "123", &cipher).is_err());
+}
+
+#[test]
+fn crib_drag_paragraph() {
+    let cipher = Vigenere {
+        options: TextOptions {
+            key_on_letters: true,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let ciphertext = cipher.encrypt(PARAGRAPH, &String::from("whale")).unwrap();
+    let fragments = vigenere::crib_drag(&ciphertext, "it was the worst", &cipher).unwrap();
+    // The crib starts after "It was the best of times", which has 19 letters.
+    let found = fragments.iter().filter(|fragment| fragment.period.is_some()).collect::<Vec<_>>();
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].offset, 19);
+    // The key is lined up with the start of the ciphertext.
+    assert_eq!(found[0].key.as_deref(), Some("whale"));
+    assert!(found[0].english);
+}
@@ -0,0 +1,81 @@
+use ciphertools::wav::{Wav, WavError};
+
+/// A WAV file with the given format chunk fields and data.
+fn wav_bytes(encoding: u16, channels: u16, sample_rate: u32, bits: u16, data: &[u8]) -> Vec<u8> {
+    let block = channels * bits / 8;
+    let mut bytes = Vec::new();
+    bytes.extend_from_slice(b"RIFF");
+    bytes.extend_from_slice(&(36 + data.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(b"WAVEfmt ");
+    bytes.extend_from_slice(&16u32.to_le_bytes());
+    bytes.extend_from_slice(&encoding.to_le_bytes());
+    bytes.extend_from_slice(&channels.to_le_bytes());
+    bytes.extend_from_slice(&sample_rate.to_le_bytes());
+    bytes.extend_from_slice(&(sample_rate * block as u32).to_le_bytes());
+    bytes.extend_from_slice(&block.to_le_bytes());
+    bytes.extend_from_slice(&bits.to_le_bytes());
+    bytes.extend_from_slice(b"data");
+    bytes.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(data);
+    bytes
+}
+
+#[test]
+fn round_trip() {
+    let wav = Wav {
+        sample_rate: 8000,
+        samples: vec![0.0, 0.5, -0.5, 1.0, -1.0],
+    };
+    let bytes = wav.to_bytes();
+    assert_eq!(bytes.len(), 44 + 10);
+    assert_eq!(&bytes[..4], b"RIFF");
+    assert_eq!(&bytes[36..40], b"data");
+    let read = Wav::from_bytes(&bytes).unwrap();
+    assert_eq!(read.sample_rate, 8000);
+    assert_eq!(read.samples.len(), 5);
+    for (a, b) in read.samples.iter().zip(&wav.samples) {
+        assert!((a - b).abs() < 0.001);
+    }
+}
+
+#[test]
+fn formats() {
+    // 8-bit samples are unsigned, centred on 128.
+    let wav = Wav::from_bytes(&wav_bytes(1, 1, 4000, 8, &[128, 255, 0])).unwrap();
+    assert_eq!(wav.sample_rate, 4000);
+    assert_eq!(wav.samples[0], 0.0);
+    assert!(wav.samples[1] > 0.99 && wav.samples[2] == -1.0);
+
+    // Only the first channel is kept.
+    let data = [0x00, 0x40, 0xff, 0x7f, 0x00, 0xc0, 0x00, 0x80];
+    let wav = Wav::from_bytes(&wav_bytes(1, 2, 8000, 16, &data)).unwrap();
+    assert_eq!(wav.samples, [0.5, -0.5]);
+
+    let data = [0.25f32.to_le_bytes(), (-0.75f32).to_le_bytes()].concat();
+    let wav = Wav::from_bytes(&wav_bytes(3, 1, 8000, 32, &data)).unwrap();
+    assert_eq!(wav.samples, [0.25, -0.75]);
+}
+
+#[test]
+fn bad_headers() {
+    assert_eq!(Wav::from_bytes(b""), Err(WavError::NotWav));
+    assert_eq!(Wav::from_bytes(b"RIFF\0\0\0\0AVI LIST"), Err(WavError::NotWav));
+    let mut bytes = wav_bytes(1, 1, 8000, 16, &[0, 0]);
+    bytes[36..40].copy_from_slice(b"junk");
+    assert_eq!(Wav::from_bytes(&bytes), Err(WavError::MissingChunk));
+    assert_eq!(Wav::from_bytes(&bytes[..36]), Err(WavError::MissingChunk));
+
+    assert_eq!(Wav::from_bytes(&wav_bytes(1, 0, 8000, 16, &[0, 0])), Err(WavError::BadFormat));
+    assert_eq!(Wav::from_bytes(&wav_bytes(1, 1, 0, 16, &[0, 0])), Err(WavError::BadFormat));
+    assert_eq!(Wav::from_bytes(&wav_bytes(1, 1, 8000, 4, &[0, 0])), Err(WavError::BadFormat));
+
+    // A-law, and 64-bit floats, aren't PCM this can read.
+    assert_eq!(
+        Wav::from_bytes(&wav_bytes(6, 1, 8000, 8, &[0])),
+        Err(WavError::Unsupported { encoding: 6, bits: 8 })
+    );
+    assert_eq!(
+        Wav::from_bytes(&wav_bytes(3, 1, 8000, 64, &[0; 8])),
+        Err(WavError::Unsupported { encoding: 3, bits: 64 })
+    );
+}